@@ -2,16 +2,37 @@ use arrow_protocol::{
     packets::{error::PacketError, Packet, PacketKind, State},
     serde::varint::{varint_len, write_varint},
 };
+use std::io::{Read, Write};
+
+use aes::Aes128;
 use bytes::{Buf, BytesMut};
+use cfb8::{
+    cipher::{AsyncStreamCipher, NewCipher},
+    Cfb8,
+};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use log::debug;
 use tokio_util::codec::{Decoder, Encoder};
 
 use crate::error::{DecoderError, EncoderError};
 
+/// The largest uncompressed size of a packet a peer may announce, the same limit vanilla uses.
+/// Larger packets are rejected before anything is allocated or decompressed.
+pub const MAX_DATA_LENGTH: i32 = 1 << 21;
+/// The largest length of a frame, the most a VarInt of 3 bytes can hold like vanilla allows.
+/// Longer frames are rejected before any buffer is reserved for them.
+pub const MAX_PACKET_LENGTH: i32 = (1 << 21) - 1;
+
 /// The minecraft protocol codec.
 pub struct McCodec {
     protocol_version: i32,
     state: State,
     serverbound: bool,
+    compression_threshold: Option<i32>,
+    encryptor: Option<Cfb8<Aes128>>,
+    decryptor: Option<Cfb8<Aes128>>,
+    /// The number of bytes at the start of the decoder buffer that are already decrypted.
+    decrypted: usize,
 }
 
 impl McCodec {
@@ -21,6 +42,10 @@ impl McCodec {
             protocol_version: 0,
             state: State::Handshake,
             serverbound,
+            compression_threshold: None,
+            encryptor: None,
+            decryptor: None,
+            decrypted: 0,
         }
     }
 
@@ -29,29 +54,177 @@ impl McCodec {
         self.protocol_version
     }
 
-    fn read_varint(&self, src: &mut BytesMut) -> Result<Option<i32>, DecoderError> {
-        let mut count = 0;
+    /// returns the current protocol state of this McCodec
+    pub fn get_state(&self) -> State {
+        self.state.clone()
+    }
+
+    /// forces the protocol state of this McCodec to `state`
+    pub fn set_state(&mut self, state: State) {
+        self.state = state;
+    }
+
+    /// returns the compression threshold of this McCodec or `None` if compression is disabled
+    pub fn get_compression_threshold(&self) -> Option<i32> {
+        self.compression_threshold
+    }
+
+    /// sets the compression threshold of this McCodec, `None` disables compression
+    ///
+    /// The codec enables compression on its own once a
+    /// [SetCompression](PacketKind::SetCompression) packet went through it.
+    pub fn set_compression_threshold(&mut self, threshold: Option<i32>) {
+        self.compression_threshold = threshold;
+    }
+
+    /// returns true if the packets going through this McCodec are encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.encryptor.is_some() || self.decryptor.is_some()
+    }
+
+    /// enables AES/CFB8 encryption in both directions for all following packets
+    ///
+    /// The shared secret is used as both the key and the initial vector, so it has to be 16 bytes
+    /// long. Bytes that were already received but not yet decoded are decrypted as well.
+    pub fn enable_encryption(&mut self, shared_secret: &[u8]) -> Result<(), String> {
+        let cipher = || {
+            Cfb8::<Aes128>::new_from_slices(shared_secret, shared_secret)
+                .map_err(|_| format!("Invalid shared secret length {}.", shared_secret.len()))
+        };
+
+        self.encryptor = Some(cipher()?);
+        self.decryptor = Some(cipher()?);
+        self.decrypted = 0;
+
+        Ok(())
+    }
+
+    /// Splits this McCodec into one that decodes and one that encodes packets, e.g. to read and
+    /// write on separate tasks. Both keep the protocol version, state and compression threshold,
+    /// so this should only be done once the state no longer changes in [Play](State::Play).
+    pub fn split(self) -> (McCodec, McCodec) {
+        let decoder = McCodec {
+            protocol_version: self.protocol_version,
+            state: self.state.clone(),
+            serverbound: self.serverbound,
+            compression_threshold: self.compression_threshold,
+            encryptor: None,
+            decryptor: self.decryptor,
+            decrypted: self.decrypted,
+        };
+        let encoder = McCodec {
+            protocol_version: self.protocol_version,
+            state: self.state,
+            serverbound: self.serverbound,
+            compression_threshold: self.compression_threshold,
+            encryptor: self.encryptor,
+            decryptor: None,
+            decrypted: 0,
+        };
+
+        (decoder, encoder)
+    }
+
+    /// Advances the protocol state after `packet` went through the codec.
+    ///
+    /// A [Handshake](PacketKind::Handshake) moves to the requested state and a
+    /// [LoginSuccess](PacketKind::LoginSuccess) moves from [Login](State::Login) to
+    /// [Play](State::Play). A [SetCompression](PacketKind::SetCompression) packet sets the
+    /// compression threshold for all following packets. This is the same for both directions.
+    fn update_state(&mut self, packet: &PacketKind) -> Result<(), String> {
+        match packet {
+            PacketKind::Handshake {
+                protocol_version,
+                next_state,
+                ..
+            } => {
+                self.protocol_version = *protocol_version;
+                self.state = match *next_state {
+                    1 => State::Status,
+                    2 => State::Login,
+                    i => return Err(format!("Invalid next state: {}", i)),
+                }
+            }
+            PacketKind::LoginSuccess(..) => self.state = State::Play,
+            PacketKind::SetCompression(threshold) => {
+                self.compression_threshold = if *threshold >= 0 {
+                    Some(*threshold)
+                } else {
+                    None
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Reads a VarInt from the start of `src` without consuming it. Returns the value and the
+    /// number of bytes it takes up or `None` if `src` ends before the VarInt does.
+    fn read_varint(&self, src: &[u8]) -> Result<Option<(i32, usize)>, DecoderError> {
         let mut result = 0;
-        let mut read: u8;
 
-        while {
-            if src.len() == 0 {
-                return Ok(None);
+        for (count, read) in src.iter().enumerate() {
+            if count >= 5 {
+                return Err(DecoderError("VarInt too long.".to_string()));
             }
-            read = src.get_u8();
 
             let value = (read & 0b01111111) as u32;
             result |= value << (7 * count);
 
-            count += 1;
-            if count > 5 {
-                return Err(DecoderError("VarInt too long.".to_string()));
+            if (read & 0b10000000) == 0 {
+                return Ok(Some((result as i32, count + 1)));
             }
+        }
 
-            (read & 0b10000000) > 0
-        } {}
+        if src.len() >= 5 {
+            return Err(DecoderError("VarInt too long.".to_string()));
+        }
 
-        Ok(Some(result as i32))
+        Ok(None)
+    }
+
+    /// Reads the uncompressed `id | data` part of the compressed frame `bytes`.
+    fn decompress(&self, mut bytes: BytesMut, threshold: i32) -> Result<BytesMut, DecoderError> {
+        let (data_len, size) = self
+            .read_varint(&bytes)?
+            .ok_or_else(|| DecoderError("Missing data length.".to_string()))?;
+        bytes.advance(size);
+
+        if data_len == 0 {
+            return Ok(bytes);
+        }
+
+        if data_len < threshold {
+            return Err(DecoderError(format!(
+                "Badly compressed packet: size {} is below threshold {}.",
+                data_len, threshold
+            )));
+        }
+
+        if data_len > MAX_DATA_LENGTH {
+            return Err(DecoderError(format!(
+                "Badly compressed packet: size {} is above the maximum {}.",
+                data_len, MAX_DATA_LENGTH
+            )));
+        }
+
+        // Reads one byte more than announced, so longer data is detected without inflating all
+        // of it.
+        let mut data = Vec::with_capacity(data_len as usize);
+        ZlibDecoder::new(&bytes[..])
+            .take(data_len as u64 + 1)
+            .read_to_end(&mut data)?;
+
+        if data.len() != data_len as usize {
+            return Err(DecoderError(format!(
+                "Badly compressed packet: expected {} bytes, got {}.",
+                data_len,
+                data.len()
+            )));
+        }
+
+        Ok(BytesMut::from(&data[..]))
     }
 }
 
@@ -61,60 +234,73 @@ impl Decoder for McCodec {
     type Error = DecoderError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        if src.len() < 2 {
-            return Ok(None);
+        if let Some(decryptor) = &mut self.decryptor {
+            decryptor.decrypt(&mut src[self.decrypted..]);
+            self.decrypted = src.len();
         }
 
-        let len = if let Some(val) = self.read_varint(src)? {
-            val
-        } else {
-            return Ok(None);
-        };
+        loop {
+            let (len, size) = if let Some(val) = self.read_varint(src)? {
+                val
+            } else {
+                return Ok(None);
+            };
 
-        if len as usize > src.len() {
-            return Ok(None);
-        }
+            if len < 0 {
+                return Err(DecoderError(format!("Invalid packet length {}.", len)));
+            }
 
-        let mut bytes = src.split_to(len as usize);
-        let id = if let Some(val) = self.read_varint(&mut bytes)? {
-            val
-        } else {
-            return Ok(None);
-        };
+            if len > MAX_PACKET_LENGTH {
+                return Err(DecoderError(format!(
+                    "Packet length {} is above the maximum {}.",
+                    len, MAX_PACKET_LENGTH
+                )));
+            }
 
-        let packet = match PacketKind::from_bytes(
-            self.state.clone(),
-            self.serverbound,
-            self.protocol_version,
-            id,
-            bytes.to_vec(),
-        ) {
-            Ok(p) => p,
-            Err(PacketError::SerdeError(s)) => {
-                if s == "Unexpected eof".to_string() {
-                    return Ok(None);
-                }
-                return Err(PacketError::SerdeError(s).into());
+            if size + len as usize > src.len() {
+                src.reserve(size + len as usize - src.len());
+                return Ok(None);
             }
-            Err(e) => return Err(e.into()),
-        };
 
-        if let PacketKind::Handshake {
-            protocol_version,
-            host: _,
-            port: _,
-            next_state,
-        } = &packet
-        {
-            self.protocol_version = *protocol_version;
-            self.state = match *next_state {
-                1 => State::Status,
-                2 => State::Login,
-                i => return Err(DecoderError(format!("Invalid next state: {}", i))),
+            src.advance(size);
+
+            let mut bytes = src.split_to(len as usize);
+
+            self.decrypted = self.decrypted.saturating_sub(size + len as usize);
+
+            if let Some(threshold) = self.compression_threshold {
+                bytes = self.decompress(bytes, threshold)?;
             }
-        }
 
-        Ok(Some(packet))
+            let id = if let Some((val, size)) = self.read_varint(&bytes)? {
+                bytes.advance(size);
+                val
+            } else {
+                return Err(DecoderError("Missing packet id.".to_string()));
+            };
+
+            let packet = match PacketKind::from_bytes(
+                self.state.clone(),
+                self.serverbound,
+                self.protocol_version,
+                id,
+                bytes.to_vec(),
+            ) {
+                Ok(p) => p,
+                Err(PacketError::InvalidPacketId(id, State::Play)) => {
+                    debug!(
+                        "Skipping unknown play packet {:#04x} for protocol version {}.",
+                        id, self.protocol_version
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            self.update_state(&packet).map_err(DecoderError)?;
+
+            return Ok(Some(packet));
+        }
     }
 }
 
@@ -122,24 +308,278 @@ impl Encoder<PacketKind> for McCodec {
     type Error = EncoderError;
 
     fn encode(&mut self, packet: PacketKind, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        // A Set Compression packet itself is never compressed.
+        let compression_threshold = self.compression_threshold;
+
+        self.update_state(&packet).map_err(EncoderError)?;
+
         let packet: Box<dyn Packet> = match packet.into_packet(self.protocol_version) {
             Ok(b) => b,
             Err(e) => return Err(EncoderError::from(e)),
         };
         let mut bytes = packet.data_bytes()?;
         let id = packet.self_id(self.protocol_version);
-        let len = bytes.len() + varint_len(id);
 
-        let mut buffer = Vec::with_capacity(len);
+        let mut data = Vec::with_capacity(bytes.len() + varint_len(id));
 
-        write_varint(len as i32, &mut buffer)
+        write_varint(id, &mut data)
             .map_err(|e| EncoderError(format!("Failed encoding varint: {}", e)))?;
-        write_varint(id, &mut buffer)
+        data.append(&mut bytes);
+
+        if let Some(threshold) = compression_threshold {
+            let mut compressed = vec![];
+
+            if data.len() >= threshold as usize {
+                write_varint(data.len() as i32, &mut compressed)
+                    .map_err(|e| EncoderError(format!("Failed encoding varint: {}", e)))?;
+
+                let mut encoder = ZlibEncoder::new(compressed, Compression::default());
+                encoder.write_all(&data)?;
+                compressed = encoder.finish()?;
+            } else {
+                write_varint(0, &mut compressed)
+                    .map_err(|e| EncoderError(format!("Failed encoding varint: {}", e)))?;
+                compressed.append(&mut data);
+            }
+
+            data = compressed;
+        }
+
+        let mut buffer = Vec::with_capacity(data.len() + varint_len(data.len() as i32));
+
+        write_varint(data.len() as i32, &mut buffer)
             .map_err(|e| EncoderError(format!("Failed encoding varint: {}", e)))?;
-        buffer.append(&mut bytes);
+        buffer.append(&mut data);
+
+        let start = dst.len();
 
         dst.extend_from_slice(&buffer);
 
+        if let Some(encryptor) = &mut self.encryptor {
+            encryptor.encrypt(&mut dst[start..]);
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an encoder and a decoder for serverbound packets that are in the play state of
+    /// `protocol_version`.
+    fn play_codecs(protocol_version: i32) -> (McCodec, McCodec) {
+        let mut encoder = McCodec::new(true);
+        let mut decoder = McCodec::new(true);
+        let mut bytes = BytesMut::new();
+        let handshake = PacketKind::Handshake {
+            protocol_version,
+            host: "localhost".to_string(),
+            port: 25565,
+            next_state: 2,
+        };
+
+        encoder.encode(handshake.clone(), &mut bytes).unwrap();
+        assert_eq!(decoder.decode(&mut bytes).unwrap(), Some(handshake));
+        assert_eq!(decoder.get_protocol_version(), protocol_version);
+
+        encoder.set_state(State::Play);
+        decoder.set_state(State::Play);
+
+        (encoder, decoder)
+    }
+
+    /// Returns an uncompressed frame of a packet with `id` and `data`.
+    fn frame(id: i32, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![];
+
+        write_varint(id, &mut packet).unwrap();
+        packet.extend_from_slice(data);
+
+        let mut frame = vec![];
+
+        write_varint(packet.len() as i32, &mut frame).unwrap();
+        frame.append(&mut packet);
+        frame
+    }
+
+    /// Returns a compressed frame announcing `data_len` uncompressed bytes and containing `data`
+    /// compressed with zlib.
+    fn compressed_frame(data_len: i32, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![];
+
+        write_varint(data_len, &mut packet).unwrap();
+
+        let mut encoder = ZlibEncoder::new(packet, Compression::default());
+        encoder.write_all(data).unwrap();
+        let mut packet = encoder.finish().unwrap();
+
+        let mut frame = vec![];
+
+        write_varint(packet.len() as i32, &mut frame).unwrap();
+        frame.append(&mut packet);
+        frame
+    }
+
+    /// Returns a serverbound Chat Message packet of 1.16.5 with the message `message`,
+    /// including the id.
+    fn chat_message(message: &str) -> Vec<u8> {
+        let mut data = vec![0x03];
+
+        write_varint(message.len() as i32, &mut data).unwrap();
+        data.extend_from_slice(message.as_bytes());
+        data
+    }
+
+    #[test]
+    fn compression_round_trip() {
+        let (mut encoder, mut decoder) = play_codecs(754);
+        let short = PacketKind::ServerboundChatMessage("hi".to_string());
+        let long = PacketKind::ServerboundChatMessage("a".repeat(250));
+        let mut bytes = BytesMut::new();
+
+        encoder.set_compression_threshold(Some(64));
+        decoder.set_compression_threshold(Some(64));
+        encoder.encode(short.clone(), &mut bytes).unwrap();
+
+        // Below the threshold the packet is sent with a data length of 0.
+        assert_eq!(&bytes[..3], &[5, 0, 0x03]);
+
+        encoder.encode(long.clone(), &mut bytes).unwrap();
+
+        assert_eq!(decoder.decode(&mut bytes).unwrap(), Some(short));
+        assert_eq!(decoder.decode(&mut bytes).unwrap(), Some(long));
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn rejects_compressed_packets_below_threshold() {
+        let (_, mut decoder) = play_codecs(754);
+        let data = chat_message("hi");
+        let mut bytes = BytesMut::from(&compressed_frame(data.len() as i32, &data)[..]);
+
+        decoder.set_compression_threshold(Some(64));
+
+        let error = decoder.decode(&mut bytes).unwrap_err();
+
+        assert!(error.0.contains("below threshold"), "{}", error.0);
+    }
+
+    #[test]
+    fn rejects_oversized_packets() {
+        let (_, mut decoder) = play_codecs(754);
+        let data = chat_message(&"a".repeat(MAX_DATA_LENGTH as usize));
+        let mut bytes = BytesMut::from(&compressed_frame(data.len() as i32, &data)[..]);
+
+        decoder.set_compression_threshold(Some(64));
+
+        let error = decoder.decode(&mut bytes).unwrap_err();
+
+        assert!(error.0.contains("above the maximum"), "{}", error.0);
+    }
+
+    #[test]
+    fn rejects_oversized_frames() {
+        let (_, mut decoder) = play_codecs(754);
+        let mut bytes = BytesMut::from(&[0xFF, 0xFF, 0xFF, 0xFF, 0x07][..]);
+
+        let error = decoder.decode(&mut bytes).unwrap_err();
+
+        assert!(error.0.contains("above the maximum"), "{}", error.0);
+        assert!(bytes.capacity() < 1024);
+
+        // The longest allowed frame is only waited for.
+        let mut bytes = BytesMut::from(&[0xFF, 0xFF, 0x7F][..]);
+
+        assert!(decoder.decode(&mut bytes).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_packets_longer_than_announced() {
+        let (_, mut decoder) = play_codecs(754);
+        let data = chat_message(&"a".repeat(1000));
+        let mut bytes = BytesMut::from(&compressed_frame(100, &data)[..]);
+
+        decoder.set_compression_threshold(Some(64));
+
+        let error = decoder.decode(&mut bytes).unwrap_err();
+
+        assert!(error.0.contains("expected 100 bytes"), "{}", error.0);
+    }
+
+    #[test]
+    fn encryption_round_trip() {
+        let (mut encoder, mut decoder) = play_codecs(754);
+        let packets = vec![
+            PacketKind::ServerboundChatMessage("hello".to_string()),
+            PacketKind::ServerboundChatMessage("b".repeat(300)),
+            PacketKind::ServerboundKeepAlive(7),
+        ];
+        let shared_secret = [0x42; 16];
+        let mut bytes = BytesMut::new();
+
+        encoder.enable_encryption(&shared_secret).unwrap();
+        decoder.enable_encryption(&shared_secret).unwrap();
+        encoder.set_compression_threshold(Some(256));
+        decoder.set_compression_threshold(Some(256));
+
+        for packet in packets.iter() {
+            encoder.encode(packet.clone(), &mut bytes).unwrap();
+        }
+
+        assert!(!bytes.windows(5).any(|window| window == b"hello"));
+
+        // The bytes arrive in pieces, so some are decrypted before the packet is complete.
+        let mut received = BytesMut::new();
+        let mut decoded = vec![];
+
+        for piece in bytes.chunks(7) {
+            received.extend_from_slice(piece);
+
+            while let Some(packet) = decoder.decode(&mut received).unwrap() {
+                decoded.push(packet);
+            }
+        }
+
+        assert_eq!(decoded, packets);
+        assert!(received.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_shared_secret() {
+        let mut codec = McCodec::new(true);
+
+        assert!(codec.enable_encryption(&[0; 15]).is_err());
+        assert!(!codec.is_encrypted());
+    }
+
+    #[test]
+    fn skips_unknown_play_packets() {
+        let (mut encoder, mut decoder) = play_codecs(754);
+        let message = PacketKind::ServerboundChatMessage("hi".to_string());
+        let mut bytes = BytesMut::from(&frame(0x7F, &[1, 2, 3])[..]);
+
+        encoder.encode(message.clone(), &mut bytes).unwrap();
+
+        assert_eq!(decoder.decode(&mut bytes).unwrap(), Some(message));
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn waits_for_packets_after_unknown_ones() {
+        let (_, mut decoder) = play_codecs(47);
+        let mut bytes = BytesMut::from(&frame(0x7F, &[])[..]);
+
+        assert_eq!(decoder.decode(&mut bytes).unwrap(), None);
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_packets_outside_play() {
+        let mut decoder = McCodec::new(true);
+        let mut bytes = BytesMut::from(&frame(0x7F, &[])[..]);
+
+        assert!(decoder.decode(&mut bytes).is_err());
+    }
+}
@@ -0,0 +1,358 @@
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use rsa::{pkcs8::ToPublicKey, PaddingScheme, RsaPrivateKey, RsaPublicKey};
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use uuid::Uuid;
+
+use crate::error::{NetError, Result};
+
+const SESSION_SERVER_URL: &str = "https://sessionserver.mojang.com/session/minecraft/hasJoined";
+
+/// A property of a [`Profile`], e.g. the skin textures.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProfileProperty {
+    /// The name of the property.
+    pub name: String,
+    /// The base64 encoded value of the property.
+    pub value: String,
+    /// The base64 encoded signature of the value.
+    pub signature: Option<String>,
+}
+
+/// The profile of a player that was verified by a [`SessionVerifier`].
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// The uuid of the player.
+    pub id: Uuid,
+    /// The name of the player.
+    pub name: String,
+    /// The properties of the player.
+    #[serde(default)]
+    pub properties: Vec<ProfileProperty>,
+}
+
+/// Verifies that a player joined this server with their account.
+#[async_trait]
+pub trait SessionVerifier: Send + Sync {
+    /// Returns the profile of the player `name` if they joined the server identified by
+    /// `server_hash` and `None` if they did not.
+    async fn has_joined(&self, name: &str, server_hash: &str) -> Result<Option<Profile>>;
+}
+
+/// A [`SessionVerifier`] asking the Mojang session server.
+#[derive(Default)]
+pub struct MojangSessionVerifier {
+    client: reqwest::Client,
+}
+
+impl MojangSessionVerifier {
+    /// Creates a new [`MojangSessionVerifier`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionVerifier for MojangSessionVerifier {
+    async fn has_joined(&self, name: &str, server_hash: &str) -> Result<Option<Profile>> {
+        let response = self
+            .client
+            .get(SESSION_SERVER_URL)
+            .query(&[("username", name), ("serverId", server_hash)])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| NetError::AuthenticationError(format!("{}", e)))?;
+
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+
+        response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| NetError::AuthenticationError(format!("{}", e)))
+    }
+}
+
+/// Holds the key pair of the server and verifies players in online mode.
+pub struct Authenticator {
+    private_key: RsaPrivateKey,
+    public_key: Vec<u8>,
+    verifier: Box<dyn SessionVerifier>,
+}
+
+impl Authenticator {
+    /// Creates a new [`Authenticator`] with a freshly generated 1024 bit RSA key pair.
+    pub fn new(verifier: Box<dyn SessionVerifier>) -> Result<Self> {
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 1024)
+            .map_err(|e| NetError::AuthenticationError(format!("{}", e)))?;
+        let public_key = RsaPublicKey::from(&private_key)
+            .to_public_key_der()
+            .map_err(|e| NetError::AuthenticationError(format!("{}", e)))?
+            .as_ref()
+            .to_vec();
+
+        Ok(Self {
+            private_key,
+            public_key,
+            verifier,
+        })
+    }
+
+    /// Returns the DER encoded public key of the server.
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    /// Decrypts `data` that was encrypted by the client using the public key.
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.private_key
+            .decrypt(PaddingScheme::new_pkcs1v15_encrypt(), data)
+            .map_err(|e| NetError::AuthenticationError(format!("{}", e)))
+    }
+
+    /// Returns the profile of the player `name` if they joined using `shared_secret`.
+    pub async fn verify(&self, name: &str, shared_secret: &[u8]) -> Result<Option<Profile>> {
+        let hash = server_hash("", shared_secret, &self.public_key);
+
+        self.verifier.has_joined(name, &hash).await
+    }
+}
+
+/// Returns the uuid a vanilla server in offline mode gives the player `name`. This is the MD5 hash
+/// of `OfflinePlayer:<name>` turned into a version 3 uuid.
+pub fn offline_uuid(name: &str) -> Uuid {
+    let mut bytes = md5::compute(format!("OfflinePlayer:{}", name)).0;
+
+    bytes[6] = (bytes[6] & 0x0f) | 0x30;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    Uuid::from_bytes(bytes)
+}
+
+/// Returns the offline mode uuid earlier versions of Arrow gave the player `name`.
+pub fn legacy_offline_uuid(name: &str) -> Uuid {
+    Uuid::new_v3(&Uuid::NAMESPACE_OID, name.as_bytes())
+}
+
+/// Computes the server hash sent to the session server. This is the SHA-1 digest of the server
+/// id, the shared secret and the public key, formatted as a signed hexadecimal number.
+pub fn server_hash(server_id: &str, shared_secret: &[u8], public_key: &[u8]) -> String {
+    let mut hash: [u8; 20] = Sha1::new()
+        .chain(server_id)
+        .chain(shared_secret)
+        .chain(public_key)
+        .finalize()
+        .into();
+    let negative = hash[0] & 0x80 != 0;
+
+    if negative {
+        let mut carry = true;
+
+        for b in hash.iter_mut().rev() {
+            *b = !*b;
+
+            if carry {
+                let (value, overflow) = b.overflowing_add(1);
+                *b = value;
+                carry = overflow;
+            }
+        }
+    }
+
+    let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+    let hex = hex.trim_start_matches('0');
+
+    if negative {
+        format!("-{}", hex)
+    } else {
+        hex.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use arrow_codec::codec::McCodec;
+    use arrow_protocol::packets::PacketKind;
+    use futures::{SinkExt, StreamExt};
+    use parking_lot::Mutex;
+    use rsa::{pkcs8::FromPublicKey, PublicKey};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_util::codec::Framed;
+
+    use super::*;
+    use crate::{client::Client, settings::Settings};
+
+    /// A [`SessionVerifier`] accepting a fixed set of profiles by name, so the online mode login
+    /// is tested without the session server. Records the server hashes it is asked about.
+    struct MockSessionVerifier {
+        profiles: HashMap<String, Profile>,
+        hashes: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl SessionVerifier for MockSessionVerifier {
+        async fn has_joined(&self, name: &str, server_hash: &str) -> Result<Option<Profile>> {
+            self.hashes.lock().push(server_hash.to_string());
+
+            Ok(self.profiles.get(name).cloned())
+        }
+    }
+
+    /// Starts a server in online mode accepting a single client, which only knows `profile`.
+    /// Returns the address and the server hashes the session server is asked about.
+    async fn online_server(profile: Profile) -> (std::net::SocketAddr, Arc<Mutex<Vec<String>>>) {
+        let hashes = Arc::new(Mutex::new(vec![]));
+        let verifier = MockSessionVerifier {
+            profiles: vec![(profile.name.clone(), profile)].into_iter().collect(),
+            hashes: hashes.clone(),
+        };
+        let settings = Arc::new(Settings {
+            authenticator: Some(Arc::new(Authenticator::new(Box::new(verifier)).unwrap())),
+            compression_threshold: 64,
+            ..Settings::default()
+        });
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+
+            Client::new(stream, settings).connect().await;
+        });
+
+        (address, hashes)
+    }
+
+    /// Logs in as `name` and answers the Encryption Request. Returns the connection, which is
+    /// encrypted from then on, and the server hash the client would send to the session server.
+    async fn log_in(
+        address: std::net::SocketAddr,
+        name: &str,
+    ) -> (Framed<TcpStream, McCodec>, String) {
+        let stream = TcpStream::connect(address).await.unwrap();
+        let mut framed = Framed::new(stream, McCodec::new(false));
+
+        framed
+            .send(PacketKind::Handshake {
+                protocol_version: 754,
+                host: "localhost".to_string(),
+                port: address.port(),
+                next_state: 2,
+            })
+            .await
+            .unwrap();
+        framed
+            .send(PacketKind::LoginStart(name.to_string()))
+            .await
+            .unwrap();
+
+        let (server_id, public_key, verify_token) = match framed.next().await.unwrap().unwrap() {
+            PacketKind::EncryptionRequest {
+                server_id,
+                public_key,
+                verify_token,
+            } => (server_id, public_key, verify_token),
+            p => panic!("Unexpected packet {}", p),
+        };
+
+        let key = RsaPublicKey::from_public_key_der(&public_key).unwrap();
+        let encrypt = |data: &[u8]| {
+            key.encrypt(
+                &mut rand::thread_rng(),
+                PaddingScheme::new_pkcs1v15_encrypt(),
+                data,
+            )
+            .unwrap()
+        };
+        let shared_secret = rand::random::<[u8; 16]>();
+
+        framed
+            .send(PacketKind::EncryptionResponse {
+                shared_secret: encrypt(&shared_secret),
+                verify_token: encrypt(&verify_token),
+            })
+            .await
+            .unwrap();
+        framed
+            .codec_mut()
+            .enable_encryption(&shared_secret)
+            .unwrap();
+
+        let hash = server_hash(&server_id, &shared_secret, &public_key);
+
+        (framed, hash)
+    }
+
+    #[test]
+    fn server_hash_vectors() {
+        // The examples of https://wiki.vg/Protocol_Encryption, which hash only the name.
+        assert_eq!(
+            server_hash("Notch", &[], &[]),
+            "4ed1f46bbe04bc756bcb17c0c7ce3e4632f06a48"
+        );
+        assert_eq!(
+            server_hash("jeb_", &[], &[]),
+            "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1"
+        );
+        assert_eq!(
+            server_hash("simon", &[], &[]),
+            "88e16a1019277b15d58faf0541e11910eb756f6"
+        );
+    }
+
+    #[test]
+    fn offline_uuid_matches_vanilla() {
+        assert_eq!(
+            offline_uuid("Notch"),
+            Uuid::parse_str("b50ad385-829d-3141-a216-7e7d7539ba7f").unwrap()
+        );
+        assert_eq!(offline_uuid("Notch").get_version_num(), 3);
+        assert_ne!(offline_uuid("Notch"), offline_uuid("notch"));
+    }
+
+    #[tokio::test]
+    async fn encrypted_login() {
+        let profile = Profile {
+            id: Uuid::parse_str("853c80ef-3c37-49fd-aa49-938b674adae6").unwrap(),
+            name: "jeb_".to_string(),
+            properties: vec![],
+        };
+        let (address, hashes) = online_server(profile.clone()).await;
+        let (mut framed, hash) = log_in(address, "jeb_").await;
+
+        assert_eq!(
+            framed.next().await.unwrap().unwrap(),
+            PacketKind::SetCompression(64)
+        );
+        assert_eq!(
+            framed.next().await.unwrap().unwrap(),
+            PacketKind::LoginSuccess(profile.id, profile.name)
+        );
+        assert_eq!(*hashes.lock(), vec![hash]);
+    }
+
+    #[tokio::test]
+    async fn encrypted_login_of_unknown_player() {
+        let profile = Profile {
+            id: Uuid::parse_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap(),
+            name: "Notch".to_string(),
+            properties: vec![],
+        };
+        let (address, hashes) = online_server(profile).await;
+        let (mut framed, hash) = log_in(address, "Herobrine").await;
+        let packet = framed.next().await.unwrap().unwrap();
+
+        assert!(
+            matches!(packet, PacketKind::LoginDisconnect(_)),
+            "Unexpected packet {}",
+            packet
+        );
+        assert_eq!(*hashes.lock(), vec![hash]);
+    }
+}
@@ -1,30 +1,40 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use arrow_codec::codec::McCodec;
+use arrow_codec::{codec::McCodec, error::EncoderError};
 use arrow_protocol::{
+    chat::ChatComponent,
     packets::{
         common::status,
         types::{self, Difficulty, Gamemode},
         version_specific::{
             self,
             types::v754::{
-                BiomeEffects, BiomeProperties, BiomeRegistry, BiomeRegistryEntry, DimensionCodec,
-                DimensionRegistry, DimensionRegistryEntry, DimensionType,
+                BiomeRegistry, DimensionCodec, DimensionRegistry, DimensionRegistryEntry,
+                DimensionType,
             },
         },
-        PacketKind,
+        PacketKind, State,
     },
     serde::varint::VarInt,
 };
-use futures::{SinkExt, StreamExt, TryStreamExt};
-use log::{error, info};
-use tokio::{net::TcpStream, sync::RwLock};
-use tokio_util::codec::Framed;
-use uuid::Uuid;
+use futures::{SinkExt, StreamExt};
+use log::{debug, error, info};
+use tokio::{
+    net::TcpStream,
+    time::{self, Instant},
+};
+use tokio_util::codec::{Framed, FramedParts};
 
+use crate::auth::{self, Authenticator, Profile};
+use crate::command::{self, argument::starts_with_ignore_case, CommandSource, COMMANDS};
+use crate::connection::{self, ClientReader, ClientWriter, PacketQueue, PacketSender};
 use crate::error::NetError;
 use crate::player::Player;
+use crate::registry::RegisterError;
 use crate::server::SERVER;
+use crate::settings::Settings;
+use crate::version;
+use crate::world::{biome::Biome, seed};
 
 macro_rules! next_packet {
     ($self:ident) => {
@@ -50,16 +60,35 @@ macro_rules! send_packet {
     };
 }
 
+/// The time between two keep alives sent to a client.
+pub const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// The interval in which pending keep alives are checked.
+const KEEP_ALIVE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum number of players listed in the status response.
+const MAX_PLAYER_SAMPLE: usize = 12;
+/// The view distance sent to clients, which is the number of chunks sent around the spawn in
+/// every direction.
+const VIEW_DISTANCE: i32 = 8;
+
 /// A client that connected to the server.
 pub struct Client {
     framed: Framed<TcpStream, McCodec>,
+    settings: Arc<Settings>,
+    packet_sender: PacketSender,
+    packet_queue: PacketQueue,
 }
 
 impl Client {
-    /// Creates a new client using a [`tokio::net::TcpStream`].
-    pub fn new(stream: TcpStream) -> Self {
+    /// Creates a new client using a [`tokio::net::TcpStream`] and the [`Settings`] of the server.
+    pub fn new(stream: TcpStream, settings: Arc<Settings>) -> Self {
+        let (packet_sender, packet_queue) = connection::packet_queue();
+
         Self {
             framed: Framed::new(stream, McCodec::new(true)),
+            settings,
+            packet_sender,
+            packet_queue,
         }
     }
 
@@ -85,34 +114,99 @@ impl Client {
         self.framed.codec().get_protocol_version()
     }
 
-    pub(crate) async fn recv(&mut self) {
-        loop {
-            match self.framed.try_next().await {
-                Ok(Some(
-                    PacketKind::Handshake { .. }
-                    | PacketKind::LoginStart(_)
-                    | PacketKind::LoginSuccess(..)
-                    | PacketKind::StatusRequest
-                    | PacketKind::StatusResponse(_)
-                    | PacketKind::StatusPing(_)
-                    | PacketKind::StatusPong(_)
-                    | PacketKind::JoinGame { .. }
-                    | PacketKind::ServerDifficulty(..)
-                    | PacketKind::HeldItemChange(_),
-                )) => {
-                    error!("Received packet from other protocol state.");
-                    return;
-                }
-                Ok(Some(PacketKind::DeclareRecipes(_))) => {
-                    error!("Received client side packet.");
-                }
-                Ok(None) => return,
-                Err(e) => {
-                    error!("Failed reading next packet: {}", e.0);
-                    return;
-                }
+    /// Returns the protocol state the codec of this client is currently in.
+    pub fn get_state(&self) -> State {
+        self.framed.codec().get_state()
+    }
+
+    /// Returns a sender to queue packets for this client while it is in the play state. The
+    /// packets are written once the client was split using [`Client::into_split`].
+    pub fn packet_sender(&self) -> PacketSender {
+        self.packet_sender.clone()
+    }
+
+    /// Disconnects the client showing `reason` to the player.
+    ///
+    /// In the login and play state the matching Disconnect packet is sent before the connection
+    /// is closed. In the other states the connection is closed without a reason.
+    pub async fn disconnect<R: Into<ChatComponent>>(&mut self, reason: R) {
+        let reason = reason.into();
+        let packet = match self.get_state() {
+            State::Login => Some(PacketKind::LoginDisconnect(reason)),
+            State::Play => Some(PacketKind::PlayDisconnect(reason)),
+            State::Handshake | State::Status => None,
+        };
+
+        if let Some(packet) = packet {
+            if let Err(e) = self.framed.send(packet).await {
+                error!("Failed sending packet: {}", e.0);
             }
         }
+
+        if let Err(e) = self.framed.close().await {
+            error!("Failed closing connection: {}", e.0);
+        }
+    }
+
+    /// Forces the codec of this client into `state`.
+    ///
+    /// The codec follows the Handshake, Login and Play transitions on its own, so this is only
+    /// needed to recover from or deliberately leave the normal state machine.
+    pub fn set_state(&mut self, state: State) {
+        self.framed.codec_mut().set_state(state);
+    }
+
+    /// Splits the connection into a half reading the packets sent by the client and a half
+    /// writing the packets queued using the [`Client::packet_sender`], which can be used on
+    /// separate tasks. Should only be called in the play state.
+    pub fn into_split(self) -> (ClientReader, ClientWriter) {
+        let parts = self.framed.into_parts();
+        let (decoder, encoder) = parts.codec.split();
+        let (read_half, write_half) = parts.io.into_split();
+
+        let mut reader = FramedParts::new::<PacketKind>(read_half, decoder);
+        reader.read_buf = parts.read_buf;
+
+        let mut writer = FramedParts::new::<PacketKind>(write_half, encoder);
+        writer.write_buf = parts.write_buf;
+
+        (
+            ClientReader::new(Framed::from_parts(reader)),
+            ClientWriter::new(Framed::from_parts(writer), self.packet_queue),
+        )
+    }
+
+    /// Runs the play state of `player` until the client disconnects.
+    ///
+    /// After joining, the connection is split into a reader task handling the packets sent by
+    /// the client and a writer task sending the packets queued using the
+    /// [`Client::packet_sender`]. Once either of them ends, the connection is closed.
+    pub(crate) async fn play(mut self, player: Arc<Player>) {
+        self.join(&player).await;
+        self.set_slot(0 as i8).await;
+        self.declare_commands(&player).await;
+        self.send_spawn(&player).await;
+
+        let settings = self.settings.clone();
+        let (reader, writer) = self.into_split();
+        let name = player.name().clone();
+
+        let mut reader = tokio::spawn(read_packets(reader, player.clone(), settings));
+        let mut writer = tokio::spawn(async move { writer.run(&name).await });
+
+        tokio::select! {
+            reason = &mut reader => match reason {
+                Ok(Some(reason)) => {
+                    player.kick(reason);
+
+                    if let Err(e) = writer.await {
+                        error!("Failed writing packets: {}", e);
+                    }
+                }
+                _ => writer.abort(),
+            },
+            _ = &mut writer => reader.abort(),
+        }
     }
 
     async fn status(mut self) {
@@ -123,28 +217,32 @@ impl Client {
                 return;
             }
         };
-        let test_img = "iVBORw0KGgoAAAANSUhEUgAAAEAAAABACAYAAACqaXHeAAAACXBIWXMAAABIAAAASABGyWs+AAAACXZwQWcAAABAAAAAQADq8/hgAAAABmJLR0QA/wD/AP+gvaeTAAAQ2ElEQVR42uWbeZwV1ZXHv/dWvXq9vF7oxm4EpBVobAVEukWEICqLMS6RuI9RR2aiMhrjqOMSM0lwS/xkzLgMoqIx6qio8WOLZlxRQBZlEVRAdpAGGoWG3rvfUnXv/FFV3cXr1xvYxHxyP5/zqeXVvbfOr845dc7v1oN/8Gb05OBpkPEDmGJAqBq+7U7fIijNhwHVsOvvDtUMyJoK02fBZ0Og7GDHOQkuvhMWjYLLBMi/C+UnwsVvQeVz8GUeFB7qeEfBiD/Cnrtg+VEw8nureAis/4RZK0DPga97w5Hf1dhHwYhHoW4GxCfCLd875SOQ8yeY/zno5ZAYBqO/6zlOgotngX4c9BXwJ9nD8avLLR0yXoJP1oBeDfpm+ENPzXUdvPykB8LV8MLfPC4IEDPhjQ2g14H+FKqzILen5suHopkQewL0TNA/gQcPdcxDQvBaw7j9TMM4XwqBAbwGs+qhpqcA2AfbV8Bs6YLPJLh1JFz8NwHgWCmH32JZ9wrTRJgmUkrK4dmetrrF8Kz0blwAl8Hj2dDnsANwT3r6jFAoFPIB2Cnl11tgXU8DsAkWNkG18G4+AvnnH0LcOSgATrOss0anpY0Xhqu8MEw+l3LJ4Yg7CpxtsNS3AAGMgiv6H2SOcFAA3BjJvstV3HDFNNgmxKbDFXy/gXW+BUg3nxeT4Y7DAsCxljXs5MyMU33Tx3CB2A97DhcAdfBt0AIEcCJcmHMQyVe3AbggknWFb/bCNFpcIC5E7HABYEPMV9y3AhPMMri8xwGYnJ11rjANCICAaZBnGL0PY7GVE3wT+DIczutRAI4wzcKSzMjQluDng2CYDLasksMFQB8YLJKUF8BAGGNBRnfGMrtz8cjMzJOFaYIQ4DjuVggQMDotbXwn3EBefxhXCKW5MCgCfUxvfhuidVC5D9Z/A59VwhIbou2NVQJjUwFggtUfRm6FxT0CwOD09BIME+Eprh0H4RMYaekDh1rWiWvj8c8DFWLmcXD5cLiyCMZaYIS8SX3zBdCA40kcaIKmTfDOanhuK/yfBuWPOQCGF8JAJwUAEjgSju8xAAakZxwtTAMc0fL0tWhV5Mrs7Gl3VlVNMyH9JLhlNPx7L+idDoQByxPDE9H6bke5lkDclYw8uHAEXFgJGxfB3ethtgY9AaYGFdZJ0hsG9pgL5IWtfGGaaOEgnNbzWrs3dFF2zlXlNTVLSm37nkIoynQDFumuC7QAYAaitw5YgA9ADNf+m93+Q/rCi5tg2mK4czJcE+yXbAURyO8xAMJmKCwMMzBdKwIaSNc6/YH8/Oc+/fZbsoBMT3wAfCsIpbAAB0h4AEQDADR6YsGpp8PiDO9a0fYufJIz1CMAlEQiQ4/PzhomTAMEnunrVhMAtNYUZ+dQ29BAc2MjEQ8A3wosKTFME2ma1AlRUwc1AkS+EAVZjpOubBvbcYgrRZMbC/DdJ+KaNyqF4kEwCuHYCPRugKoulvSd0MZCGDcXD7nr1oGDfh3SOoTjoB0b7Tho293HttG2f84mEYuxdUcFabZNBAiHQtSFQlV/hdnzbfudL217WbVS+4Lz9DOMojLT/MEk0/zxJJhixGLhZtumAaj3FNeemzidSC3snQ3Xfw6vHRIAvSwr75nRp7xyeu/ek7Tj4Aue4kGlg4JjE29qIrZ7N/WWVfU/St3zaiz2dFTr5i4RH1IWXJeWdvuVQtyomputb5Ui1gXFk2UBPFYOtziuZ3UPgH4ZGf3LJ0z8YEhmpEQ7DrQAYKNtB5yg0gFAWiRBc3NzdNI33wzfbtubDybhGWaaZTOFWBJLJKyuKp1sIevggz/DlLjrUW0tPNXJvpmZ/d4+60fzi7NzhvjO1QYpnXSgXRG6dd8U0hxnhSfOjza/W6tUdXeUz4a8W5V6qkCpwTrF6y4oqoPf8mBQEYz7Al5VbpztGIDMUCjzzbPP+eC4vPyhQrSNtULTOrzuQJS7zZey4MJw+Kodtv31Rtte2xXlT4RT/xveLYaR3VU41TW5cHQBDF8Nf9FJj64NAE9MmPzM5KKiH/oeIlI99jYzJiuvvH0FShNGpJ8dti4qlXLMJsdZt0ep3e3w/4NuhYdvhoci0Ks7SqtOfusNxypgG8xvNwZcXFJy2XNnnTMb1Rrw3KCWFOh8f0+0+nvrsb+faP0tYaO8rXZsVsZiS+fG4nNecOyZjVrXXw43jIdzRsFkQPq+bHfD17vyWxycp2B8BSxpUw3mhMM5D06c/HCQ5PAFKcEwPJHusfC2Urj7Qnj7nkjvnPebEBIhBAJBqWmOviRk/kuj1vUK1BiYMA5+aATuR3SQ7HCQvxlgnA9PyUD+07LzH2PH3dEnJ7dQK8dNdNCuvxutpr2pvm7D6l2VOyM11RNFUxNZyqG3ZZGXGSE9N9cDRAaqRHFAxdiyj2BGwr5feUXOE/C7M2BK8o23l+xIL5v0U+Z6b383LDwKSkNu/nVAP78VwPGl8LMV8ERLDMhJS8t5/pJLZ6eZobCnfYvPxxKJ+LPrvnr6+o8X/Ot9n6/69e7KXf0j+/dPdOrrkQ0NGLW1hPZV8d6+feW3V+29xhDCGJKWdrzU2nBjgfICYut2u2Nv/lVz83U+AHugcgScchQMTuXXXqqsV8D7f4Zf/jgz87T8RCIS99LkWmAv8AJc+RrctANWZUFhHhydKh4UwAnLYIYGJQGuGFl2VW5mZjaGgTCka/rSYN6OnR+OevmloTd9vGDamv37v/TIiFLTewItxY2ULHSc95fV1S3+xZbNV49Zs7r4xX37Ztla263v0NbtzFjsAdt11Zb2GNzdXlKyBOZcD2W/grMWwWtLYW5Iypb5/QqzEMpsiK2C1x+GM2bA5D2wKdk9cmBACVzQEgOuGjXqKuH5PdJACaHvXbJo+rmv/2XylpqaA5KYHBhoBEpaEzAMgzWO85l/za54vOK2nTuuO3Pr1pHLo82Lg8pXalVRHo//b7KSX8Cni+G9oMnugA03wcRfwZTNsMq/do3jrDSkdOduZYbJTSqFN8DcP0LpKpidDMIJcAWA7JeT26+0qKjMD24K9E3vvXPD/YsW3q211in4uEKZNDFSskup7cnXbohF11xYUTH+vn37bksoHQd4rKnp9wmtU6amT8MDXnWoX4FHr4QTV8JHyddVal0hhAjS4ki3cCpIvjYGDS/CTz+Bx4PnB8KZFkTM04qLTzcMU2iVAAG//3j+vbNWLH+8vSTFgow2UVYIEu3k2wrUk7U1D66KNi/9TXb2Q69Go8+0N/ZymL8Y3n0Tnn836akdQIsrVe2+UdpQYuFU12vQb8ANFmQPh596wS98FIwzTxow4CS/nJ2/ZfO8e+d9dHdHWZpfWOgUK8Ud9VsWiy2cUlU1xtY60eGiC5znJMWHNvyiEJk6RVYepM5SgVAO1xVCWT6UAPSFk80hvXoP0Y5DNB6P/1t5+XVKa9XR5FHYr6BABagslKJQyr6d5fu21onrMzJ+aWkd1o4C5aCVcsUbaxbc31mqXChlX23bBO9BuQRKhxxAHBrnwLVTYQEg8qDE7BNJP1LbNs8uX/rMpn1VnS5v1cLXDpSoQIalHIdiwxjWlVz/R6HQRcOkLHUzRYFOJFoYofWwdiZM72yMIVIOVUq1zu9t62FnZ323w8L18GYxnJ8N/WWuGeqlbZtHFy96pCuFyl5YbXv0lU9hOUpxipSnd6X/Fkd91Wqz+gAzXg8ruzLGKVKeYWtNPHAfjntvX3Sl/6fwsMdaZ0ntKFbuqFi5Ye/e9V3pvMvl7A8AIK4Uk6X8iSlEp3xcpXJ2tlSSAT7RT4g6619smkMHO87QeBIACVCV8ElXdNgOC2qgAkDub6yrmbtx4wddrdMr4MMYxPw01N/mxeOFZ1tWp19rVCtV5afW2pPAKyvaWf9/DodvtOPxlrl92QXLmtyEsNOmQW+Bd22Iysrq2srllbuWdxWAONRvgjeDzG0zELdtbjLNuy0hwh31j2kddRUPkKmeWC553G472jCKL4SpUcehKcAex1zm5+XuEC6VsKwRdsuNVXvXb63ev6U7nVfBzFhA+SagUWv6x2KDf5Ge/ttO0G/lCnwr8M4fAX3bJWfB+F1GxizR3Gw1enM2efPXQ8NaeK47OlTD5v2wUa6orFxeG41268OmHTB/OyxuCvD29UCzbXON1ndMsKxz211b0KQdQJ4E2jHe+zlVuzkj476TYrHTG5SiwZvTB+EzmBHr5sdZTVC1G1bIjyoq5tpK2d0lLOfBrQ2gfNq63v1wgXg0Kh8JhV4ZGwpNTLm6JDhCK9Xi/8FKbRAMT+UG0zIy7pym9Z3N8XjLXA2eVMG3y7wUupvfGES3wzyj2bab6uPxuu4O0AC7LMg9EsYE1/oEkG7boSnh8GXVhlG12rZXBPtdFgr9rBiG4THNwURGgrkC5u+CbQCWEOHpkciMax3nzuZolBrcx1wbkLdg6p5AodTVFoOaBDQd0neCC+GubbC8LnBDNUCN1iSamqy7tX78mayst48xjCF+n2NhhM8ZpuLvTocpAGMta+Kbkchnl8Zi0xqjUaqTlK8DlsJjG7uw+NHem6BLK0OdtQj0uwKW9IcBuW65TI5La7vrg1Ii0tPtD4QofzeReP0hKV8Sti20baO0bsPbVUN1VSSytsxxxiViMRqVos4z+xaAgTXw/utu3RA/lPs/ZABw6/DBl8GH/WBAUPngAmlYSkLhMEJrtG2jbLtNKuuLJQRa6wMCrB9jaoGv4KPX4bxEO4sd3WnfyRfXUdi/Hl4tgPHp0C+43u8vedtak2maoJTLNmvdppjxJZbC1/3jFfD8m3Cp3YWk6bAB4CdIa+F5IDMfRjsg7EC63CsUIsv7tEYrdQDflyyO9+T3BgDYA3Vvw88XwW+0ewnfKwC8wOJ8De9thvcy4bg0GJAAsqSkxLJa1xeTFNZJyiuPatvlgqCWw4vlcMGOpEWN76IJerAVwaRzpPztz8PhcWFvcSVVGdueNIC6FSZthnk9dY89+oeDXMOomxoODwn7pIdn+slPXadwB+1+VSJvgUfyOkiRewwAS8pwYcg6qP/9mGBeGw7f9kpa2sd5Whdoxw187SkfNP1kMIpg+IOw9HgYfwhKmt2OAY7WzmnZ2Wdempc3dWsstrHOcWo7mygkhHWuZV36cEbGS+dLeblUytSOg1atUV+34/uqg7iQBtmnwdUFMHAHfNUA+7oY4KwRcLkBVl07/z/sNAacEck6a0bffi+ua25cvaCx8f3V0ejKHYnEtpjWMbTmCCn7FBvm8SebxqkTQqFzeyndu+UTGscBz+9Vkt+35/+qk2Mb9BqYvwLe2AiLdsFa231z+usW/fpBaTGceQxMmAM3bOsgeHYpCBaFQoMeLezzfKlljcXzZbwni6PcJ+yfO+BTmlblUwGQAL0A5pTAKbnQR3UTDP9cPdR4X46lGZDmuJniX8thWl0n/zzt0muwVqnqV+vqnt2dSOwcKs2yiFbZ7hJ6gNl1PDB85Z3UyvuyDD6aDle9CP81B55shPoiGB6GSEevyVTHhqe4BHMHrHoNps2F6TE3gfxuX4MhIazz09IuvyQUmlpmmuOkUlI7TmuG51d5AeVVK2m5Zy68Vg5Pb0hRwZlgjYUp4+GfToDJIchUXbCIOqheDW8thec2wzzddtmiZ/KAfCEKxhjGhBFCjhqEPu4Ix+mXpXW21pooxKpgTwVsWQ9ffubS0StVB4sXyWAMgrJjoLQABuXAkQakealyczXs3g0bt8OKnfC5+g6zw3+o9v89UKv1klb6bAAAACV0RVh0ZGF0ZTpjcmVhdGUAMjAxMC0wMi0xMFQxNDoxNTo1NS0wNjowMBqQkBsAAAAldEVYdGRhdGU6bW9kaWZ5ADIwMDgtMDQtMDFUMjM6MjE6MzYtMDU6MDCJxuy+AAAAAElFTkSuQmCC";
 
-        let pv = if self.get_protocol_version() == -1 {
-            754
-        } else {
+        // Clients outside of the version range get the newest allowed version, which differs
+        // from their own and marks the server as incompatible in their server list.
+        let version_range = &self.settings.version_range;
+        let pv = if version_range.contains(&self.get_protocol_version()) {
             self.get_protocol_version()
+        } else {
+            *version_range.end()
         };
 
         let response_data = status::ResponseData {
             version: status::VersionData {
-                name: String::from("1.8 - 1.16"),
+                name: version::range_name(version_range),
                 protocol: pv,
             },
             players: status::PlayerData {
-                max: SERVER.read().await.get_max_online_player_count(),
-                online: SERVER.read().await.get_online_player_count(),
-                sample: Vec::new(),
-            },
-            description: status::DescriptionData {
-                text: String::from("Hello world"),
+                max: SERVER.get_max_online_player_count(),
+                online: SERVER.get_online_player_count(),
+                sample: SERVER
+                    .player_sample(MAX_PLAYER_SAMPLE)
+                    .into_iter()
+                    .map(|(id, name)| status::SinglePlayerData { name, id })
+                    .collect(),
             },
-            favicon: String::from(format!("data:image/png;base64,{}", test_img)),
+            description: self.settings.motd.clone(),
+            favicon: self.settings.favicon.clone(),
         };
 
         send_packet!(self PacketKind::StatusResponse(response_data));
@@ -161,6 +259,24 @@ impl Client {
     }
 
     async fn login(mut self) {
+        let protocol_version = self.get_protocol_version();
+
+        let version_range = &self.settings.version_range;
+
+        if !version_range.contains(&protocol_version) {
+            info!(
+                "Client with unsupported protocol version {} tried to log in.",
+                protocol_version
+            );
+            let name = version::range_name(version_range);
+            let reason = if protocol_version < *version_range.start() {
+                format!("Outdated client! Please use {}", name)
+            } else {
+                format!("Outdated server! I'm still on {}", name)
+            };
+            return self.disconnect(reason).await;
+        }
+
         let name = match next_packet!(self) {
             PacketKind::LoginStart(n) => n,
             p => {
@@ -169,22 +285,134 @@ impl Client {
             }
         };
 
-        let uuid = Uuid::new_v3(&Uuid::NAMESPACE_OID, name.as_bytes());
+        let (uuid, name) = match self.settings.authenticator.clone() {
+            Some(authenticator) => match self.authenticate(&authenticator, name).await {
+                Ok(Some(profile)) => (profile.id, profile.name),
+                Ok(None) => {
+                    error!("Failed verifying username.");
+                    return self.disconnect("Failed to verify username!").await;
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    return self.disconnect("Failed to verify username!").await;
+                }
+            },
+            None if self.settings.legacy_offline_uuids => (auth::legacy_offline_uuid(&name), name),
+            None => (auth::offline_uuid(&name), name),
+        };
+
+        let player = Arc::new(Player::new(
+            uuid,
+            name,
+            SERVER.next_entity_id(),
+            protocol_version,
+            self.packet_sender(),
+        ));
 
-        if SERVER.read().await.has_uuid(&uuid).await {
-            error!("Player already connected.");
-        } else {
-            info!("Player {} with uuid {} logged in successfully.", name, uuid);
-            send_packet!(self PacketKind::LoginSuccess(uuid.clone(), name.clone()));
-            SERVER
-                .write()
-                .await
-                .add_player(Arc::new(RwLock::new(Player::new(uuid, name, self))));
+        player.set_operator(
+            self.settings
+                .operators
+                .iter()
+                .any(|o| o.eq_ignore_ascii_case(player.name())),
+        );
+
+        if let Err(e) = SERVER.register_player(player.clone()) {
+            match e {
+                RegisterError::AlreadyConnected => error!(
+                    "Player {} with uuid {} already connected.",
+                    player.name(),
+                    uuid
+                ),
+                RegisterError::Full => {
+                    info!(
+                        "Player {} could not join, the server is full.",
+                        player.name()
+                    )
+                }
+            }
+
+            return self.disconnect(e.to_string()).await;
+        }
+
+        if let Err(e) = self.finish_login(&player).await {
+            error!("Failed sending packet: {}", e.0);
+            SERVER.remove_player(&uuid);
+            return;
         }
+
+        info!(
+            "Player {} with uuid {} logged in successfully.",
+            player.name(),
+            uuid
+        );
+        SERVER.add_player(player, self);
+    }
+
+    /// Enables compression if configured and sends the Login Success packet for `player`.
+    async fn finish_login(&mut self, player: &Player) -> Result<(), EncoderError> {
+        if self.settings.compression_threshold >= 0 {
+            self.framed
+                .send(PacketKind::SetCompression(
+                    self.settings.compression_threshold,
+                ))
+                .await?;
+        }
+
+        self.framed
+            .send(PacketKind::LoginSuccess(
+                *player.uuid(),
+                player.name().clone(),
+            ))
+            .await
+    }
+
+    /// Runs the encryption handshake with the client and verifies the player `name` using
+    /// `authenticator`. Returns the profile of the player if they are logged in.
+    async fn authenticate(
+        &mut self,
+        authenticator: &Authenticator,
+        name: String,
+    ) -> Result<Option<Profile>, NetError> {
+        let verify_token = rand::random::<[u8; 4]>().to_vec();
+
+        self.framed
+            .send(PacketKind::EncryptionRequest {
+                server_id: String::new(),
+                public_key: authenticator.public_key().to_vec(),
+                verify_token: verify_token.clone(),
+            })
+            .await
+            .map_err(|e| NetError::AuthenticationError(e.0))?;
+
+        let (shared_secret, response_token) = match self.next_packet().await? {
+            PacketKind::EncryptionResponse {
+                shared_secret,
+                verify_token,
+            } => (shared_secret, verify_token),
+            p => {
+                error!("Unexpected packet {}, expected EncryptionResponse.", p);
+                return Err(NetError::UnexpectedPacket);
+            }
+        };
+
+        if authenticator.decrypt(&response_token)? != verify_token {
+            return Err(NetError::AuthenticationError(
+                "Verify token does not match.".to_string(),
+            ));
+        }
+
+        let shared_secret = authenticator.decrypt(&shared_secret)?;
+
+        self.framed
+            .codec_mut()
+            .enable_encryption(&shared_secret)
+            .map_err(NetError::AuthenticationError)?;
+
+        authenticator.verify(&name, &shared_secret).await
     }
 
-    /// send the [JoinGame] Packet
-    pub async fn join(&mut self) {
+    /// send the [JoinGame] Packet for `player`
+    pub async fn join(&mut self, player: &Player) {
         let dimension = DimensionType {
             piglin_safe: false,
             natural: true,
@@ -275,38 +503,14 @@ impl Client {
             },
             biome_registry: BiomeRegistry {
                 biome_type: String::from("minecraft:worldgen/biome"),
-                value: vec![BiomeRegistryEntry {
-                    name: String::from("minecraft:plains"),
-                    id: 1,
-                    element: BiomeProperties {
-                        precipitation: String::from("rain"),
-                        depth: -1.0,
-                        temperature: 0.5,
-                        scale: 0.1,
-                        downfall: 0.5,
-                        category: String::from("plains"),
-                        temperature_modifier: None,
-                        effects: BiomeEffects {
-                            sky_color: 8103167,
-                            water_fog_color: 329011,
-                            fog_color: 12638463,
-                            water_color: 4159204,
-                            foilage_color: None,
-                            grass_color: None,
-                            grass_color_modifier: None,
-                            music: None,
-                            ambient_sound: None,
-                            additions_sound: None,
-                            mood_sound: None,
-                        },
-                        particle: None,
-                    },
-                }],
+                value: Biome::ALL.iter().map(|b| b.registry_entry()).collect(),
             },
         };
 
+        let is_flat = SERVER.world().is_flat();
+
         let packet = PacketKind::JoinGame {
-            entity_id: 0,
+            entity_id: player.entity_id(),
             is_hardcore: false,
             gamemode: Gamemode::Survival,
             previous_gamemode: Gamemode::NoPreviousMode,
@@ -316,14 +520,18 @@ impl Client {
             dimension_47: version_specific::types::v47::Dimension::Overworld,
             difficulty: types::Difficulty::Peaceful,
             world_name: String::from("world"),
-            max_players: SERVER.read().await.get_max_online_player_count(),
-            level_type: types::LevelType::Default,
-            view_distance: VarInt(8),
-            hashed_seed: 0x6B51D431DF5D7F14,
+            max_players: SERVER.get_max_online_player_count(),
+            level_type: if is_flat {
+                types::LevelType::Flat
+            } else {
+                types::LevelType::Default
+            },
+            view_distance: VarInt(VIEW_DISTANCE),
+            hashed_seed: seed::hashed_seed(self.settings.seed),
             reduced_debug_info: false,
             enable_respawn_screen: true,
-            is_debug: true,
-            is_flat: false,
+            is_debug: false,
+            is_flat,
         };
         send_packet!(self packet);
 
@@ -334,6 +542,61 @@ impl Client {
         }
         self.send_server_difficulty(Difficulty::Peaceful, true)
             .await;
+
+        send_packet!(self SERVER.time().packet());
+    }
+
+    /// Sends the chunks within the view distance around the spawn, closest first, and then
+    /// teleports `player` to the spawn. The block states of the chunks are converted to the ids
+    /// of the protocol version of the client.
+    pub async fn send_spawn(&mut self, player: &Player) {
+        let protocol_version = self.get_protocol_version();
+        let blocks = self.settings.blocks.clone();
+        let world = SERVER.world();
+        let spawn = world.spawn();
+        let (center_x, center_z) = ((spawn.0.floor() as i32) >> 4, (spawn.2.floor() as i32) >> 4);
+
+        let mut positions: Vec<(i32, i32)> = (-VIEW_DISTANCE..=VIEW_DISTANCE)
+            .flat_map(|x| (-VIEW_DISTANCE..=VIEW_DISTANCE).map(move |z| (x, z)))
+            .map(|(x, z)| (center_x + x, center_z + z))
+            .collect();
+        positions.sort_by_key(|(x, z)| (x - center_x).pow(2) + (z - center_z).pow(2));
+
+        // Clients only show the chunks around the chunk they think the player is in.
+        if protocol_version >= 477 {
+            send_packet!(self PacketKind::UpdateViewPosition {
+                x: center_x,
+                z: center_z,
+            });
+        }
+
+        // Generates chunks on all worker threads while sending them in order.
+        let mut chunks = futures::stream::iter(positions)
+            .map(|(x, z)| async move { (x, z, world.chunk(x, z).await) })
+            .buffered(world.generator_threads());
+
+        while let Some((x, z, chunk)) = chunks.next().await {
+            let chunk = chunk.map_states(|state| blocks.canonical_to_id(state, protocol_version));
+
+            if protocol_version >= 477 {
+                let (sky_light, block_light) = chunk.light();
+
+                send_packet!(self PacketKind::UpdateLight {
+                    x,
+                    z,
+                    sky_light,
+                    block_light,
+                });
+            }
+
+            send_packet!(self PacketKind::ChunkData {
+                x,
+                z,
+                chunk: Arc::new(chunk),
+            });
+        }
+
+        player.teleport(spawn);
     }
 
     /// used to send the [ServerDifficulty] packet
@@ -350,7 +613,220 @@ impl Client {
         send_packet!(self PacketKind::HeldItemChange(slot));
     }
 
+    /// Declares the commands `player` may use to 1.13+ clients. Older clients do not know the
+    /// commands and ask for completions using Tab-Complete packets.
+    pub async fn declare_commands(&mut self, player: &Player) {
+        if self.get_protocol_version() >= 393 {
+            let operator = player.is_operator();
+            let packet = COMMANDS.read().await.declare_commands(operator);
+
+            send_packet!(self packet);
+        }
+    }
+
     async fn next_packet(&mut self) -> Result<PacketKind, NetError> {
         Ok(self.framed.next().await.ok_or(NetError::UnexpectedEof)??)
     }
 }
+
+/// Reads the packets sent by the client of `player` in the play state until it disconnects.
+/// Packets changing the game are queued for the next tick, keep alives and completions are
+/// answered right away.
+///
+/// Every [`KEEP_ALIVE_INTERVAL`] a keep alive is queued and the latency measured from the answer
+/// is stored in `player`. Returns the reason to disconnect the client with if it has to be
+/// kicked, e.g. because it did not answer a keep alive within the keep alive timeout of the
+/// [`Settings`].
+async fn read_packets(
+    mut reader: ClientReader,
+    player: Arc<Player>,
+    settings: Arc<Settings>,
+) -> Option<ChatComponent> {
+    let mut keep_alive_check = time::interval(KEEP_ALIVE_CHECK_INTERVAL);
+    let mut last_keep_alive = Instant::now();
+    let mut pending_keep_alive: Option<(i64, Instant)> = None;
+
+    loop {
+        let packet = tokio::select! {
+            packet = reader.next_packet() => packet,
+            _ = keep_alive_check.tick() => {
+                match pending_keep_alive {
+                    Some((_, sent)) if sent.elapsed() >= settings.keep_alive_timeout => {
+                        info!("Player {} timed out.", player.name());
+                        return Some("Timed out".into());
+                    }
+                    None if last_keep_alive.elapsed() >= KEEP_ALIVE_INTERVAL => {
+                        // Stays in the range of the VarInt used before protocol version 339.
+                        let id = rand::random::<i32>() as i64;
+
+                        player.send_packet(PacketKind::ClientboundKeepAlive(id));
+                        last_keep_alive = Instant::now();
+                        pending_keep_alive = Some((id, last_keep_alive));
+                    }
+                    _ => {}
+                }
+
+                continue;
+            }
+        };
+
+        match packet {
+            Ok(Some(
+                PacketKind::Handshake { .. }
+                | PacketKind::LoginStart(_)
+                | PacketKind::LoginSuccess(..)
+                | PacketKind::EncryptionRequest { .. }
+                | PacketKind::EncryptionResponse { .. }
+                | PacketKind::SetCompression(_)
+                | PacketKind::LoginDisconnect(_)
+                | PacketKind::StatusRequest
+                | PacketKind::StatusResponse(_)
+                | PacketKind::StatusPing(_)
+                | PacketKind::StatusPong(_)
+                | PacketKind::JoinGame { .. }
+                | PacketKind::ServerDifficulty(..)
+                | PacketKind::HeldItemChange(_),
+            )) => {
+                error!("Received packet from other protocol state.");
+                return None;
+            }
+            Ok(Some(
+                PacketKind::DeclareRecipes(_)
+                | PacketKind::ClientboundKeepAlive(_)
+                | PacketKind::PlayDisconnect(_)
+                | PacketKind::ClientboundChatMessage { .. }
+                | PacketKind::DeclareCommands { .. }
+                | PacketKind::ClientboundTabComplete { .. }
+                | PacketKind::PlayerPositionAndLook { .. }
+                | PacketKind::TimeUpdate { .. }
+                | PacketKind::ChunkData { .. }
+                | PacketKind::UnloadChunk { .. }
+                | PacketKind::UpdateLight { .. }
+                | PacketKind::UpdateViewPosition { .. },
+            )) => {
+                error!("Received client side packet.");
+            }
+            Ok(Some(PacketKind::ServerboundChatMessage(message))) => {
+                if message.chars().any(|c| c == '§' || c.is_control()) {
+                    return Some("Illegal characters in chat".into());
+                }
+
+                player
+                    .queue_inbound(PacketKind::ServerboundChatMessage(message))
+                    .await;
+            }
+            Ok(Some(PacketKind::ServerboundTabComplete {
+                transaction_id,
+                text,
+            })) => {
+                player.send_packet(tab_complete(&player, transaction_id, &text).await);
+            }
+            Ok(Some(PacketKind::ServerboundKeepAlive(id))) => match pending_keep_alive.take() {
+                Some((pending, sent)) if pending == id => {
+                    player.update_latency(sent.elapsed());
+                }
+                _ => {
+                    error!(
+                        "Player {} answered with an invalid keep alive id {}.",
+                        player.name(),
+                        id
+                    );
+                    return Some("Timed out".into());
+                }
+            },
+            Ok(Some(
+                p @ (PacketKind::PlayerPosition { .. }
+                | PacketKind::PlayerPositionAndRotation { .. }
+                | PacketKind::TeleportConfirm(_)
+                | PacketKind::ClientStatus(_)
+                | PacketKind::ClientSettings { .. }
+                | PacketKind::PlayerMovement(_)
+                | PacketKind::PlayerRotation { .. }
+                | PacketKind::ServerboundHeldItemChange(_)
+                | PacketKind::ServerboundPluginMessage { .. }
+                | PacketKind::Animation(_)
+                | PacketKind::PlayerDigging { .. }
+                | PacketKind::PlayerBlockPlacement { .. }
+                | PacketKind::UseItem(_)
+                | PacketKind::EntityAction { .. }
+                | PacketKind::ClickWindow { .. }
+                | PacketKind::ServerboundCloseWindow(_)),
+            )) => {
+                player.queue_inbound(p).await;
+            }
+            Ok(None) => return None,
+            Err(e) => {
+                error!("Failed reading next packet: {}", e.0);
+                return None;
+            }
+        }
+    }
+}
+
+/// Handles `packet` received from the client of `player` during a tick.
+pub(crate) async fn handle_packet(player: &Arc<Player>, packet: PacketKind) {
+    match packet {
+        PacketKind::ServerboundChatMessage(message) => {
+            if let Some(command) = message.strip_prefix('/') {
+                info!("{} issued server command: /{}", player.name(), command);
+                command::execute(CommandSource::Player(player.clone()), command).await;
+                return;
+            }
+
+            SERVER.broadcast_chat(player.uuid(), player.name(), message.trim());
+        }
+        PacketKind::PlayerPosition { x, feet_y, z, .. }
+        | PacketKind::PlayerPositionAndRotation { x, feet_y, z, .. } => {
+            player.set_position((x, feet_y, z));
+        }
+        p => debug!("Received {}.", p),
+    }
+}
+
+/// Returns the answer to a Tab-Complete request for `text`, all text behind the cursor of
+/// `player`. Clients before 1.13 also ask for completions of chat messages, which complete
+/// player names, and replace the whole last word with the matches.
+async fn tab_complete(player: &Player, transaction_id: i32, text: &str) -> PacketKind {
+    let operator = player.is_operator();
+    let players = SERVER.player_names();
+
+    let (start, matches) = match text.strip_prefix('/') {
+        Some(command) => {
+            let (start, matches) = COMMANDS.read().await.suggest(command, operator, &players);
+
+            (start + 1, matches)
+        }
+        None => {
+            let start = text.rfind(' ').map_or(0, |i| i + 1);
+            let matches = players
+                .into_iter()
+                .filter(|p| starts_with_ignore_case(p, &text[start..]))
+                .collect();
+
+            (start, matches)
+        }
+    };
+
+    if player.protocol_version() < 393 {
+        let matches = if start == 1 && text.starts_with('/') {
+            matches.into_iter().map(|m| format!("/{}", m)).collect()
+        } else {
+            matches
+        };
+
+        return PacketKind::ClientboundTabComplete {
+            transaction_id,
+            start: 0,
+            length: 0,
+            matches,
+        };
+    }
+
+    // Brigadier counts in UTF-16 code units.
+    PacketKind::ClientboundTabComplete {
+        transaction_id,
+        start: text[..start].encode_utf16().count() as i32,
+        length: text[start..].encode_utf16().count() as i32,
+        matches,
+    }
+}
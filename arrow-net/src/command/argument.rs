@@ -0,0 +1,338 @@
+use arrow_protocol::packets::types::{ArgumentParser, StringKind};
+
+use super::CommandError;
+
+/// The suggestions provider telling 1.13+ clients to ask the server for suggestions.
+const ASK_SERVER: &str = "minecraft:ask_server";
+
+/// The type of an argument node deciding how its input is parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgumentType {
+    /// `true` or `false`.
+    Bool,
+    /// An integer within the optional bounds.
+    Integer {
+        /// The minimum value.
+        min: Option<i32>,
+        /// The maximum value.
+        max: Option<i32>,
+    },
+    /// A floating point number within the optional bounds.
+    Double {
+        /// The minimum value.
+        min: Option<f64>,
+        /// The maximum value.
+        max: Option<f64>,
+    },
+    /// A string reading as much of the input as `StringKind` allows.
+    String(StringKind),
+    /// The name of a player.
+    Player,
+    /// Three coordinates, each of which may be relative to the source using `~`.
+    Position,
+}
+
+/// The value of a parsed argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgumentValue {
+    /// A parsed [`ArgumentType::Bool`].
+    Bool(bool),
+    /// A parsed [`ArgumentType::Integer`].
+    Integer(i32),
+    /// A parsed [`ArgumentType::Double`].
+    Double(f64),
+    /// A parsed [`ArgumentType::String`].
+    String(String),
+    /// A parsed [`ArgumentType::Player`]. The player is not necessarily online.
+    Player(String),
+    /// A parsed [`ArgumentType::Position`].
+    Position(Coordinates),
+}
+
+/// A single coordinate of a [`Coordinates`] argument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinate {
+    /// The value, an offset if the coordinate is relative.
+    pub value: f64,
+    /// Whether the coordinate was prefixed with `~`.
+    pub relative: bool,
+}
+
+/// The coordinates of an [`ArgumentType::Position`] argument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    /// The x coordinate.
+    pub x: Coordinate,
+    /// The y coordinate.
+    pub y: Coordinate,
+    /// The z coordinate.
+    pub z: Coordinate,
+}
+
+impl ArgumentType {
+    /// Parses the argument at the start of `input`. Returns the value and the number of bytes
+    /// read.
+    pub fn parse(&self, input: &str) -> Result<(ArgumentValue, usize), CommandError> {
+        let word = read_word(input);
+
+        match self {
+            Self::Bool => match word {
+                "true" => Ok((ArgumentValue::Bool(true), word.len())),
+                "false" => Ok((ArgumentValue::Bool(false), word.len())),
+                "" => Err(CommandError::InvalidArgument("Expected bool".to_string())),
+                _ => Err(CommandError::InvalidArgument(format!(
+                    "Invalid bool, expected true or false but found '{}'",
+                    word
+                ))),
+            },
+            Self::Integer { min, max } => {
+                let value = parse_number::<i32>(word, "integer")?;
+
+                check_bounds(value, *min, *max, "Integer")?;
+
+                Ok((ArgumentValue::Integer(value), word.len()))
+            }
+            Self::Double { min, max } => {
+                let value = parse_number::<f64>(word, "double")?;
+
+                check_bounds(value, *min, *max, "Double")?;
+
+                Ok((ArgumentValue::Double(value), word.len()))
+            }
+            Self::String(StringKind::SingleWord) => {
+                Ok((ArgumentValue::String(word.to_string()), word.len()))
+            }
+            Self::String(StringKind::QuotablePhrase) => {
+                let (value, len) = read_quotable(input)?;
+
+                Ok((ArgumentValue::String(value), len))
+            }
+            Self::String(StringKind::GreedyPhrase) => {
+                Ok((ArgumentValue::String(input.to_string()), input.len()))
+            }
+            Self::Player => {
+                if word.is_empty() {
+                    return Err(CommandError::InvalidArgument(
+                        "Expected player name".to_string(),
+                    ));
+                }
+
+                Ok((ArgumentValue::Player(word.to_string()), word.len()))
+            }
+            Self::Position => {
+                let mut read = 0;
+                let mut coordinates = [Coordinate {
+                    value: 0.0,
+                    relative: false,
+                }; 3];
+
+                for (i, coordinate) in coordinates.iter_mut().enumerate() {
+                    if i > 0 {
+                        if !input[read..].starts_with(' ') {
+                            return Err(CommandError::InvalidArgument(
+                                "Incomplete (expected 3 coordinates)".to_string(),
+                            ));
+                        }
+
+                        read += 1;
+                    }
+
+                    let word = read_word(&input[read..]);
+
+                    // Like vanilla, whole block coordinates on the x and z axis point to the
+                    // center of the block.
+                    *coordinate = parse_coordinate(word, i != 1)?;
+                    read += word.len();
+                }
+
+                let [x, y, z] = coordinates;
+
+                Ok((ArgumentValue::Position(Coordinates { x, y, z }), read))
+            }
+        }
+    }
+
+    /// Returns the values the partially typed `input` can be completed to. `players` are the
+    /// names of all online players.
+    pub fn suggest(&self, input: &str, players: &[String]) -> Vec<String> {
+        let candidates = match self {
+            Self::Bool => vec!["true".to_string(), "false".to_string()],
+            Self::Player => players.to_vec(),
+            Self::Position => vec!["~ ~ ~".to_string()],
+            _ => vec![],
+        };
+
+        candidates
+            .into_iter()
+            .filter(|c| starts_with_ignore_case(c, input))
+            .collect()
+    }
+
+    /// Returns the parser this argument is declared with to 1.13+ clients.
+    pub fn parser(&self) -> ArgumentParser {
+        match *self {
+            Self::Bool => ArgumentParser::Bool,
+            Self::Integer { min, max } => ArgumentParser::Integer { min, max },
+            Self::Double { min, max } => ArgumentParser::Double { min, max },
+            Self::String(kind) => ArgumentParser::String(kind),
+            Self::Player => ArgumentParser::Entity {
+                single: true,
+                only_players: true,
+            },
+            Self::Position => ArgumentParser::Vec3,
+        }
+    }
+
+    /// Returns the suggestions provider this argument is declared with to 1.13+ clients.
+    pub fn suggestions(&self) -> Option<String> {
+        match self {
+            // The client only knows the players in its tab list, so it has to ask the server.
+            Self::Player => Some(ASK_SERVER.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl Coordinate {
+    /// Returns the absolute value of the coordinate relative to `origin`.
+    pub fn resolve(&self, origin: f64) -> f64 {
+        if self.relative {
+            origin + self.value
+        } else {
+            self.value
+        }
+    }
+}
+
+impl Coordinates {
+    /// Returns the absolute coordinates relative to `origin`.
+    pub fn resolve(&self, origin: (f64, f64, f64)) -> (f64, f64, f64) {
+        (
+            self.x.resolve(origin.0),
+            self.y.resolve(origin.1),
+            self.z.resolve(origin.2),
+        )
+    }
+}
+
+/// Returns the input up to the next space.
+pub(crate) fn read_word(input: &str) -> &str {
+    input.split(' ').next().unwrap_or("")
+}
+
+/// Returns whether `s` starts with `prefix`, ignoring the ASCII case.
+pub(crate) fn starts_with_ignore_case(s: &str, prefix: &str) -> bool {
+    s.len() >= prefix.len()
+        && s.is_char_boundary(prefix.len())
+        && s[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+/// Parses `word` as a number of type `T` called `name` in errors.
+fn parse_number<T: std::str::FromStr>(word: &str, name: &str) -> Result<T, CommandError> {
+    if word.is_empty() {
+        return Err(CommandError::InvalidArgument(format!("Expected {}", name)));
+    }
+
+    word.parse()
+        .map_err(|_| CommandError::InvalidArgument(format!("Invalid {} '{}'", name, word)))
+}
+
+/// Checks that `value` lies within `min` and `max`.
+fn check_bounds<T: PartialOrd + std::fmt::Display>(
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+    name: &str,
+) -> Result<(), CommandError> {
+    match (min, max) {
+        (Some(min), _) if value < min => Err(CommandError::InvalidArgument(format!(
+            "{} must not be less than {}, found {}",
+            name, min, value
+        ))),
+        (_, Some(max)) if value > max => Err(CommandError::InvalidArgument(format!(
+            "{} must not be more than {}, found {}",
+            name, max, value
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Parses a single coordinate. Whole absolute numbers are moved to the center of the block if
+/// `center` is set.
+fn parse_coordinate(word: &str, center: bool) -> Result<Coordinate, CommandError> {
+    if word.starts_with('^') {
+        return Err(CommandError::InvalidArgument(
+            "Local coordinates are not supported".to_string(),
+        ));
+    }
+
+    if let Some(offset) = word.strip_prefix('~') {
+        let value = if offset.is_empty() {
+            0.0
+        } else {
+            parse_number(offset, "double")?
+        };
+
+        return Ok(Coordinate {
+            value,
+            relative: true,
+        });
+    }
+
+    if word.is_empty() {
+        return Err(CommandError::InvalidArgument(
+            "Incomplete (expected 3 coordinates)".to_string(),
+        ));
+    }
+
+    let mut value: f64 = parse_number(word, "double")?;
+
+    if center && !word.contains('.') {
+        value += 0.5;
+    }
+
+    Ok(Coordinate {
+        value,
+        relative: false,
+    })
+}
+
+/// Reads a single word or a phrase in single or double quotes, in which `\` escapes the next
+/// character. Returns the string and the number of bytes read.
+fn read_quotable(input: &str) -> Result<(String, usize), CommandError> {
+    let quote = match input.chars().next() {
+        Some(c @ ('"' | '\'')) => c,
+        _ => {
+            let word = read_word(input);
+
+            return Ok((word.to_string(), word.len()));
+        }
+    };
+
+    let mut value = String::new();
+    let mut escaped = false;
+
+    for (i, c) in input.char_indices().skip(1) {
+        if escaped {
+            if c != quote && c != '\\' {
+                return Err(CommandError::InvalidArgument(format!(
+                    "Invalid escape sequence '\\{}' in quoted string",
+                    c
+                )));
+            }
+
+            value.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Ok((value, i + 1));
+        } else {
+            value.push(c);
+        }
+    }
+
+    Err(CommandError::InvalidArgument(
+        "Unclosed quoted string".to_string(),
+    ))
+}
@@ -0,0 +1,261 @@
+use std::{sync::Arc, time::Duration};
+
+use arrow_protocol::packets::types::StringKind;
+
+use super::{
+    argument, argument::ArgumentType, literal, CommandContext, CommandDispatcher, CommandError,
+    CommandNode, CommandResult, COMMANDS,
+};
+use crate::{player::Player, server::SERVER, time::TICKS_PER_DAY};
+
+/// The reason shown to players kicked without one.
+const DEFAULT_KICK_REASON: &str = "Kicked by an operator";
+
+/// Registers `/help`, `/list`, `/kick`, `/stop`, `/time`, `/tp` and `/tps`.
+pub fn register(dispatcher: &mut CommandDispatcher) {
+    dispatcher.register(help());
+    dispatcher.register(list());
+    dispatcher.register(kick());
+    dispatcher.register(stop());
+    dispatcher.register(time());
+    dispatcher.register(tp());
+    dispatcher.register(tps());
+}
+
+/// `/help [<command>]` lists the usage of all commands or of a single one.
+fn help() -> CommandNode {
+    literal("help").executes(send_usage).then(
+        argument("command", ArgumentType::String(StringKind::SingleWord)).executes(send_usage),
+    )
+}
+
+async fn send_usage(context: CommandContext) -> CommandResult {
+    let operator = context.source().is_operator();
+    let usage = COMMANDS
+        .read()
+        .await
+        .usage(context.string("command"), operator);
+
+    if usage.is_empty() {
+        return Err(CommandError::UnknownCommand);
+    }
+
+    for line in usage {
+        context.source().send_message(line);
+    }
+
+    Ok(())
+}
+
+/// `/list` shows the names of all online players.
+fn list() -> CommandNode {
+    literal("list").executes(|context: CommandContext| async move {
+        let names = SERVER.player_names();
+        let max = SERVER.get_max_online_player_count();
+
+        context.source().send_message(format!(
+            "There are {} of a max of {} players online: {}",
+            names.len(),
+            max,
+            names.join(", ")
+        ));
+
+        Ok(())
+    })
+}
+
+/// `/kick <player> [<reason>]` disconnects a player.
+fn kick() -> CommandNode {
+    literal("kick").requires_operator().then(
+        argument("player", ArgumentType::Player)
+            .executes(kick_player)
+            .then(
+                argument("reason", ArgumentType::String(StringKind::GreedyPhrase))
+                    .executes(kick_player),
+            ),
+    )
+}
+
+async fn kick_player(context: CommandContext) -> CommandResult {
+    let player = find_player(context.player("player").unwrap_or_default())?;
+    let reason = context.string("reason").unwrap_or(DEFAULT_KICK_REASON);
+
+    player.kick(reason);
+    context
+        .source()
+        .send_message(format!("Kicked {}: {}", player.name(), reason));
+
+    Ok(())
+}
+
+/// `/stop` stops the server.
+fn stop() -> CommandNode {
+    literal("stop")
+        .requires_operator()
+        .executes(|context: CommandContext| async move {
+            context.source().send_message("Stopping the server");
+            SERVER.stop();
+
+            Ok(())
+        })
+}
+
+/// `/time set|add <time>` changes the time of day, `/time query daytime|gametime|day` shows
+/// it. The time can also be set to `day`, `noon`, `night` or `midnight`.
+fn time() -> CommandNode {
+    let time = || {
+        argument(
+            "time",
+            ArgumentType::Integer {
+                min: Some(0),
+                max: None,
+            },
+        )
+    };
+    let set_to = |time: i64| move |context: CommandContext| set_time(context, Some(time));
+    let query = |query: Query| move |context: CommandContext| query_time(context, query);
+
+    literal("time")
+        .requires_operator()
+        .then(
+            literal("set")
+                .then(literal("day").executes(set_to(1000)))
+                .then(literal("noon").executes(set_to(6000)))
+                .then(literal("night").executes(set_to(13000)))
+                .then(literal("midnight").executes(set_to(18000)))
+                .then(time().executes(|context| set_time(context, None))),
+        )
+        .then(literal("add").then(time().executes(add_time)))
+        .then(
+            literal("query")
+                .then(literal("daytime").executes(query(Query::DayTime)))
+                .then(literal("gametime").executes(query(Query::GameTime)))
+                .then(literal("day").executes(query(Query::Day))),
+        )
+}
+
+/// The values `/time query` can show.
+#[derive(Clone, Copy)]
+enum Query {
+    DayTime,
+    GameTime,
+    Day,
+}
+
+/// Sets the time of day to `time` or the `time` argument.
+async fn set_time(context: CommandContext, time: Option<i64>) -> CommandResult {
+    let time = time.unwrap_or_else(|| context.integer("time").unwrap_or_default() as i64);
+
+    SERVER.time().set_time_of_day(time);
+    SERVER.broadcast(SERVER.time().packet());
+    context
+        .source()
+        .send_message(format!("Set the time to {}", time));
+
+    Ok(())
+}
+
+async fn add_time(context: CommandContext) -> CommandResult {
+    let ticks = context.integer("time").unwrap_or_default() as i64;
+    let time = SERVER.time().add_time(ticks);
+
+    SERVER.broadcast(SERVER.time().packet());
+    context
+        .source()
+        .send_message(format!("Set the time to {}", time % TICKS_PER_DAY));
+
+    Ok(())
+}
+
+async fn query_time(context: CommandContext, query: Query) -> CommandResult {
+    let time = SERVER.time();
+    let value = match query {
+        Query::DayTime => time.time_of_day() % TICKS_PER_DAY,
+        Query::GameTime => time.age() % i32::MAX as i64,
+        Query::Day => time.time_of_day() / TICKS_PER_DAY % i32::MAX as i64,
+    };
+
+    context
+        .source()
+        .send_message(format!("The time is {}", value));
+
+    Ok(())
+}
+
+/// `/tp [<target>] <location>` and `/tp [<target>] <destination>` teleport the target, the
+/// source by default, to a position or to another player.
+fn tp() -> CommandNode {
+    literal("tp")
+        .requires_operator()
+        .then(argument("location", ArgumentType::Position).executes(teleport))
+        .then(argument("destination", ArgumentType::Player).executes(teleport))
+        .then(
+            argument("target", ArgumentType::Player)
+                .then(argument("location", ArgumentType::Position).executes(teleport))
+                .then(argument("destination", ArgumentType::Player).executes(teleport)),
+        )
+}
+
+async fn teleport(context: CommandContext) -> CommandResult {
+    let target = match context.player("target") {
+        Some(name) => find_player(name)?,
+        None => context
+            .source()
+            .player()
+            .cloned()
+            .ok_or(CommandError::PlayerRequired)?,
+    };
+
+    let (position, destination) = match context.position("location") {
+        Some(location) => (location.resolve(context.source().position()), None),
+        None => {
+            let destination = find_player(context.player("destination").unwrap_or_default())?;
+
+            (destination.position(), Some(destination.name().clone()))
+        }
+    };
+
+    target.teleport(position);
+
+    let message = match destination {
+        Some(destination) => format!("Teleported {} to {}", target.name(), destination),
+        None => format!(
+            "Teleported {} to {:.2}, {:.2}, {:.2}",
+            target.name(),
+            position.0,
+            position.1,
+            position.2
+        ),
+    };
+
+    context.source().send_message(message);
+
+    Ok(())
+}
+
+/// `/tps` shows the ticks per second of the last 5 seconds and minute and the milliseconds per
+/// tick.
+fn tps() -> CommandNode {
+    literal("tps").executes(|context: CommandContext| async move {
+        let (mspt, max_mspt) = SERVER.mspt();
+
+        context.source().send_message(format!(
+            "TPS from last 5s, 1m: {:.1}, {:.1}",
+            SERVER.tps(Duration::from_secs(5)),
+            SERVER.tps(Duration::from_secs(60))
+        ));
+        context.source().send_message(format!(
+            "Milliseconds per tick: {:.2} (max {:.2})",
+            mspt, max_mspt
+        ));
+
+        Ok(())
+    })
+}
+
+/// Returns the online player called `name`.
+fn find_player(name: &str) -> Result<Arc<Player>, CommandError> {
+    SERVER
+        .player_by_name(name)
+        .ok_or_else(|| CommandError::PlayerNotFound(name.to_string()))
+}
@@ -0,0 +1,792 @@
+/// The argument types of command nodes.
+pub mod argument;
+/// The commands built into the server.
+pub mod builtin;
+
+use std::{collections::HashMap, fmt::Display, future::Future, sync::Arc};
+
+use arrow_protocol::{
+    chat::{ChatComponent, Color},
+    packets::{
+        types::{ChatPosition, CommandNodeKind},
+        PacketKind,
+    },
+};
+use futures::future::BoxFuture;
+use log::info;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::player::Player;
+use argument::{read_word, starts_with_ignore_case, ArgumentType, ArgumentValue, Coordinates};
+
+/// The commands of the server.
+pub static COMMANDS: RwLock<CommandDispatcher> = RwLock::const_new(CommandDispatcher::new());
+
+/// The result of executing a command.
+pub type CommandResult = std::result::Result<(), CommandError>;
+
+type Handler = Arc<dyn Fn(CommandContext) -> BoxFuture<'static, CommandResult> + Send + Sync>;
+
+/// The argument parsed by a node, if it is an argument node, and the number of bytes read.
+type ParsedNode = (Option<(String, ArgumentValue)>, usize);
+
+/// The error for commands that could not be parsed or failed executing. It is shown to the
+/// source of the command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    /// No command with the given name exists.
+    UnknownCommand,
+    /// The arguments do not match any usage of the command.
+    IncorrectArgument,
+    /// An argument could not be parsed.
+    InvalidArgument(String),
+    /// No player with the given name is online.
+    PlayerNotFound(String),
+    /// The command can only be executed by players.
+    PlayerRequired,
+}
+
+/// Who executes a command.
+#[derive(Clone)]
+pub enum CommandSource {
+    /// A player sending the command in chat.
+    Player(Arc<Player>),
+    /// The server console.
+    Console,
+}
+
+/// The source and the parsed arguments passed to the handler of a command.
+pub struct CommandContext {
+    source: CommandSource,
+    arguments: HashMap<String, ArgumentValue>,
+}
+
+/// A node of the command tree. Create one using [`literal`] or [`argument`].
+pub struct CommandNode {
+    kind: NodeKind,
+    children: Vec<CommandNode>,
+    handler: Option<Handler>,
+    operator_only: bool,
+}
+
+enum NodeKind {
+    Root,
+    Literal(String),
+    Argument { name: String, ty: ArgumentType },
+}
+
+/// Parses and executes commands and declares them to clients.
+pub struct CommandDispatcher {
+    root: CommandNode,
+}
+
+/// Returns a node matching the word `name`.
+pub fn literal(name: &str) -> CommandNode {
+    CommandNode::new(NodeKind::Literal(name.to_string()))
+}
+
+/// Returns a node parsing an argument of type `ty` called `name`.
+pub fn argument(name: &str, ty: ArgumentType) -> CommandNode {
+    CommandNode::new(NodeKind::Argument {
+        name: name.to_string(),
+        ty,
+    })
+}
+
+/// Executes `input`, a command without the leading `/`, for `source`. Errors are sent to the
+/// source.
+pub async fn execute(source: CommandSource, input: &str) {
+    let operator = source.is_operator();
+    // The handler may use the dispatcher itself, so the lock must not be held while it runs.
+    let parsed = COMMANDS.read().await.parse(input, operator);
+    let result = match parsed {
+        Ok((handler, arguments)) => {
+            handler(CommandContext {
+                source: source.clone(),
+                arguments,
+            })
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        source.send_message(ChatComponent::text(e.to_string()).with_color(Color::Red));
+    }
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownCommand => write!(f, "Unknown command. Type \"/help\" for help."),
+            Self::IncorrectArgument => write!(f, "Incorrect argument for command"),
+            Self::InvalidArgument(m) => write!(f, "{}", m),
+            Self::PlayerNotFound(name) => write!(f, "No player named {} was found", name),
+            Self::PlayerRequired => write!(f, "A player is required to run this command here"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl CommandSource {
+    /// Returns the name of the source. The console is called `Server`.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Player(player) => player.name().clone(),
+            Self::Console => "Server".to_string(),
+        }
+    }
+
+    /// Returns whether the source may use commands restricted to operators.
+    pub fn is_operator(&self) -> bool {
+        match self {
+            Self::Player(player) => player.is_operator(),
+            Self::Console => true,
+        }
+    }
+
+    /// Returns the position relative coordinates are resolved against.
+    pub fn position(&self) -> (f64, f64, f64) {
+        match self {
+            Self::Player(player) => player.position(),
+            Self::Console => (0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Returns the player if the source is one.
+    pub fn player(&self) -> Option<&Arc<Player>> {
+        match self {
+            Self::Player(player) => Some(player),
+            Self::Console => None,
+        }
+    }
+
+    /// Sends `message` to the source. Messages to the console are logged.
+    pub fn send_message<M: Into<ChatComponent>>(&self, message: M) {
+        let message = message.into();
+
+        match self {
+            Self::Player(player) => player.send_packet(PacketKind::ClientboundChatMessage {
+                message,
+                position: ChatPosition::System,
+                sender: Uuid::nil(),
+            }),
+            Self::Console => info!("{}", message),
+        }
+    }
+}
+
+impl CommandContext {
+    /// Returns the source executing the command.
+    pub fn source(&self) -> &CommandSource {
+        &self.source
+    }
+
+    /// Returns the argument `name` if it was given.
+    pub fn argument(&self, name: &str) -> Option<&ArgumentValue> {
+        self.arguments.get(name)
+    }
+
+    /// Returns the bool argument `name` if it was given.
+    pub fn bool(&self, name: &str) -> Option<bool> {
+        match self.argument(name) {
+            Some(ArgumentValue::Bool(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer argument `name` if it was given.
+    pub fn integer(&self, name: &str) -> Option<i32> {
+        match self.argument(name) {
+            Some(ArgumentValue::Integer(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the double argument `name` if it was given.
+    pub fn double(&self, name: &str) -> Option<f64> {
+        match self.argument(name) {
+            Some(ArgumentValue::Double(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the string argument `name` if it was given.
+    pub fn string(&self, name: &str) -> Option<&str> {
+        match self.argument(name) {
+            Some(ArgumentValue::String(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the player name argument `name` if it was given.
+    pub fn player(&self, name: &str) -> Option<&str> {
+        match self.argument(name) {
+            Some(ArgumentValue::Player(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the position argument `name` if it was given.
+    pub fn position(&self, name: &str) -> Option<Coordinates> {
+        match self.argument(name) {
+            Some(ArgumentValue::Position(value)) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl CommandNode {
+    const fn new(kind: NodeKind) -> Self {
+        Self {
+            kind,
+            children: Vec::new(),
+            handler: None,
+            operator_only: false,
+        }
+    }
+
+    /// Adds `child` to the nodes following this one.
+    pub fn then(mut self, child: CommandNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Makes the command executable at this node, running `handler`.
+    pub fn executes<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(CommandContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = CommandResult> + Send + 'static,
+    {
+        self.handler = Some(Arc::new(move |context| Box::pin(handler(context))));
+        self
+    }
+
+    /// Restricts this node and all following ones to operators. Other sources neither see nor
+    /// execute them.
+    pub fn requires_operator(mut self) -> Self {
+        self.operator_only = true;
+        self
+    }
+
+    /// Returns the children visible to a source that is an `operator` or not.
+    fn visible_children(&self, operator: bool) -> impl Iterator<Item = &CommandNode> {
+        self.children
+            .iter()
+            .filter(move |c| operator || !c.operator_only)
+    }
+
+    /// Parses this node at the start of `input`. Returns the parsed argument and the number of
+    /// bytes read, or `None` if a literal does not match.
+    fn parse(&self, input: &str) -> Option<Result<ParsedNode, CommandError>> {
+        match &self.kind {
+            NodeKind::Root => None,
+            NodeKind::Literal(literal) => {
+                if read_word(input) == literal {
+                    Some(Ok((None, literal.len())))
+                } else {
+                    None
+                }
+            }
+            NodeKind::Argument { name, ty } => Some(
+                ty.parse(input)
+                    .map(|(value, read)| (Some((name.clone(), value)), read)),
+            ),
+        }
+    }
+
+    /// Returns how this node is shown in the usage of a command.
+    fn usage(&self) -> String {
+        match &self.kind {
+            NodeKind::Root => String::new(),
+            NodeKind::Literal(literal) => literal.clone(),
+            NodeKind::Argument { name, .. } => format!("<{}>", name),
+        }
+    }
+}
+
+impl CommandDispatcher {
+    /// Creates a new [`CommandDispatcher`] without any commands.
+    pub const fn new() -> Self {
+        Self {
+            root: CommandNode::new(NodeKind::Root),
+        }
+    }
+
+    /// Registers `command`, which should be a [`literal`] node. A command with the same name is
+    /// replaced.
+    pub fn register(&mut self, command: CommandNode) {
+        let name = command.usage();
+
+        self.root.children.retain(|c| c.usage() != name);
+        self.root.children.push(command);
+    }
+
+    /// Parses `input` for a source that is an `operator` or not. Returns the handler to run and
+    /// the parsed arguments.
+    fn parse(
+        &self,
+        input: &str,
+        operator: bool,
+    ) -> Result<(Handler, HashMap<String, ArgumentValue>), CommandError> {
+        let mut arguments = HashMap::new();
+        let handler = Self::parse_node(&self.root, input, 0, operator, &mut arguments)?;
+
+        Ok((handler.clone(), arguments))
+    }
+
+    fn parse_node<'a>(
+        node: &'a CommandNode,
+        input: &str,
+        cursor: usize,
+        operator: bool,
+        arguments: &mut HashMap<String, ArgumentValue>,
+    ) -> Result<&'a Handler, CommandError> {
+        if cursor == input.len() {
+            return node.handler.as_ref().ok_or(CommandError::IncorrectArgument);
+        }
+
+        let mut error = None;
+
+        for child in node.visible_children(operator) {
+            let (argument, read) = match child.parse(&input[cursor..]) {
+                Some(Ok(parsed)) => parsed,
+                Some(Err(e)) => {
+                    error.get_or_insert(e);
+                    continue;
+                }
+                None => continue,
+            };
+            let end = cursor + read;
+
+            if end < input.len() && !input[end..].starts_with(' ') {
+                error.get_or_insert(CommandError::IncorrectArgument);
+                continue;
+            }
+
+            let name = argument.map(|(name, value)| {
+                arguments.insert(name.clone(), value);
+                name
+            });
+
+            match Self::parse_node(
+                child,
+                input,
+                (end + 1).min(input.len()),
+                operator,
+                arguments,
+            ) {
+                Ok(handler) => return Ok(handler),
+                Err(e) => {
+                    error.get_or_insert(e);
+
+                    if let Some(name) = name {
+                        arguments.remove(&name);
+                    }
+                }
+            }
+        }
+
+        Err(error.unwrap_or(match node.kind {
+            NodeKind::Root => CommandError::UnknownCommand,
+            _ => CommandError::IncorrectArgument,
+        }))
+    }
+
+    /// Returns the completions of the last word of `input` for a source that is an `operator`
+    /// or not, together with the byte index the word starts at. `players` are the names of all
+    /// online players.
+    pub fn suggest(&self, input: &str, operator: bool, players: &[String]) -> (usize, Vec<String>) {
+        let mut suggestions = vec![];
+
+        Self::suggest_node(&self.root, input, 0, operator, players, &mut suggestions);
+
+        let start = suggestions
+            .iter()
+            .map(|(s, _)| *s)
+            .max()
+            .unwrap_or(input.len());
+        let mut matches: Vec<_> = suggestions
+            .into_iter()
+            .filter(|(s, _)| *s == start)
+            .map(|(_, m)| m)
+            .collect();
+
+        matches.sort();
+        matches.dedup();
+
+        (start, matches)
+    }
+
+    fn suggest_node(
+        node: &CommandNode,
+        input: &str,
+        cursor: usize,
+        operator: bool,
+        players: &[String],
+        suggestions: &mut Vec<(usize, String)>,
+    ) {
+        let rest = &input[cursor..];
+
+        for child in node.visible_children(operator) {
+            if let Some(Ok((_, read))) = child.parse(rest) {
+                if rest[read..].starts_with(' ') {
+                    Self::suggest_node(
+                        child,
+                        input,
+                        cursor + read + 1,
+                        operator,
+                        players,
+                        suggestions,
+                    );
+                    continue;
+                }
+            }
+
+            match &child.kind {
+                NodeKind::Literal(literal) if starts_with_ignore_case(literal, rest) => {
+                    suggestions.push((cursor, literal.clone()))
+                }
+                NodeKind::Argument { ty, .. } => {
+                    suggestions.extend(ty.suggest(rest, players).into_iter().map(|s| (cursor, s)))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the usage of every command, or only of `command` if given, that a source which is
+    /// an `operator` or not can execute. Every executable path is listed on its own.
+    pub fn usage(&self, command: Option<&str>, operator: bool) -> Vec<String> {
+        let mut usage = vec![];
+
+        for child in self.root.visible_children(operator) {
+            match command {
+                Some(command) if command != child.usage() => {}
+                _ => Self::collect_usage(child, "/", operator, &mut usage),
+            }
+        }
+
+        usage.sort();
+        usage
+    }
+
+    fn collect_usage(node: &CommandNode, prefix: &str, operator: bool, usage: &mut Vec<String>) {
+        let path = format!("{}{}", prefix, node.usage());
+
+        if node.handler.is_some() {
+            usage.push(path.clone());
+        }
+
+        for child in node.visible_children(operator) {
+            Self::collect_usage(child, &format!("{} ", path), operator, usage);
+        }
+    }
+
+    /// Returns the [`DeclareCommands`](PacketKind::DeclareCommands) packet with the commands
+    /// visible to a source that is an `operator` or not. It gives 1.13+ clients tab-completion
+    /// and syntax highlighting.
+    pub fn declare_commands(&self, operator: bool) -> PacketKind {
+        let mut nodes = vec![];
+
+        Self::flatten(&self.root, operator, &mut nodes);
+
+        PacketKind::DeclareCommands {
+            nodes,
+            root_index: 0,
+        }
+    }
+
+    /// Appends `node` and all its visible children to `nodes`. Returns the index of `node`.
+    fn flatten(
+        node: &CommandNode,
+        operator: bool,
+        nodes: &mut Vec<arrow_protocol::packets::types::CommandNode>,
+    ) -> i32 {
+        let (kind, suggestions) = match &node.kind {
+            NodeKind::Root => (CommandNodeKind::Root, None),
+            NodeKind::Literal(literal) => (CommandNodeKind::Literal(literal.clone()), None),
+            NodeKind::Argument { name, ty } => (
+                CommandNodeKind::Argument {
+                    name: name.clone(),
+                    parser: ty.parser(),
+                },
+                ty.suggestions(),
+            ),
+        };
+        let index = nodes.len();
+
+        nodes.push(arrow_protocol::packets::types::CommandNode {
+            kind,
+            executable: node.handler.is_some(),
+            children: vec![],
+            redirect: None,
+            suggestions,
+        });
+
+        let children = node
+            .visible_children(operator)
+            .map(|c| Self::flatten(c, operator, nodes))
+            .collect();
+
+        nodes[index].children = children;
+
+        index as i32
+    }
+}
+
+impl Default for CommandDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_protocol::packets::types::{self, ArgumentParser};
+
+    use super::*;
+
+    /// Returns a handler failing with `name`, so that tests can tell which node was executed.
+    fn fails_with(
+        name: &'static str,
+    ) -> impl Fn(CommandContext) -> BoxFuture<'static, CommandResult> + Send + Sync {
+        move |_| Box::pin(async move { Err(CommandError::InvalidArgument(name.to_string())) })
+    }
+
+    /// Returns a dispatcher with `/give <player> <count>`, `/tp spawn`, `/tp <target>`,
+    /// `/tp <position>` and the operator command `/stop`.
+    fn dispatcher() -> CommandDispatcher {
+        let mut dispatcher = CommandDispatcher::new();
+
+        dispatcher.register(
+            literal("give").then(
+                argument("player", ArgumentType::Player).then(
+                    argument(
+                        "count",
+                        ArgumentType::Integer {
+                            min: Some(1),
+                            max: None,
+                        },
+                    )
+                    .executes(fails_with("give")),
+                ),
+            ),
+        );
+        dispatcher.register(
+            literal("tp")
+                .then(literal("spawn").executes(fails_with("spawn")))
+                .then(argument("target", ArgumentType::Player).executes(fails_with("target")))
+                .then(
+                    argument("position", ArgumentType::Position).executes(fails_with("position")),
+                ),
+        );
+        dispatcher.register(
+            literal("stop")
+                .requires_operator()
+                .executes(fails_with("stop")),
+        );
+
+        dispatcher
+    }
+
+    /// Parses `input` and runs its handler. Returns the name the handler fails with and the
+    /// parsed arguments.
+    async fn run(
+        input: &str,
+        operator: bool,
+    ) -> Result<(String, HashMap<String, ArgumentValue>), CommandError> {
+        let (handler, arguments) = dispatcher().parse(input, operator)?;
+        let context = CommandContext {
+            source: CommandSource::Console,
+            arguments: arguments.clone(),
+        };
+
+        match handler(context).await {
+            Err(CommandError::InvalidArgument(name)) => Ok((name, arguments)),
+            other => panic!("Unexpected result {:?} of {}", other, input),
+        }
+    }
+
+    fn players() -> Vec<String> {
+        vec!["Steve".to_string(), "alex".to_string()]
+    }
+
+    #[tokio::test]
+    async fn parse_backtracks_to_matching_node() {
+        let (name, arguments) = run("tp spawn", false).await.unwrap();
+
+        assert_eq!(name, "spawn");
+        assert!(arguments.is_empty());
+
+        let (name, arguments) = run("tp Steve", false).await.unwrap();
+
+        assert_eq!(name, "target");
+        assert_eq!(
+            arguments.get("target"),
+            Some(&ArgumentValue::Player("Steve".to_string()))
+        );
+
+        // `1` is a valid player name too, but `<target>` can't be followed by more arguments.
+        let (name, arguments) = run("tp 1 ~2 3", false).await.unwrap();
+
+        assert_eq!(name, "position");
+        assert_eq!(arguments.len(), 1);
+        assert!(matches!(
+            arguments.get("position"),
+            Some(ArgumentValue::Position(Coordinates { x, y, .. }))
+                if x.value == 1.5 && !x.relative && y.value == 2.0 && y.relative
+        ));
+
+        let (name, arguments) = run("give Steve 64", false).await.unwrap();
+
+        assert_eq!(name, "give");
+        assert_eq!(arguments.get("count"), Some(&ArgumentValue::Integer(64)));
+    }
+
+    #[tokio::test]
+    async fn parse_errors() {
+        assert_eq!(run("foo", true).await, Err(CommandError::UnknownCommand));
+        assert_eq!(run("stop", false).await, Err(CommandError::UnknownCommand));
+        assert_eq!(run("stop", true).await.unwrap().0, "stop");
+        assert_eq!(run("tp", false).await, Err(CommandError::IncorrectArgument));
+        assert_eq!(
+            run("tp spawn now", false).await,
+            Err(CommandError::IncorrectArgument)
+        );
+        assert_eq!(
+            run("give Steve 0", false).await,
+            Err(CommandError::InvalidArgument(
+                "Integer must not be less than 1, found 0".to_string()
+            ))
+        );
+        assert_eq!(
+            run("give Steve", false).await,
+            Err(CommandError::IncorrectArgument)
+        );
+    }
+
+    #[test]
+    fn suggest_partial_input() {
+        let dispatcher = dispatcher();
+        let suggest = |input, operator| dispatcher.suggest(input, operator, &players());
+        let strings =
+            |strings: &[&str]| -> Vec<String> { strings.iter().map(|s| s.to_string()).collect() };
+
+        assert_eq!(suggest("", false), (0, strings(&["give", "tp"])));
+        assert_eq!(suggest("", true), (0, strings(&["give", "stop", "tp"])));
+        assert_eq!(suggest("G", false), (0, strings(&["give"])));
+        assert_eq!(suggest("tp", false), (0, strings(&["tp"])));
+        assert_eq!(
+            suggest("tp ", false),
+            (3, strings(&["Steve", "alex", "spawn", "~ ~ ~"]))
+        );
+        assert_eq!(suggest("tp s", false), (3, strings(&["Steve", "spawn"])));
+        assert_eq!(suggest("give A", false), (5, strings(&["alex"])));
+        assert_eq!(suggest("give alex ", false), (10, vec![]));
+        assert_eq!(suggest("foo ", false), (4, vec![]));
+    }
+
+    #[test]
+    fn usage() {
+        let dispatcher = dispatcher();
+
+        assert_eq!(
+            dispatcher.usage(None, false),
+            vec![
+                "/give <player> <count>",
+                "/tp <position>",
+                "/tp <target>",
+                "/tp spawn"
+            ]
+        );
+        assert_eq!(dispatcher.usage(Some("stop"), true), vec!["/stop"]);
+        assert!(dispatcher.usage(Some("stop"), false).is_empty());
+    }
+
+    #[test]
+    fn declared_nodes() {
+        let node = |kind, executable, children: &[i32]| types::CommandNode {
+            kind,
+            executable,
+            children: children.to_vec(),
+            redirect: None,
+            suggestions: None,
+        };
+        let literal = |name: &str, executable, children| {
+            node(
+                CommandNodeKind::Literal(name.to_string()),
+                executable,
+                children,
+            )
+        };
+        let argument = |name: &str, parser, executable, children| {
+            node(
+                CommandNodeKind::Argument {
+                    name: name.to_string(),
+                    parser,
+                },
+                executable,
+                children,
+            )
+        };
+        let player = |name| types::CommandNode {
+            suggestions: Some("minecraft:ask_server".to_string()),
+            ..argument(
+                name,
+                ArgumentParser::Entity {
+                    single: true,
+                    only_players: true,
+                },
+                false,
+                &[],
+            )
+        };
+
+        let mut nodes = vec![
+            node(CommandNodeKind::Root, false, &[1, 4]),
+            literal("give", false, &[2]),
+            types::CommandNode {
+                children: vec![3],
+                ..player("player")
+            },
+            argument(
+                "count",
+                ArgumentParser::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+                true,
+                &[],
+            ),
+            literal("tp", false, &[5, 6, 7]),
+            literal("spawn", true, &[]),
+            types::CommandNode {
+                executable: true,
+                ..player("target")
+            },
+            argument("position", ArgumentParser::Vec3, true, &[]),
+        ];
+
+        assert_eq!(
+            dispatcher().declare_commands(false),
+            PacketKind::DeclareCommands {
+                nodes: nodes.clone(),
+                root_index: 0,
+            }
+        );
+
+        nodes[0].children.push(8);
+        nodes.push(literal("stop", true, &[]));
+
+        assert_eq!(
+            dispatcher().declare_commands(true),
+            PacketKind::DeclareCommands {
+                nodes,
+                root_index: 0,
+            }
+        );
+    }
+}
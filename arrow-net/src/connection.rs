@@ -0,0 +1,212 @@
+use std::{sync::Arc, time::Duration};
+
+use arrow_codec::{
+    codec::McCodec,
+    error::{DecoderError, EncoderError},
+};
+use arrow_protocol::{chat::ChatComponent, packets::PacketKind};
+use futures::{SinkExt, TryStreamExt};
+use log::{error, info, warn};
+use parking_lot::Mutex;
+use tokio::{
+    net::tcp::{OwnedReadHalf, OwnedWriteHalf},
+    sync::{
+        mpsc::{self, error::TrySendError},
+        Notify,
+    },
+    time,
+};
+use tokio_util::codec::Framed;
+
+/// The number of packets that can be queued for a client. A client that falls this far behind
+/// is disconnected.
+pub const PACKET_QUEUE_CAPACITY: usize = 1024;
+/// The time the disconnect packet and closing the connection may take.
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// The reason shown to clients that could not keep up with their packet queue.
+const OVERFLOW_REASON: &str = "Your connection is too slow";
+
+/// A cloneable handle to queue packets for a client in the play state. Packets are encoded for
+/// the protocol version of the client.
+#[derive(Clone)]
+pub struct PacketSender {
+    sender: mpsc::Sender<PacketKind>,
+    shared: Arc<Shared>,
+}
+
+/// The receiving end of the queue of a [`PacketSender`], drained by the [`ClientWriter`].
+pub struct PacketQueue {
+    receiver: mpsc::Receiver<PacketKind>,
+    shared: Arc<Shared>,
+}
+
+/// The state shared by the [`PacketSender`]s and the [`PacketQueue`] of a client.
+struct Shared {
+    /// The reason of a disconnect requested past the queued packets.
+    disconnect_reason: Mutex<Option<ChatComponent>>,
+    /// Notified when the client should be disconnected.
+    disconnect: Notify,
+    /// Notified when the packets written so far should be flushed.
+    flush: Notify,
+}
+
+/// The half of a client connection the packets sent by the client are read from.
+pub struct ClientReader {
+    framed: Framed<OwnedReadHalf, McCodec>,
+}
+
+/// The half of a client connection the packets queued using a [`PacketSender`] are written to.
+pub struct ClientWriter {
+    framed: Framed<OwnedWriteHalf, McCodec>,
+    queue: PacketQueue,
+}
+
+/// Creates a packet queue holding at most [`PACKET_QUEUE_CAPACITY`] packets.
+pub fn packet_queue() -> (PacketSender, PacketQueue) {
+    let (sender, receiver) = mpsc::channel(PACKET_QUEUE_CAPACITY);
+    let shared = Arc::new(Shared {
+        disconnect_reason: Mutex::new(None),
+        disconnect: Notify::new(),
+        flush: Notify::new(),
+    });
+
+    (
+        PacketSender {
+            sender,
+            shared: shared.clone(),
+        },
+        PacketQueue { receiver, shared },
+    )
+}
+
+impl PacketSender {
+    /// Queues `packet` without waiting. If the queue is full the client can't keep up and is
+    /// disconnected.
+    pub fn send(&self, packet: PacketKind) {
+        match self.sender.try_send(packet) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                warn!("Packet queue overflowed, disconnecting the client.");
+                self.disconnect(OVERFLOW_REASON);
+            }
+            // The client is already gone.
+            Err(TrySendError::Closed(_)) => {}
+        }
+    }
+
+    /// Queues `packet`, waiting for space in the queue if it is full. Use this for large amounts
+    /// of packets that should slow down the sender instead of disconnecting a slow client.
+    pub async fn send_async(&self, packet: PacketKind) {
+        // The client is already gone if the queue was closed.
+        let _ = self.sender.send(packet).await;
+    }
+
+    /// Disconnects the client showing `reason`. Packets still in the queue are dropped. If the
+    /// client is disconnected multiple times, the first reason is shown.
+    pub fn disconnect<R: Into<ChatComponent>>(&self, reason: R) {
+        self.shared
+            .disconnect_reason
+            .lock()
+            .get_or_insert_with(|| reason.into());
+        self.shared.disconnect.notify_one();
+    }
+
+    /// Flushes the packets queued so far once they are written. This happens once per tick, so
+    /// all packets of a tick are sent together.
+    pub fn flush(&self) {
+        self.shared.flush.notify_one();
+    }
+
+    /// Returns whether the connection of the client was closed.
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+impl ClientReader {
+    pub(crate) fn new(framed: Framed<OwnedReadHalf, McCodec>) -> Self {
+        Self { framed }
+    }
+
+    /// Reads the next packet. Returns `None` once the client closed the connection.
+    pub async fn next_packet(&mut self) -> Result<Option<PacketKind>, DecoderError> {
+        self.framed.try_next().await
+    }
+}
+
+impl ClientWriter {
+    pub(crate) fn new(framed: Framed<OwnedWriteHalf, McCodec>, queue: PacketQueue) -> Self {
+        Self { framed, queue }
+    }
+
+    /// Writes the queued packets of the player `name` until it is disconnected, after which the
+    /// connection is closed. The written packets are only flushed when requested using
+    /// [`PacketSender::flush`] or once the write buffer is full.
+    pub async fn run(mut self, name: &str) {
+        let shared = self.queue.shared.clone();
+
+        let reason = loop {
+            let written = tokio::select! {
+                biased;
+                _ = shared.disconnect.notified() => break shared.disconnect_reason.lock().take(),
+                _ = shared.flush.notified() => self.flush(name).await,
+                packet = self.queue.receiver.recv() => match packet {
+                    Some(packet) => self.write(name, packet).await,
+                    None => Ok(false),
+                },
+            };
+
+            match written {
+                Ok(true) => {}
+                Ok(false) => break None,
+                Err(e) => {
+                    error!("Failed sending packet: {}", e.0);
+                    return;
+                }
+            }
+        };
+
+        let close = async {
+            if let Some(reason) = reason {
+                info!("Player {} was kicked: {}", name, reason);
+                self.framed.send(PacketKind::PlayDisconnect(reason)).await?;
+            }
+
+            self.framed.close().await
+        };
+
+        match time::timeout(DISCONNECT_TIMEOUT, close).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => error!("Failed closing connection: {}", e.0),
+            Err(_) => warn!("Closing the connection of {} timed out.", name),
+        }
+    }
+
+    /// Writes `packet` without flushing. Returns `false` once a Disconnect packet was written,
+    /// which is flushed right away.
+    async fn write(&mut self, name: &str, packet: PacketKind) -> Result<bool, EncoderError> {
+        if let PacketKind::PlayDisconnect(reason) = &packet {
+            info!("Player {} was kicked: {}", name, reason);
+            self.framed.send(packet).await?;
+            return Ok(false);
+        }
+
+        self.framed.feed(packet).await?;
+
+        Ok(true)
+    }
+
+    /// Writes the packets that are already queued and flushes. Returns `false` once a Disconnect
+    /// packet was written.
+    async fn flush(&mut self, name: &str) -> Result<bool, EncoderError> {
+        while let Ok(packet) = self.queue.receiver.try_recv() {
+            if !self.write(name, packet).await? {
+                return Ok(false);
+            }
+        }
+
+        self.framed.flush().await?;
+
+        Ok(true)
+    }
+}
@@ -12,6 +12,11 @@ pub enum NetError {
     InvalidStatus(i32),
     UnexpectedEof,
     UnexpectedPacket,
+    AuthenticationError(String),
+    IoError(String),
+    InvalidFavicon(String),
+    InvalidGenerator(String),
+    InvalidWorld(String),
 }
 
 impl Display for NetError {
@@ -23,6 +28,11 @@ impl Display for NetError {
             Self::InvalidStatus(status) => write!(f, "Invalid status {}", status),
             Self::UnexpectedEof => write!(f, "Unexpected eof"),
             Self::UnexpectedPacket => write!(f, "Unexpected packet"),
+            Self::AuthenticationError(m) => write!(f, "Failed authenticating: {}", m),
+            Self::IoError(m) => write!(f, "Failed reading or writing: {}", m),
+            Self::InvalidFavicon(m) => write!(f, "Invalid favicon: {}", m),
+            Self::InvalidGenerator(m) => write!(f, "Invalid world generator: {}", m),
+            Self::InvalidWorld(m) => write!(f, "Invalid world: {}", m),
         }
     }
 }
@@ -33,4 +43,16 @@ impl From<DecoderError> for NetError {
     }
 }
 
+impl From<std::io::Error> for NetError {
+    fn from(e: std::io::Error) -> Self {
+        Self::IoError(format!("{}", e))
+    }
+}
+
+impl From<nbt::Error> for NetError {
+    fn from(e: nbt::Error) -> Self {
+        Self::InvalidWorld(format!("{}", e))
+    }
+}
+
 impl Error for NetError {}
@@ -0,0 +1,127 @@
+use std::{io::ErrorKind, time::Duration};
+
+use log::debug;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time,
+};
+
+use crate::{
+    error::{NetError, Result},
+    server::SERVER,
+    settings::Settings,
+    version,
+};
+
+/// The protocol version sent to 1.4 - 1.6 clients. It never matches theirs, so the server is
+/// shown as incompatible.
+const LEGACY_PROTOCOL_VERSION: i32 = 127;
+/// The time to wait for the bytes following `0xFE` that tell the variants of the ping apart.
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
+/// The channel of the plugin message sent by 1.6 clients.
+const PING_HOST_CHANNEL: &str = "MC|PingHost";
+
+/// Answers the legacy server list ping of a client that sent `0xFE` as its first byte.
+///
+/// Beta 1.8 - 1.3 clients only send `0xFE`, 1.4 - 1.5 clients follow it with `0x01` and 1.6
+/// clients additionally send a `MC|PingHost` plugin message. All of them are answered with a
+/// `0xFF` kick packet containing the server list information before the connection is closed.
+pub async fn handle(mut socket: TcpStream, settings: &Settings) -> Result<()> {
+    if socket.read_u8().await? != 0xFE {
+        return Err(NetError::UnexpectedPacket);
+    }
+
+    let online = SERVER.get_online_player_count();
+    let max = SERVER.get_max_online_player_count();
+
+    let response = match read_optional_u8(&mut socket).await? {
+        None => {
+            debug!("Received beta 1.8 - 1.3 server list ping.");
+
+            // These clients use `§` as separator and can't display formatting codes.
+            let motd = settings.motd.to_plain_text().replace('§', "");
+
+            format!("{}§{}§{}", motd, online, max)
+        }
+        Some(0x01) => {
+            match read_optional_u8(&mut socket).await? {
+                None => debug!("Received 1.4 - 1.5 server list ping."),
+                Some(0xFA) => read_ping_host(&mut socket).await?,
+                Some(_) => return Err(NetError::UnexpectedPacket),
+            }
+
+            format!(
+                "§1\0{}\0{}\0{}\0{}\0{}",
+                LEGACY_PROTOCOL_VERSION,
+                version::range_name(&settings.version_range),
+                settings.motd.to_legacy(),
+                online,
+                max
+            )
+        }
+        Some(_) => return Err(NetError::UnexpectedPacket),
+    };
+
+    let mut packet = vec![0xFF];
+    let response: Vec<u16> = response.encode_utf16().collect();
+
+    packet.extend_from_slice(&(response.len() as u16).to_be_bytes());
+    for c in response {
+        packet.extend_from_slice(&c.to_be_bytes());
+    }
+
+    socket.write_all(&packet).await?;
+    socket.shutdown().await?;
+
+    Ok(())
+}
+
+/// Reads the next byte or returns `None` if the client sent nothing more.
+async fn read_optional_u8(socket: &mut TcpStream) -> Result<Option<u8>> {
+    match time::timeout(READ_TIMEOUT, socket.read_u8()).await {
+        Ok(Ok(b)) => Ok(Some(b)),
+        Ok(Err(e)) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Reads the `MC|PingHost` plugin message sent by 1.6 clients after `0xFE 0x01 0xFA`.
+async fn read_ping_host(socket: &mut TcpStream) -> Result<()> {
+    let read = async {
+        let channel = read_utf16(socket).await?;
+
+        if channel != PING_HOST_CHANNEL {
+            return Err(NetError::UnexpectedPacket);
+        }
+
+        let _length = socket.read_u16().await?;
+        let protocol_version = socket.read_u8().await?;
+        let host = read_utf16(socket).await?;
+        let port = socket.read_i32().await?;
+
+        debug!(
+            "Received 1.6 server list ping with protocol version {} for {}:{}.",
+            protocol_version, host, port
+        );
+
+        Ok(())
+    };
+
+    time::timeout(READ_TIMEOUT, read)
+        .await
+        .map_err(|_| NetError::UnexpectedEof)?
+}
+
+/// Reads a string prefixed by its length in UTF-16 code units.
+async fn read_utf16(socket: &mut TcpStream) -> Result<String> {
+    let length = socket.read_u16().await?;
+    let mut chars = Vec::with_capacity(length as usize);
+
+    for _ in 0..length {
+        chars.push(socket.read_u16().await?);
+    }
+
+    String::from_utf16(&chars).map_err(|e| NetError::IoError(format!("{}", e)))
+}
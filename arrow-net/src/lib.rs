@@ -1,32 +1,106 @@
 #[deny(missing_docs)]
 
+/// The online mode authentication of players.
+pub mod auth;
 /// A client of the Minecraft protocol.
 pub mod client;
+/// The commands players can execute.
+pub mod command;
+/// The read and write halves of client connections in the play state.
+pub mod connection;
 /// The error for errors when binding, accepting, reading and writing.
 pub mod error;
+/// The server list ping of clients older than 1.7.
+pub mod legacy_ping;
 /// A player in the play state.
 pub mod player;
+/// The registry of all online players.
+pub mod registry;
 /// The server that stores all players.
 pub mod server;
+/// The settings of the server.
+pub mod settings;
+/// The game loop running the server at a fixed rate of ticks.
+pub mod tick;
+/// The time of the world.
+pub mod time;
+/// The protocol versions supported by Arrow.
+pub mod version;
+/// The chunks of the world and their generation.
+pub mod world;
 
-use log::{error, info};
-use tokio::net::TcpListener;
+use std::{sync::Arc, time::Duration};
+
+use log::{error, info, warn};
+use tokio::{
+    net::TcpListener,
+    time::{interval, sleep, Instant},
+};
 
 use error::{NetError, Result};
+use settings::Settings;
+use world::anvil::LevelData;
+
+/// The time stopping the server waits for the players to disconnect.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+/// The time between two saves of a stored world, which is the same as on vanilla servers.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Starts the server by taking the host name, the port and the [`Settings`] as arguments. Returns
+/// once the server was stopped using [`Server::stop`](server::Server::stop) and all players were
+/// disconnected.
+pub async fn start_server(host: &str, port: u16, settings: Settings) -> Result<()> {
+    let settings = Arc::new(settings);
+
+    server::SERVER.set_max_online_player_count(settings.max_players);
+    server::SERVER
+        .time()
+        .set_daylight_cycle(settings.do_daylight_cycle);
+    server::SERVER
+        .world()
+        .set_generator(settings.generator.clone());
+    server::SERVER.world().set_storage(settings.storage.clone());
+
+    if let Some(level) = settings
+        .storage
+        .as_ref()
+        .and_then(|storage| storage.level())
+    {
+        server::SERVER.time().set_age(level.time());
+        server::SERVER.time().set_time_of_day(level.day_time());
+
+        if let Some((x, y, z)) = level.spawn() {
+            server::SERVER
+                .world()
+                .set_spawn(Some((x as f64 + 0.5, y as f64, z as f64 + 0.5)));
+        }
+    }
+
+    command::builtin::register(&mut *command::COMMANDS.write().await);
 
-/// Starts the server by taking the host name and the port as arguments.
-pub async fn start_server(host: &str, port: u16) -> Result<()> {
     let listener = TcpListener::bind((host, port))
         .await
         .map_err(|e| NetError::ServerBindError(format!("{}", e)))?;
 
     info!("Started server on {}:{}.", host, port);
 
+    let ticker = tokio::spawn(tick::run());
+    let autosaver = tokio::spawn(autosave(settings.clone()));
+
     loop {
-        let (socket, ip) = listener
-            .accept()
-            .await
-            .map_err(|e| NetError::ClientAcceptError(format!("{}", e)))?;
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = server::SHUTDOWN.notified() => break,
+        };
+
+        // Accept errors (e.g. running out of file descriptors) only affect this connection.
+        let (socket, ip) = match accepted {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                error!("{}", NetError::ClientAcceptError(format!("{}", e)));
+                continue;
+            }
+        };
 
         info!(
             "Client with ip {} and port {} connected.",
@@ -34,21 +108,97 @@ pub async fn start_server(host: &str, port: u16) -> Result<()> {
             ip.port()
         );
 
+        let settings = settings.clone();
+
         tokio::spawn(async move {
             let mut buf = [0];
 
             socket.peek(&mut buf).await.unwrap();
 
-            match buf[0] {
-                0xfe => {
-                    error!("Implement legacy server ping.");
-                    return;
+            if buf[0] == 0xfe {
+                if let Err(e) = legacy_ping::handle(socket, &settings).await {
+                    error!("Failed answering legacy server list ping: {}", e);
                 }
-                _ => {}
+                return;
             }
 
-            let client = client::Client::new(socket);
+            let client = client::Client::new(socket, settings);
             client.connect().await;
         });
     }
+
+    drop(listener);
+
+    server::SERVER.kick_all(settings.shutdown_message.clone());
+
+    // Give the kicked players a moment to receive their disconnect packet.
+    let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+
+    while server::SERVER.get_online_player_count() > 0 && Instant::now() < deadline {
+        sleep(Duration::from_millis(50)).await;
+    }
+
+    let remaining = server::SERVER.get_online_player_count();
+
+    if remaining > 0 {
+        warn!("{} players did not disconnect in time.", remaining);
+    }
+
+    ticker.abort();
+    autosaver.abort();
+    save_world(&settings).await;
+
+    info!("Stopped server.");
+
+    Ok(())
+}
+
+/// Saves the world every [`AUTOSAVE_INTERVAL`].
+async fn autosave(settings: Arc<Settings>) {
+    let mut interval = interval(AUTOSAVE_INTERVAL);
+
+    // The first tick completes immediately.
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        save_world(&settings).await;
+    }
+}
+
+/// Saves the modified chunks and the `level.dat` of the world if it is stored. A new world gets
+/// a `level.dat` with the seed and the spawn of the settings.
+async fn save_world(settings: &Settings) {
+    let storage = match &settings.storage {
+        Some(storage) => storage,
+        None => return,
+    };
+
+    let world = server::SERVER.world();
+
+    match world.save().await {
+        Ok(count) => info!("Saved {} chunks.", count),
+        Err(e) => error!("Failed saving chunks: {}", e),
+    }
+
+    let time = server::SERVER.time();
+    let mut level = storage.level().unwrap_or_else(|| {
+        let spawn = world.spawn();
+
+        LevelData::new(
+            settings.seed,
+            (
+                spawn.0.floor() as i32,
+                spawn.1 as i32,
+                spawn.2.floor() as i32,
+            ),
+        )
+    });
+
+    level.set_time(time.age(), time.time_of_day());
+    level.set_game_rule("doDaylightCycle", &time.daylight_cycle().to_string());
+
+    if let Err(e) = storage.save_level(level) {
+        error!("Failed saving level.dat: {}", e);
+    }
 }
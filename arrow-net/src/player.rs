@@ -1,16 +1,61 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicI32, Ordering},
+    time::Duration,
+};
+
+use arrow_protocol::{chat::ChatComponent, packets::PacketKind};
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
-use crate::client::Client;
+use crate::connection::PacketSender;
+
+/// The flags of the Player Position And Look packet marking yaw and pitch as relative.
+const RELATIVE_ROTATION: i8 = 0x08 | 0x10;
 
+/// The number of received packets that can wait for the next tick. Reading from the client pauses
+/// while the queue is full.
+pub const INBOUND_QUEUE_CAPACITY: usize = 1024;
+
+/// A player in the play state. The state that changes while the player is online uses interior
+/// mutability, so players are shared as `Arc<Player>` without an outer lock.
 pub struct Player {
     uuid: Uuid,
     name: String,
-    client: Client,
+    entity_id: i32,
+    protocol_version: i32,
+    latency: Mutex<Duration>,
+    operator: AtomicBool,
+    position: Mutex<(f64, f64, f64)>,
+    next_teleport_id: AtomicI32,
+    packet_sender: PacketSender,
+    inbound_sender: mpsc::Sender<PacketKind>,
+    inbound: Mutex<mpsc::Receiver<PacketKind>>,
 }
 
 impl Player {
-    pub fn new(uuid: Uuid, name: String, client: Client) -> Self {
-        Self { uuid, name, client }
+    pub fn new(
+        uuid: Uuid,
+        name: String,
+        entity_id: i32,
+        protocol_version: i32,
+        packet_sender: PacketSender,
+    ) -> Self {
+        let (inbound_sender, inbound) = mpsc::channel(INBOUND_QUEUE_CAPACITY);
+
+        Self {
+            uuid,
+            name,
+            entity_id,
+            protocol_version,
+            latency: Mutex::new(Duration::ZERO),
+            operator: AtomicBool::new(false),
+            position: Mutex::new((0.0, 0.0, 0.0)),
+            next_teleport_id: AtomicI32::new(0),
+            packet_sender,
+            inbound_sender,
+            inbound: Mutex::new(inbound),
+        }
     }
 
     pub fn uuid(&self) -> &Uuid {
@@ -21,11 +66,98 @@ impl Player {
         &self.name
     }
 
-    pub fn client_mut(&mut self) -> &mut Client {
-        &mut self.client
+    /// Returns the id of the entity representing the player.
+    pub fn entity_id(&self) -> i32 {
+        self.entity_id
+    }
+
+    /// Returns the protocol version of the client of the player.
+    pub fn protocol_version(&self) -> i32 {
+        self.protocol_version
+    }
+
+    /// Returns the latency of the player measured using keep alives. This is the value shown in
+    /// the tab list.
+    pub fn latency(&self) -> Duration {
+        *self.latency.lock()
+    }
+
+    /// Updates the latency with a new measurement. Like vanilla servers, the new value is weighted
+    /// with a quarter against the previous latency to smooth out spikes.
+    pub fn update_latency(&self, latency: Duration) {
+        let mut current = self.latency.lock();
+
+        *current = (*current * 3 + latency) / 4;
+    }
+
+    /// Returns whether the player may use commands restricted to operators.
+    pub fn is_operator(&self) -> bool {
+        self.operator.load(Ordering::Relaxed)
+    }
+
+    pub fn set_operator(&self, operator: bool) {
+        self.operator.store(operator, Ordering::Relaxed);
+    }
+
+    /// Returns the position of the feet of the player as last reported by the client.
+    pub fn position(&self) -> (f64, f64, f64) {
+        *self.position.lock()
+    }
+
+    pub fn set_position(&self, position: (f64, f64, f64)) {
+        *self.position.lock() = position;
+    }
+
+    /// Teleports the player to `position`, keeping the direction the player looks in.
+    pub fn teleport(&self, position: (f64, f64, f64)) {
+        self.set_position(position);
+
+        self.send_packet(PacketKind::PlayerPositionAndLook {
+            x: position.0,
+            y: position.1,
+            z: position.2,
+            yaw: 0.0,
+            pitch: 0.0,
+            flags: RELATIVE_ROTATION,
+            teleport_id: self
+                .next_teleport_id
+                .fetch_add(1, Ordering::Relaxed)
+                .wrapping_add(1),
+        });
+    }
+
+    /// Returns the handle to queue packets for the client of the player.
+    pub fn packet_sender(&self) -> &PacketSender {
+        &self.packet_sender
     }
 
-    pub async fn recv(&mut self) {
-        self.client.recv().await;
+    /// Queues `packet` to be sent to the client of the player. It is encoded for the protocol
+    /// version of the client. The player is kicked if its client can't keep up.
+    pub fn send_packet(&self, packet: PacketKind) {
+        self.packet_sender.send(packet);
+    }
+
+    /// Kicks the player from the server showing `reason`.
+    pub fn kick<R: Into<ChatComponent>>(&self, reason: R) {
+        self.packet_sender.disconnect(reason);
+    }
+
+    /// Queues `packet` received from the client to be handled in the next tick. Waits while
+    /// [`INBOUND_QUEUE_CAPACITY`] packets are queued.
+    pub(crate) async fn queue_inbound(&self, packet: PacketKind) {
+        // The receiver lives as long as the player, so sending can't fail.
+        let _ = self.inbound_sender.send(packet).await;
+    }
+
+    /// Removes and returns the packets received from the client since the last tick.
+    pub(crate) fn take_inbound(&self) -> Vec<PacketKind> {
+        let mut inbound = self.inbound.lock();
+        let mut packets = Vec::new();
+
+        while let Ok(packet) = inbound.try_recv() {
+            packets.push(packet);
+        }
+
+        packets
     }
 }
@@ -0,0 +1,122 @@
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use dashmap::{mapref::entry::Entry, DashMap};
+use uuid::Uuid;
+
+use crate::player::Player;
+
+/// The online players indexed by uuid and entity id. Both maps are sharded, so lookups only lock
+/// a single shard for as long as it takes to clone the [`Arc`] of the player.
+pub struct PlayerRegistry {
+    by_uuid: DashMap<Uuid, Arc<Player>>,
+    by_entity_id: DashMap<i32, Arc<Player>>,
+    count: AtomicUsize,
+}
+
+/// The reason a player could not be added to the [`PlayerRegistry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterError {
+    /// A player with the same uuid is already online.
+    AlreadyConnected,
+    /// The maximum number of players is online.
+    Full,
+}
+
+impl PlayerRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            by_uuid: DashMap::new(),
+            by_entity_id: DashMap::new(),
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Adds `player` unless a player with the same uuid is online or `max` players are online
+    /// already. Both checks and the insertion happen atomically.
+    pub fn register(&self, player: Arc<Player>, max: usize) -> Result<(), RegisterError> {
+        let entry = match self.by_uuid.entry(*player.uuid()) {
+            Entry::Occupied(_) => return Err(RegisterError::AlreadyConnected),
+            Entry::Vacant(entry) => entry,
+        };
+
+        self.count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                if count < max {
+                    Some(count + 1)
+                } else {
+                    None
+                }
+            })
+            .map_err(|_| RegisterError::Full)?;
+
+        self.by_entity_id.insert(player.entity_id(), player.clone());
+        entry.insert(player);
+
+        Ok(())
+    }
+
+    /// Removes the player with `uuid` and returns it.
+    pub fn unregister(&self, uuid: &Uuid) -> Option<Arc<Player>> {
+        let (_, player) = self.by_uuid.remove(uuid)?;
+
+        self.by_entity_id.remove(&player.entity_id());
+        self.count.fetch_sub(1, Ordering::SeqCst);
+
+        Some(player)
+    }
+
+    /// Returns the player with `uuid`.
+    pub fn get(&self, uuid: &Uuid) -> Option<Arc<Player>> {
+        self.by_uuid.get(uuid).map(|p| p.value().clone())
+    }
+
+    /// Returns the player with the entity id `entity_id`.
+    pub fn get_by_entity_id(&self, entity_id: i32) -> Option<Arc<Player>> {
+        self.by_entity_id.get(&entity_id).map(|p| p.value().clone())
+    }
+
+    /// Returns whether a player with `uuid` is online.
+    pub fn contains(&self, uuid: &Uuid) -> bool {
+        self.by_uuid.contains_key(uuid)
+    }
+
+    /// Returns the number of online players.
+    pub fn len(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Returns whether no player is online.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns all online players. The shards are only locked while collecting them, so the
+    /// players can be used across `.await`s.
+    pub fn players(&self) -> Vec<Arc<Player>> {
+        self.by_uuid.iter().map(|p| p.value().clone()).collect()
+    }
+}
+
+impl Default for PlayerRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for RegisterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AlreadyConnected => write!(f, "You are already connected to this server!"),
+            Self::Full => write!(f, "The server is full!"),
+        }
+    }
+}
+
+impl std::error::Error for RegisterError {}
@@ -1,79 +1,221 @@
-use std::sync::Arc;
+use std::{
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
-use tokio::sync::RwLock;
+use arrow_protocol::{
+    chat::{ChatComponent, Color},
+    packets::{types::ChatPosition, PacketKind},
+};
+use log::info;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rand::seq::SliceRandom;
+use tokio::{sync::Notify, time::Instant};
 use uuid::Uuid;
 
-use crate::player::Player;
+use crate::{
+    client::Client,
+    player::Player,
+    registry::{PlayerRegistry, RegisterError},
+    tick::TickStats,
+    time::WorldTime,
+    world::{generator::VoidGenerator, World},
+};
 
-pub static SERVER: RwLock<Server> = RwLock::const_new(Server::new(100));
+pub static SERVER: Lazy<Server> = Lazy::new(|| Server::new(100));
 
+/// Notified when the server should stop accepting clients and shut down.
+pub static SHUTDOWN: Notify = Notify::const_new();
+
+/// The server that stores all players. All methods take `&self`, so the global [`SERVER`] is
+/// used without locking it.
 pub struct Server {
-    players: Vec<Arc<RwLock<Player>>>,
-    max_player_count: i32,
+    players: PlayerRegistry,
+    max_player_count: AtomicI32,
+    next_entity_id: AtomicI32,
+    tick_stats: Mutex<TickStats>,
+    time: WorldTime,
+    world: World,
 }
 
 impl Server {
-    pub const fn new(max_player_count: i32) -> Self {
+    pub fn new(max_player_count: i32) -> Self {
         Self {
-            players: vec![],
-            max_player_count,
+            players: PlayerRegistry::new(),
+            max_player_count: AtomicI32::new(max_player_count),
+            next_entity_id: AtomicI32::new(0),
+            tick_stats: Mutex::new(TickStats::new()),
+            time: WorldTime::new(true),
+            world: World::new(Arc::new(VoidGenerator::new(None))),
         }
     }
 
-    pub fn add_player(&mut self, player: Arc<RwLock<Player>>) {
-        let player_clone = player.clone();
-        tokio::spawn(async move {
-            player_clone.write().await.client_mut().join().await;
-            player_clone
-                .write()
-                .await
-                .client_mut()
-                .set_slot(0 as i8)
-                .await;
-        });
-        self.players.push(player);
+    /// Returns a new entity id that is not used by any other entity.
+    pub fn next_entity_id(&self) -> i32 {
+        self.next_entity_id.fetch_add(1, Ordering::Relaxed)
     }
 
-    pub async fn remove_player(&mut self, uuid: &Uuid) {
-        let mut idx = usize::MAX;
+    /// Returns the age and time of day of the world.
+    pub fn time(&self) -> &WorldTime {
+        &self.time
+    }
 
-        for (i, player) in self.players.iter().enumerate() {
-            if player.read().await.uuid() == uuid {
-                idx = i;
-            }
-        }
+    /// Returns the chunks of the world.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
 
-        if idx != usize::MAX {
-            self.players.remove(idx);
-        }
+    /// Registers `player` unless a player with the same uuid is online or the server is full.
+    pub fn register_player(&self, player: Arc<Player>) -> Result<(), RegisterError> {
+        let max = self.get_max_online_player_count().max(0) as usize;
+
+        self.players.register(player, max)
+    }
+
+    /// Spawns the task running the already registered `player` with its `client` until it
+    /// disconnects. The player is unregistered again afterwards.
+    pub fn add_player(&self, player: Arc<Player>, client: Client) {
+        tokio::spawn(async move {
+            let uuid = *player.uuid();
+            let name = player.name().clone();
+
+            SERVER.broadcast_message(
+                ChatComponent::translate("multiplayer.player.joined", vec![name.clone().into()])
+                    .with_color(Color::Yellow),
+            );
+
+            client.play(player).await;
+
+            SERVER.remove_player(&uuid);
+            SERVER.broadcast_message(
+                ChatComponent::translate("multiplayer.player.left", vec![name.into()])
+                    .with_color(Color::Yellow),
+            );
+        });
+    }
+
+    pub fn remove_player(&self, uuid: &Uuid) {
+        self.players.unregister(uuid);
     }
 
     pub fn get_max_online_player_count(&self) -> i32 {
+        self.max_player_count.load(Ordering::Relaxed)
+    }
+
+    pub fn set_max_online_player_count(&self, max_player_count: i32) {
         self.max_player_count
+            .store(max_player_count, Ordering::Relaxed);
     }
 
     pub fn get_online_player_count(&self) -> i32 {
         self.players.len() as i32
     }
 
-    pub async fn has_uuid(&self, uuid: &Uuid) -> bool {
-        for player in self.players.iter() {
-            if player.read().await.uuid() == uuid {
-                return true;
-            }
+    pub fn has_uuid(&self, uuid: &Uuid) -> bool {
+        self.players.contains(uuid)
+    }
+
+    /// Returns the online player with `uuid`.
+    pub fn player(&self, uuid: &Uuid) -> Option<Arc<Player>> {
+        self.players.get(uuid)
+    }
+
+    /// Returns the online player represented by the entity with `entity_id`.
+    pub fn player_by_entity_id(&self, entity_id: i32) -> Option<Arc<Player>> {
+        self.players.get_by_entity_id(entity_id)
+    }
+
+    /// Returns all online players.
+    pub fn players(&self) -> Vec<Arc<Player>> {
+        self.players.players()
+    }
+
+    /// Returns the names of all online players.
+    pub fn player_names(&self) -> Vec<String> {
+        self.players()
+            .iter()
+            .map(|player| player.name().clone())
+            .collect()
+    }
+
+    /// Returns the online player called `name`, ignoring the case.
+    pub fn player_by_name(&self, name: &str) -> Option<Arc<Player>> {
+        self.players()
+            .into_iter()
+            .find(|player| player.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Notifies [`SHUTDOWN`] making [`start_server`](crate::start_server) disconnect all players
+    /// and return.
+    pub fn stop(&self) {
+        info!("Stopping the server.");
+
+        SHUTDOWN.notify_one();
+    }
+
+    /// Kicks all online players showing `reason`.
+    pub fn kick_all(&self, reason: ChatComponent) {
+        for player in self.players() {
+            player.kick(reason.clone());
         }
+    }
+
+    /// Records a tick of the game loop that started at `start` and took `duration`.
+    pub(crate) fn record_tick(&self, start: Instant, duration: Duration) {
+        self.tick_stats.lock().record(start, duration);
+    }
 
-        return false;
+    /// Returns the average number of ticks per second over the last `period`, at most a minute.
+    pub fn tps(&self, period: Duration) -> f64 {
+        self.tick_stats.lock().tps(period)
     }
 
-    pub async fn recv(&self) {
-        for player in self.players.iter() {
-            let player = player.clone();
-            tokio::spawn(async move {
-                let mut player = player.write().await;
+    /// Returns the average and the maximum milliseconds per tick of the last 100 ticks.
+    pub fn mspt(&self) -> (f64, f64) {
+        let stats = self.tick_stats.lock();
 
-                player.recv().await;
-            });
+        (stats.mspt(), stats.max_mspt())
+    }
+
+    /// Returns the uuids and names of at most `max` randomly chosen online players.
+    pub fn player_sample(&self, max: usize) -> Vec<(Uuid, String)> {
+        self.players()
+            .choose_multiple(&mut rand::thread_rng(), max)
+            .map(|player| (*player.uuid(), player.name().clone()))
+            .collect()
+    }
+
+    /// Sends `packet` to every player. It is encoded for the protocol version of each client.
+    pub fn broadcast(&self, packet: PacketKind) {
+        for player in self.players() {
+            player.send_packet(packet.clone());
         }
     }
+
+    /// Sends the system `message` to every player.
+    pub fn broadcast_message(&self, message: ChatComponent) {
+        info!("{}", message);
+
+        self.broadcast(PacketKind::ClientboundChatMessage {
+            message,
+            position: ChatPosition::System,
+            sender: Uuid::nil(),
+        });
+    }
+
+    /// Sends the chat `message` of the player `name` to every player formatted as
+    /// `<name> message`.
+    pub fn broadcast_chat(&self, sender: &Uuid, name: &str, message: &str) {
+        info!("<{}> {}", name, message);
+
+        self.broadcast(PacketKind::ClientboundChatMessage {
+            message: ChatComponent::translate("chat.type.text", vec![name.into(), message.into()]),
+            position: ChatPosition::Chat,
+            sender: *sender,
+        });
+    }
 }
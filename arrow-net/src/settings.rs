@@ -0,0 +1,106 @@
+use std::{ops::RangeInclusive, sync::Arc, time::Duration};
+
+use arrow_protocol::{block::BlockRegistry, chat::ChatComponent};
+
+use crate::{
+    auth::Authenticator,
+    error::{NetError, Result},
+    version::SUPPORTED_PROTOCOL_VERSIONS,
+    world::{
+        anvil::AnvilWorld,
+        generator::{FlatGenerator, WorldGenerator, DEFAULT_LAYERS},
+    },
+};
+
+/// The signature every PNG file starts with.
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+/// The width and height of a favicon in pixels.
+const FAVICON_SIZE: u32 = 64;
+
+/// The settings used by the server and its clients.
+#[derive(Clone)]
+pub struct Settings {
+    /// The message of the day shown in the server list.
+    pub motd: ChatComponent,
+    /// The favicon shown in the server list as a `data:image/png;base64,` URI. Use
+    /// [`favicon_from_png`] to create it.
+    pub favicon: Option<String>,
+    /// The maximum number of players that can be online at the same time.
+    pub max_players: i32,
+    /// The compression threshold sent to clients during login. A negative value disables
+    /// compression.
+    pub compression_threshold: i32,
+    /// The authenticator used in online mode. Without one the server runs in offline mode.
+    pub authenticator: Option<Arc<Authenticator>>,
+    /// Derive offline mode uuids using the legacy scheme of earlier Arrow versions instead of
+    /// the one used by vanilla servers.
+    pub legacy_offline_uuids: bool,
+    /// The time a client has to answer a keep alive before it gets disconnected.
+    pub keep_alive_timeout: Duration,
+    /// The protocol versions clients are allowed to log in with. Should lie within
+    /// [`SUPPORTED_PROTOCOL_VERSIONS`].
+    pub version_range: RangeInclusive<i32>,
+    /// The names of the players allowed to use commands restricted to operators.
+    pub operators: Vec<String>,
+    /// The reason shown to the players disconnected when the server stops.
+    pub shutdown_message: ChatComponent,
+    /// Whether the time of day advances. The `doDaylightCycle` game rule of vanilla servers.
+    pub do_daylight_cycle: bool,
+    /// The block states of all supported protocol versions.
+    pub blocks: Arc<BlockRegistry>,
+    /// The generator of the chunks of the world. Its block states are canonical ids of the
+    /// `blocks`.
+    pub generator: Arc<dyn WorldGenerator>,
+    /// The seed of the world. Clients receive it hashed and use it for biome noise.
+    pub seed: i64,
+    /// The vanilla world chunks are loaded from and saved to. Its `level.dat` supplies the spawn
+    /// point and the time. Without one chunks are only kept in memory.
+    pub storage: Option<Arc<AnvilWorld>>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        let blocks = BlockRegistry::new();
+        // The default layers only use built-in blocks.
+        let generator = FlatGenerator::from_layers(DEFAULT_LAYERS, &blocks).unwrap();
+
+        Self {
+            motd: ChatComponent::text("Arrow - A minecraft server written in Rust"),
+            favicon: None,
+            max_players: 100,
+            compression_threshold: 256,
+            authenticator: None,
+            legacy_offline_uuids: false,
+            keep_alive_timeout: Duration::from_secs(30),
+            version_range: SUPPORTED_PROTOCOL_VERSIONS,
+            operators: vec![],
+            shutdown_message: ChatComponent::text("Server closed"),
+            do_daylight_cycle: true,
+            blocks: Arc::new(blocks),
+            generator: Arc::new(generator),
+            seed: rand::random(),
+            storage: None,
+        }
+    }
+}
+
+/// Validates that `png` is a 64x64 PNG image and encodes it as the favicon sent in the status
+/// response.
+pub fn favicon_from_png(png: &[u8]) -> Result<String> {
+    // The IHDR chunk is always first and starts with the width and height.
+    if png.len() < 24 || png[..8] != PNG_SIGNATURE || &png[12..16] != b"IHDR" {
+        return Err(NetError::InvalidFavicon("not a PNG image".to_string()));
+    }
+
+    let width = u32::from_be_bytes([png[16], png[17], png[18], png[19]]);
+    let height = u32::from_be_bytes([png[20], png[21], png[22], png[23]]);
+
+    if width != FAVICON_SIZE || height != FAVICON_SIZE {
+        return Err(NetError::InvalidFavicon(format!(
+            "expected {0}x{0} pixels, got {1}x{2}",
+            FAVICON_SIZE, width, height
+        )));
+    }
+
+    Ok(format!("data:image/png;base64,{}", base64::encode(png)))
+}
@@ -0,0 +1,212 @@
+use std::{collections::VecDeque, time::Duration};
+
+use log::warn;
+use tokio::time::{self, Instant};
+
+use crate::{client, server::SERVER};
+
+/// The number of ticks per second the server aims for.
+pub const TICKS_PER_SECOND: u32 = 20;
+/// The time between the start of two ticks.
+pub const TICK_DURATION: Duration = Duration::from_millis(1000 / TICKS_PER_SECOND as u64);
+
+/// How far the server may fall behind before skipping the missed ticks instead of catching up.
+const MAX_TICK_LAG: Duration = Duration::from_secs(2);
+/// The minimum time between two warnings about the server being overloaded.
+const OVERLOAD_WARNING_INTERVAL: Duration = Duration::from_secs(15);
+/// The number of ticks the milliseconds per tick are averaged over.
+const MSPT_SAMPLE_SIZE: usize = 100;
+/// The longest period the ticks per second can be measured over.
+const MAX_TPS_PERIOD: Duration = Duration::from_secs(60);
+/// The number of ticks between two Time Update packets sent to all players.
+const TIME_UPDATE_INTERVAL: i64 = TICKS_PER_SECOND as i64;
+
+/// The start times and durations of the recent ticks.
+pub struct TickStats {
+    starts: VecDeque<Instant>,
+    durations: VecDeque<Duration>,
+}
+
+/// Runs the game loop, ticking [`TICKS_PER_SECOND`] times per second until the task is aborted.
+///
+/// Like vanilla servers, ticks that were missed because a tick took too long are run back to
+/// back until the loop caught up. If it is more than [`MAX_TICK_LAG`] behind, the missed ticks
+/// are skipped and a warning is logged. Ticks taking longer than [`TICK_DURATION`] are warned
+/// about as well, at most once per [`OVERLOAD_WARNING_INTERVAL`] like falling behind.
+pub async fn run() {
+    let mut next_tick = Instant::now();
+    let mut last_warning: Option<Instant> = None;
+
+    loop {
+        time::sleep_until(next_tick).await;
+
+        let start = Instant::now();
+        let behind = start.saturating_duration_since(next_tick);
+
+        if behind > MAX_TICK_LAG {
+            let ticks = (behind.as_nanos() / TICK_DURATION.as_nanos()) as u32;
+
+            if overload_warning_due(&mut last_warning, start) {
+                warn!(
+                    "Can't keep up! Is the server overloaded? Running {}ms or {} ticks behind",
+                    behind.as_millis(),
+                    ticks
+                );
+            }
+
+            next_tick += TICK_DURATION * ticks;
+        }
+
+        next_tick += TICK_DURATION;
+
+        tick().await;
+
+        let duration = start.elapsed();
+
+        if duration > TICK_DURATION && overload_warning_due(&mut last_warning, start) {
+            warn!(
+                "Tick took {}ms, longer than the {}ms between two ticks",
+                duration.as_millis(),
+                TICK_DURATION.as_millis()
+            );
+        }
+
+        SERVER.record_tick(start, duration);
+    }
+}
+
+/// Returns whether an overload warning may be logged at `now`, remembering it in
+/// `last_warning` if so.
+fn overload_warning_due(last_warning: &mut Option<Instant>, now: Instant) -> bool {
+    if matches!(last_warning, Some(w) if now - *w < OVERLOAD_WARNING_INTERVAL) {
+        return false;
+    }
+
+    *last_warning = Some(now);
+
+    true
+}
+
+/// Runs a single tick. The packets the players sent since the last tick are handled, the time
+/// advances and the packets sent to the players in the meantime are flushed.
+async fn tick() {
+    let players = SERVER.players();
+
+    for player in &players {
+        for packet in player.take_inbound() {
+            client::handle_packet(player, packet).await;
+        }
+    }
+
+    if SERVER.time().tick() % TIME_UPDATE_INTERVAL == 0 {
+        SERVER.broadcast(SERVER.time().packet());
+    }
+
+    for player in &players {
+        player.packet_sender().flush();
+    }
+}
+
+impl TickStats {
+    /// Creates empty stats.
+    pub fn new() -> Self {
+        Self {
+            starts: VecDeque::new(),
+            durations: VecDeque::with_capacity(MSPT_SAMPLE_SIZE),
+        }
+    }
+
+    /// Records a tick that started at `start` and took `duration`.
+    pub fn record(&mut self, start: Instant, duration: Duration) {
+        while let Some(oldest) = self.starts.front() {
+            if start.saturating_duration_since(*oldest) <= MAX_TPS_PERIOD {
+                break;
+            }
+
+            self.starts.pop_front();
+        }
+
+        if self.durations.len() == MSPT_SAMPLE_SIZE {
+            self.durations.pop_front();
+        }
+
+        self.starts.push_back(start);
+        self.durations.push_back(duration);
+    }
+
+    /// Returns the average number of ticks per second over the last `period`, at most a minute.
+    /// Never exceeds [`TICKS_PER_SECOND`].
+    pub fn tps(&self, period: Duration) -> f64 {
+        let now = Instant::now();
+        let period = period.min(MAX_TPS_PERIOD);
+
+        let first = match self.starts.front() {
+            Some(first) => *first,
+            None => return 0.0,
+        };
+        let since = match now.checked_sub(period) {
+            Some(since) if since > first => since,
+            _ => first,
+        };
+
+        let ticks = self.starts.iter().filter(|start| **start >= since).count();
+        let elapsed = now.saturating_duration_since(since).as_secs_f64();
+
+        if elapsed <= 0.0 {
+            return TICKS_PER_SECOND as f64;
+        }
+
+        (ticks as f64 / elapsed).min(TICKS_PER_SECOND as f64)
+    }
+
+    /// Returns the average milliseconds per tick of the last ticks.
+    pub fn mspt(&self) -> f64 {
+        if self.durations.is_empty() {
+            return 0.0;
+        }
+
+        let total: Duration = self.durations.iter().sum();
+
+        total.as_secs_f64() * 1000.0 / self.durations.len() as f64
+    }
+
+    /// Returns the milliseconds the longest of the last ticks took.
+    pub fn max_mspt(&self) -> f64 {
+        self.durations
+            .iter()
+            .max()
+            .map_or(0.0, |duration| duration.as_secs_f64() * 1000.0)
+    }
+}
+
+impl Default for TickStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overload_warnings_are_rate_limited() {
+        let start = Instant::now();
+        let mut last_warning = None;
+
+        assert!(overload_warning_due(&mut last_warning, start));
+        assert!(!overload_warning_due(
+            &mut last_warning,
+            start + TICK_DURATION
+        ));
+        assert!(!overload_warning_due(
+            &mut last_warning,
+            start + OVERLOAD_WARNING_INTERVAL - TICK_DURATION
+        ));
+        assert!(overload_warning_due(
+            &mut last_warning,
+            start + OVERLOAD_WARNING_INTERVAL
+        ));
+        assert_eq!(last_warning, Some(start + OVERLOAD_WARNING_INTERVAL));
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+
+use arrow_protocol::packets::PacketKind;
+
+/// The number of ticks a day lasts.
+pub const TICKS_PER_DAY: i64 = 24000;
+
+/// The age and the time of day of the world, both in ticks. The time of day starts at sunrise
+/// and keeps counting up, so the day is `time_of_day / TICKS_PER_DAY`.
+pub struct WorldTime {
+    age: AtomicI64,
+    time_of_day: AtomicI64,
+    daylight_cycle: AtomicBool,
+}
+
+impl WorldTime {
+    /// Creates the time of a new world at sunrise of the first day.
+    pub fn new(daylight_cycle: bool) -> Self {
+        Self {
+            age: AtomicI64::new(0),
+            time_of_day: AtomicI64::new(0),
+            daylight_cycle: AtomicBool::new(daylight_cycle),
+        }
+    }
+
+    /// Returns the number of ticks the world has run for.
+    pub fn age(&self) -> i64 {
+        self.age.load(Ordering::Relaxed)
+    }
+
+    pub fn set_age(&self, age: i64) {
+        self.age.store(age, Ordering::Relaxed);
+    }
+
+    /// Returns the time of day including all past days.
+    pub fn time_of_day(&self) -> i64 {
+        self.time_of_day.load(Ordering::Relaxed)
+    }
+
+    pub fn set_time_of_day(&self, time_of_day: i64) {
+        self.time_of_day.store(time_of_day, Ordering::Relaxed);
+    }
+
+    /// Adds `ticks` to the time of day and returns the new time of day.
+    pub fn add_time(&self, ticks: i64) -> i64 {
+        self.time_of_day.fetch_add(ticks, Ordering::Relaxed) + ticks
+    }
+
+    /// Returns whether the time of day advances every tick.
+    pub fn daylight_cycle(&self) -> bool {
+        self.daylight_cycle.load(Ordering::Relaxed)
+    }
+
+    pub fn set_daylight_cycle(&self, daylight_cycle: bool) {
+        self.daylight_cycle.store(daylight_cycle, Ordering::Relaxed);
+    }
+
+    /// Advances the time by one tick and returns the new age of the world.
+    pub fn tick(&self) -> i64 {
+        if self.daylight_cycle() {
+            self.time_of_day.fetch_add(1, Ordering::Relaxed);
+        }
+
+        self.age.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Returns the Time Update packet for the current time. Like vanilla servers, the time of day
+    /// is negated while the daylight cycle is stopped, which stops the sun on the client as well.
+    pub fn packet(&self) -> PacketKind {
+        let time_of_day = match self.time_of_day() {
+            time_of_day if self.daylight_cycle() => time_of_day,
+            0 => -1,
+            time_of_day => -time_of_day,
+        };
+
+        PacketKind::TimeUpdate {
+            world_age: self.age(),
+            time_of_day,
+        }
+    }
+}
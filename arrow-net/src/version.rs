@@ -0,0 +1,64 @@
+use std::ops::RangeInclusive;
+
+/// The protocol versions supported by Arrow.
+pub const SUPPORTED_PROTOCOL_VERSIONS: RangeInclusive<i32> = 47..=754;
+
+/// The supported releases as protocol version, first and last release using it.
+const RELEASES: &[(i32, &str, &str)] = &[
+    (47, "1.8", "1.8.9"),
+    (107, "1.9", "1.9"),
+    (108, "1.9.1", "1.9.1"),
+    (109, "1.9.2", "1.9.2"),
+    (110, "1.9.3", "1.9.4"),
+    (210, "1.10", "1.10.2"),
+    (315, "1.11", "1.11"),
+    (316, "1.11.1", "1.11.2"),
+    (335, "1.12", "1.12"),
+    (338, "1.12.1", "1.12.1"),
+    (340, "1.12.2", "1.12.2"),
+    (393, "1.13", "1.13"),
+    (401, "1.13.1", "1.13.1"),
+    (404, "1.13.2", "1.13.2"),
+    (477, "1.14", "1.14"),
+    (480, "1.14.1", "1.14.1"),
+    (485, "1.14.2", "1.14.2"),
+    (490, "1.14.3", "1.14.3"),
+    (498, "1.14.4", "1.14.4"),
+    (573, "1.15", "1.15"),
+    (575, "1.15.1", "1.15.1"),
+    (578, "1.15.2", "1.15.2"),
+    (735, "1.16", "1.16"),
+    (736, "1.16.1", "1.16.1"),
+    (751, "1.16.2", "1.16.2"),
+    (753, "1.16.3", "1.16.3"),
+    (754, "1.16.4", "1.16.5"),
+];
+
+/// Returns the name of the last version of every supported release and the protocol versions
+/// using its data, which are the ones up to the next release, e.g. `("1.13.2", 404..=476)`.
+pub fn releases() -> Vec<(&'static str, RangeInclusive<i32>)> {
+    RELEASES
+        .iter()
+        .enumerate()
+        .map(|(i, (pv, _, last))| {
+            let end = RELEASES
+                .get(i + 1)
+                .map_or(*SUPPORTED_PROTOCOL_VERSIONS.end(), |(next, _, _)| next - 1);
+
+            (*last, *pv..=end)
+        })
+        .collect()
+}
+
+/// Returns the name shown to players for the protocol versions in `range`, e.g. `1.8 - 1.16.5`.
+/// Falls back to the protocol versions if the range contains no release.
+pub fn range_name(range: &RangeInclusive<i32>) -> String {
+    let mut releases = RELEASES.iter().filter(|(pv, _, _)| range.contains(pv));
+
+    match (releases.next(), releases.next_back()) {
+        (Some((_, first, _)), Some((_, _, last))) => format!("{} - {}", first, last),
+        (Some((_, first, last)), None) if first == last => first.to_string(),
+        (Some((_, first, last)), None) => format!("{} - {}", first, last),
+        (None, _) => format!("protocol {} - {}", range.start(), range.end()),
+    }
+}
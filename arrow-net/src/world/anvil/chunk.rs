@@ -0,0 +1,603 @@
+use std::collections::HashMap;
+
+use arrow_protocol::{
+    block::{BlockRegistry, BlockState},
+    chunk::{
+        storage::{BitStorage, PalettedContainer},
+        Chunk, ChunkSection, NibbleArray, AIR, BIOME_COUNT, PLAINS, SECTION_COUNT, SECTION_VOLUME,
+    },
+};
+use nbt::{Blob, Map, Value};
+
+use super::{byte_array, compound, int, list, string};
+use crate::error::{NetError, Result};
+
+/// The data version of 1.16.5, whose format chunks are written in.
+pub const DATA_VERSION: i32 = 2586;
+/// The data version of 1.16, the first release with the format chunks are written in.
+const FIRST_WRITTEN_DATA_VERSION: i64 = 2566;
+/// The first data version storing block states in palettes instead of numeric ids.
+const FLATTENED_DATA_VERSION: i64 = 1451;
+/// The first data version whose block states don't span two longs.
+const NON_SPANNING_DATA_VERSION: i64 = 2529;
+/// The protocol version whose numeric block ids chunks saved before the flattening use.
+const LEGACY_PROTOCOL_VERSION: i32 = 340;
+/// The statuses of chunks that finished generating. Other chunks are generated again.
+const FULL_STATUSES: &[&str] = &["full", "fullchunk", "postprocessed"];
+/// The biome id marking columns without a biome before 1.13.
+const NO_BIOME: u8 = 255;
+
+/// Decodes the NBT of a chunk saved by any release from 1.8 to 1.16. The block states are
+/// converted to canonical ids of `blocks`, blocks unknown to `blocks` are replaced with its
+/// fallback block. Returns `None` if the chunk didn't finish generating.
+pub fn decode_chunk(nbt: &Blob, blocks: &BlockRegistry) -> Result<Option<Chunk>> {
+    let data_version = nbt.get("DataVersion").and_then(int).unwrap_or(0);
+    let level = nbt
+        .get("Level")
+        .and_then(compound)
+        .ok_or_else(|| NetError::InvalidWorld("Chunk without level".to_string()))?;
+
+    if let Some(status) = level.get("Status").and_then(string) {
+        let status = status.trim_start_matches("minecraft:");
+
+        if !FULL_STATUSES.contains(&status) {
+            return Ok(None);
+        }
+    }
+
+    let mut chunk = Chunk::new(true);
+    let mut states = States::new(blocks);
+
+    for section in level.get("Sections").and_then(list).unwrap_or(&[]) {
+        let section = match compound(section) {
+            Some(section) => section,
+            None => continue,
+        };
+
+        // Sections below and above the chunk only store light.
+        let y = match section.get("Y").and_then(int) {
+            Some(y) if (0..SECTION_COUNT as i64).contains(&y) => y as usize,
+            _ => continue,
+        };
+
+        let values = if data_version >= FLATTENED_DATA_VERSION {
+            states.flattened(section, data_version >= NON_SPANNING_DATA_VERSION)?
+        } else {
+            states.legacy(section)?
+        };
+
+        let values = match values {
+            Some(values) => values,
+            None => continue,
+        };
+
+        let light = |key| {
+            section
+                .get(key)
+                .and_then(byte_array)
+                .and_then(|light| NibbleArray::from_bytes(light.iter().map(|b| *b as u8).collect()))
+        };
+
+        chunk.set_section(
+            y,
+            Some(ChunkSection::from_parts(
+                values,
+                light("BlockLight").unwrap_or_else(|| NibbleArray::new(0)),
+                light("SkyLight").unwrap_or_else(|| NibbleArray::new(15)),
+            )),
+        );
+    }
+
+    chunk.set_biomes(decode_biomes(level.get("Biomes")));
+
+    Ok(Some(chunk))
+}
+
+/// Encodes `chunk` at the chunk coordinates `x` and `z` in the format of 1.16.5. The block states
+/// are canonical ids of `blocks`. Entities, block entities and other data of the chunk are kept
+/// from `previous`, the NBT the chunk was loaded from, if it was saved by 1.16 or later.
+pub fn encode_chunk(
+    x: i32,
+    z: i32,
+    chunk: &Chunk,
+    blocks: &BlockRegistry,
+    previous: Option<&Blob>,
+) -> Result<Blob> {
+    let previous = previous.filter(|nbt| {
+        nbt.get("DataVersion").and_then(int).unwrap_or(0) >= FIRST_WRITTEN_DATA_VERSION
+    });
+    let mut level = match previous.and_then(|nbt| nbt.get("Level")).and_then(compound) {
+        Some(level) => level.clone(),
+        None => {
+            let mut level = Map::new();
+
+            level.insert("Entities".to_string(), Value::List(vec![]));
+            level.insert("TileEntities".to_string(), Value::List(vec![]));
+            level.insert("LastUpdate".to_string(), Value::Long(0));
+            level.insert("InhabitedTime".to_string(), Value::Long(0));
+            level
+        }
+    };
+
+    let sections = (0..SECTION_COUNT)
+        .filter_map(|y| Some(encode_section(y, chunk.section(y)?, blocks)))
+        .collect();
+
+    // The heightmaps and the light are computed again by vanilla servers.
+    level.remove("Heightmaps");
+    level.insert("isLightOn".to_string(), Value::Byte(0));
+    level.insert("xPos".to_string(), Value::Int(x));
+    level.insert("zPos".to_string(), Value::Int(z));
+    level.insert("Status".to_string(), Value::String("full".to_string()));
+    level.insert("Sections".to_string(), Value::List(sections));
+    level.insert(
+        "Biomes".to_string(),
+        Value::IntArray(chunk.biomes().to_vec()),
+    );
+
+    let mut nbt = Blob::new();
+
+    nbt.insert("DataVersion", DATA_VERSION)?;
+    nbt.insert("Level", Value::Compound(level))?;
+
+    Ok(nbt)
+}
+
+/// Converts the block states of sections to canonical ids, remembering the ids of the states
+/// seen before.
+struct States<'a> {
+    blocks: &'a BlockRegistry,
+    fallback: u32,
+    legacy: HashMap<u32, u32>,
+}
+
+impl<'a> States<'a> {
+    fn new(blocks: &'a BlockRegistry) -> Self {
+        Self {
+            blocks,
+            fallback: blocks.canonical_id(blocks.fallback()).unwrap_or(AIR),
+            legacy: HashMap::new(),
+        }
+    }
+
+    /// Decodes the palette and the block states of a section saved since 1.13. Returns `None`
+    /// if the section has no block states.
+    fn flattened(
+        &mut self,
+        section: &Map<String, Value>,
+        non_spanning: bool,
+    ) -> Result<Option<PalettedContainer>> {
+        let (palette, data) = match (section.get("Palette"), section.get("BlockStates")) {
+            (Some(Value::List(palette)), Some(Value::LongArray(data))) => (palette, data),
+            _ => return Ok(None),
+        };
+
+        let palette: Vec<u32> = palette
+            .iter()
+            .map(|entry| {
+                palette_state(entry)
+                    .and_then(|state| self.blocks.closest_canonical_id(&state))
+                    .unwrap_or(self.fallback)
+            })
+            .collect();
+        let data: Vec<u64> = data.iter().map(|long| *long as u64).collect();
+
+        // Usually the palette decides the number of bits, but the data is what counts.
+        let storage = (PalettedContainer::bits_for(palette.len())..=32)
+            .find(|bits| {
+                BitStorage::longs_needed(*bits, SECTION_VOLUME, !non_spanning) == data.len()
+            })
+            .and_then(|bits| BitStorage::from_data(bits, SECTION_VOLUME, !non_spanning, data))
+            .ok_or_else(|| NetError::InvalidWorld("Invalid block states".to_string()))?;
+
+        let values = storage.values().map(|index| {
+            palette
+                .get(index as usize)
+                .copied()
+                .unwrap_or(self.fallback)
+        });
+
+        Ok(Some(PalettedContainer::from_values(SECTION_VOLUME, values)))
+    }
+
+    /// Decodes the numeric block ids and metadata of a section saved before 1.13. Returns
+    /// `None` if the section has no block ids.
+    fn legacy(&mut self, section: &Map<String, Value>) -> Result<Option<PalettedContainer>> {
+        let ids = match section.get("Blocks").and_then(byte_array) {
+            Some(ids) if ids.len() == SECTION_VOLUME => ids,
+            Some(_) => return Err(NetError::InvalidWorld("Invalid block ids".to_string())),
+            None => return Ok(None),
+        };
+
+        let nibbles = |key| {
+            section
+                .get(key)
+                .and_then(byte_array)
+                .filter(|nibbles| nibbles.len() == NibbleArray::LEN)
+        };
+        let nibble = |nibbles: Option<&[i8]>, index: usize| {
+            nibbles.map_or(0, |nibbles| {
+                (nibbles[index / 2] as u8 >> (index % 2 * 4) & 0xF) as u32
+            })
+        };
+
+        let (add, data) = (nibbles("Add"), nibbles("Data"));
+        let mut values = Vec::with_capacity(SECTION_VOLUME);
+
+        for (index, id) in ids.iter().enumerate() {
+            let id = nibble(add, index) << 8 | *id as u8 as u32;
+            let id = id << 4 | nibble(data, index);
+
+            values.push(self.legacy_state(id));
+        }
+
+        Ok(Some(PalettedContainer::from_values(SECTION_VOLUME, values)))
+    }
+
+    /// Returns the canonical id of the legacy `id << 4 | meta`, ignoring metadata unknown to
+    /// the registry.
+    fn legacy_state(&mut self, id: u32) -> u32 {
+        if let Some(state) = self.legacy.get(&id) {
+            return *state;
+        }
+
+        let blocks = self.blocks;
+        let state = blocks
+            .id_to_canonical(id, LEGACY_PROTOCOL_VERSION)
+            .or_else(|| blocks.id_to_canonical(id & !0xF, LEGACY_PROTOCOL_VERSION))
+            .unwrap_or(self.fallback);
+
+        self.legacy.insert(id, state);
+
+        state
+    }
+}
+
+/// Returns the block state of an entry of a section palette.
+fn palette_state(entry: &Value) -> Option<BlockState> {
+    let entry = compound(entry)?;
+    let mut state = BlockState::new(entry.get("Name").and_then(string)?);
+
+    if let Some(properties) = entry.get("Properties").and_then(compound) {
+        for (key, value) in properties {
+            if let Some(value) = string(value) {
+                state = state.with(key.as_str(), value);
+            }
+        }
+    }
+
+    Some(state)
+}
+
+/// Encodes the section `y` with the palette of the block states it contains.
+fn encode_section(y: usize, section: &ChunkSection, blocks: &BlockRegistry) -> Value {
+    let mut palette = vec![];
+    let mut indices = HashMap::new();
+    let values: Vec<u32> = section
+        .states()
+        .values()
+        .map(|state| {
+            *indices.entry(state).or_insert_with(|| {
+                palette.push(state);
+                palette.len() as u32 - 1
+            })
+        })
+        .collect();
+
+    let mut storage = BitStorage::new(
+        PalettedContainer::bits_for(palette.len()),
+        SECTION_VOLUME,
+        false,
+    );
+
+    for (index, value) in values.into_iter().enumerate() {
+        storage.set(index, value);
+    }
+
+    let palette = palette
+        .into_iter()
+        .map(|state| {
+            let state = blocks
+                .state(state)
+                .cloned()
+                .unwrap_or_else(|| BlockState::new("air"));
+            let mut entry = Map::new();
+
+            entry.insert("Name".to_string(), Value::String(state.name().to_string()));
+
+            if !state.properties().is_empty() {
+                let properties = state
+                    .properties()
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+                    .collect();
+
+                entry.insert("Properties".to_string(), Value::Compound(properties));
+            }
+
+            Value::Compound(entry)
+        })
+        .collect();
+
+    let light =
+        |light: &NibbleArray| Value::ByteArray(light.as_bytes().iter().map(|b| *b as i8).collect());
+
+    let mut nbt = Map::new();
+
+    nbt.insert("Y".to_string(), Value::Byte(y as i8));
+    nbt.insert("Palette".to_string(), Value::List(palette));
+    nbt.insert(
+        "BlockStates".to_string(),
+        Value::LongArray(storage.data().iter().map(|long| *long as i64).collect()),
+    );
+    nbt.insert("BlockLight".to_string(), light(section.block_light()));
+    nbt.insert("SkyLight".to_string(), light(section.sky_light()));
+
+    Value::Compound(nbt)
+}
+
+/// Decodes the biomes of a chunk, which are stored for every 4x4x4 cube since 1.15 and for every
+/// column before. Missing biomes are plains.
+fn decode_biomes(biomes: Option<&Value>) -> Vec<i32> {
+    let columns: Vec<i32> = match biomes {
+        Some(Value::IntArray(biomes)) if biomes.len() == BIOME_COUNT => return biomes.clone(),
+        Some(Value::IntArray(biomes)) if biomes.len() == 16 * 16 => biomes.clone(),
+        Some(Value::ByteArray(biomes)) if biomes.len() == 16 * 16 => biomes
+            .iter()
+            .map(|biome| match *biome as u8 {
+                NO_BIOME => PLAINS,
+                biome => biome as i32,
+            })
+            .collect(),
+        _ => return vec![PLAINS; BIOME_COUNT],
+    };
+
+    // Every cube uses the biome of the column at its corner.
+    (0..BIOME_COUNT)
+        .map(|index| {
+            let (x, z) = (index & 3, index >> 2 & 3);
+
+            columns[z * 4 * 16 + x * 4]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The blocks of the palette of the section in [`flattened_nbt`]. 17 states need 5 bits,
+    /// which don't divide a long.
+    const PALETTE: [&str; 17] = [
+        "air",
+        "stone",
+        "granite",
+        "polished_granite",
+        "diorite",
+        "polished_diorite",
+        "andesite",
+        "polished_andesite",
+        "dirt",
+        "coarse_dirt",
+        "cobblestone",
+        "oak_planks",
+        "spruce_planks",
+        "birch_planks",
+        "jungle_planks",
+        "acacia_planks",
+        "dark_oak_planks",
+    ];
+
+    fn canonical(blocks: &BlockRegistry, state: &str) -> u32 {
+        blocks.canonical_id(&state.parse().unwrap()).unwrap()
+    }
+
+    fn states(chunk: &Chunk, y: usize) -> Vec<u32> {
+        chunk.section(y).unwrap().states().values().collect()
+    }
+
+    fn level(nbt: &Blob) -> &Map<String, Value> {
+        nbt.get("Level").and_then(compound).unwrap()
+    }
+
+    /// Returns the NBT of a chunk saved with `data_version`, whose lowest section has the block
+    /// `PALETTE[i]` at index `i`.
+    fn flattened_nbt(data_version: i32) -> Blob {
+        let spanning = (data_version as i64) < NON_SPANNING_DATA_VERSION;
+        let mut storage = BitStorage::new(5, SECTION_VOLUME, spanning);
+
+        for i in 0..PALETTE.len() {
+            storage.set(i, i as u32);
+        }
+
+        let palette = PALETTE
+            .iter()
+            .map(|name| {
+                let mut entry = Map::new();
+
+                entry.insert(
+                    "Name".to_string(),
+                    Value::String(format!("minecraft:{}", name)),
+                );
+                Value::Compound(entry)
+            })
+            .collect();
+
+        let mut section = Map::new();
+        section.insert("Y".to_string(), Value::Byte(0));
+        section.insert("Palette".to_string(), Value::List(palette));
+        section.insert(
+            "BlockStates".to_string(),
+            Value::LongArray(storage.data().iter().map(|long| *long as i64).collect()),
+        );
+
+        let mut level = Map::new();
+        level.insert("Status".to_string(), Value::String("full".to_string()));
+        level.insert(
+            "Sections".to_string(),
+            Value::List(vec![Value::Compound(section)]),
+        );
+
+        let mut nbt = Blob::new();
+        nbt.insert("DataVersion", data_version).unwrap();
+        nbt.insert("Level", Value::Compound(level)).unwrap();
+
+        nbt
+    }
+
+    #[test]
+    fn round_trip() {
+        let blocks = BlockRegistry::new();
+        let mut chunk = Chunk::new(true);
+        let snowy = canonical(&blocks, "grass_block[snowy=true]");
+        let water = canonical(&blocks, "water[level=3]");
+
+        chunk.set_block(0, 0, 0, canonical(&blocks, "bedrock"));
+        chunk.set_block(15, 17, 15, snowy);
+        chunk.set_block(3, 255, 4, water);
+        chunk.section_mut(1).block_light_mut().set(15, 1, 15, 7);
+        chunk.set_biome(12, 200, 8, 2);
+
+        let nbt = encode_chunk(3, -2, &chunk, &blocks, None).unwrap();
+
+        assert_eq!(nbt.get("DataVersion"), Some(&Value::Int(DATA_VERSION)));
+        assert_eq!(level(&nbt).get("xPos"), Some(&Value::Int(3)));
+        assert_eq!(level(&nbt).get("zPos"), Some(&Value::Int(-2)));
+
+        let decoded = decode_chunk(&nbt, &blocks).unwrap().unwrap();
+
+        for y in [0, 1, 15].iter().copied() {
+            assert_eq!(states(&decoded, y), states(&chunk, y), "{}", y);
+        }
+
+        assert!(decoded.section(2).is_none());
+        assert_eq!(decoded.block(15, 17, 15), snowy);
+        assert_eq!(decoded.block(3, 255, 4), water);
+        assert_eq!(decoded.section(1).unwrap().block_light().get(15, 1, 15), 7);
+        assert_eq!(decoded.section(1).unwrap().sky_light().get(0, 0, 0), 15);
+        assert_eq!(decoded.biomes(), chunk.biomes());
+    }
+
+    #[test]
+    fn round_trip_keeps_previous_data() {
+        let blocks = BlockRegistry::new();
+        let mut previous = encode_chunk(0, 0, &Chunk::new(true), &blocks, None).unwrap();
+
+        let mut previous_level = level(&previous).clone();
+
+        previous_level.insert("InhabitedTime".to_string(), Value::Long(42));
+        previous
+            .insert("Level", Value::Compound(previous_level))
+            .unwrap();
+
+        let nbt = encode_chunk(0, 0, &Chunk::new(true), &blocks, Some(&previous)).unwrap();
+
+        assert_eq!(level(&nbt).get("InhabitedTime"), Some(&Value::Long(42)));
+    }
+
+    #[test]
+    fn spanning_and_non_spanning_palettes() {
+        let blocks = BlockRegistry::new();
+        let expected: Vec<u32> = (0..SECTION_VOLUME)
+            .map(|i| canonical(&blocks, PALETTE.get(i).copied().unwrap_or("air")))
+            .collect();
+
+        // 1.15.2 and 1.16.5.
+        for data_version in [2230, DATA_VERSION].iter().copied() {
+            let chunk = decode_chunk(&flattened_nbt(data_version), &blocks)
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(states(&chunk, 0), expected, "{}", data_version);
+            assert_eq!(chunk.biomes(), &[PLAINS; BIOME_COUNT][..]);
+        }
+
+        // Longs of the other layout don't fit the number of bits of the palette.
+        let mut nbt = flattened_nbt(2230);
+        nbt.insert("DataVersion", DATA_VERSION).unwrap();
+
+        assert!(decode_chunk(&nbt, &blocks).is_err());
+    }
+
+    #[test]
+    fn unfinished_chunks() {
+        let blocks = BlockRegistry::new();
+        let mut nbt = flattened_nbt(DATA_VERSION);
+        let mut level = level(&nbt).clone();
+
+        level.insert(
+            "Status".to_string(),
+            Value::String("minecraft:features".to_string()),
+        );
+        nbt.insert("Level", Value::Compound(level)).unwrap();
+
+        assert_eq!(decode_chunk(&nbt, &blocks).unwrap(), None);
+    }
+
+    #[test]
+    fn legacy_sections() {
+        let mut blocks = BlockRegistry::new();
+        blocks.set_fallback(BlockState::new("bedrock"));
+
+        let mut ids = vec![0i8; SECTION_VOLUME];
+        let mut add = vec![0i8; NibbleArray::LEN];
+        let mut data = vec![0i8; NibbleArray::LEN];
+        let mut block_light = vec![0i8; NibbleArray::LEN];
+
+        // Granite, grass, water of level 3, stone with unknown metadata and the unknown id 257.
+        ids[..5].copy_from_slice(&[1, 2, 9, 1, 1]);
+        data[0] = 0x01;
+        data[1] = 0xF3u8 as i8;
+        add[2] = 0x01;
+        block_light[0] = 0x0F;
+
+        let mut section = Map::new();
+        section.insert("Y".to_string(), Value::Byte(1));
+        section.insert("Blocks".to_string(), Value::ByteArray(ids));
+        section.insert("Add".to_string(), Value::ByteArray(add));
+        section.insert("Data".to_string(), Value::ByteArray(data));
+        section.insert("BlockLight".to_string(), Value::ByteArray(block_light));
+
+        // Biome 255 marks columns without a biome.
+        let mut biomes = vec![4i8; 16 * 16];
+        biomes[0] = 2;
+        biomes[4] = -1;
+
+        let mut level = Map::new();
+        level.insert(
+            "Sections".to_string(),
+            Value::List(vec![Value::Compound(section)]),
+        );
+        level.insert("Biomes".to_string(), Value::ByteArray(biomes));
+
+        let mut nbt = Blob::new();
+        nbt.insert("Level", Value::Compound(level)).unwrap();
+
+        let chunk = decode_chunk(&nbt, &blocks).unwrap().unwrap();
+        let expected = [
+            "granite",
+            "grass_block[snowy=false]",
+            "water[level=3]",
+            "stone",
+            "bedrock",
+            "air",
+        ];
+
+        for (x, state) in expected.iter().enumerate() {
+            assert_eq!(
+                chunk.block(x, 16, 0),
+                canonical(&blocks, state),
+                "{}",
+                state
+            );
+        }
+
+        assert!(chunk.section(0).is_none());
+        assert_eq!(chunk.section(1).unwrap().block_light().get(0, 0, 0), 15);
+        assert_eq!(chunk.section(1).unwrap().block_light().get(1, 0, 0), 0);
+        assert_eq!(chunk.section(1).unwrap().sky_light().get(0, 0, 0), 15);
+        assert_eq!(chunk.biome(0, 0, 0), 2);
+        assert_eq!(chunk.biome(4, 0, 0), PLAINS);
+        assert_eq!(chunk.biome(8, 100, 0), 4);
+    }
+}
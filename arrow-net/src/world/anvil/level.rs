@@ -0,0 +1,127 @@
+use nbt::{Blob, Map, Value};
+
+use super::{compound, int, string};
+use crate::error::{NetError, Result};
+
+/// The version of the Anvil format in `level.dat`.
+const ANVIL_VERSION: i32 = 19133;
+
+/// The `level.dat` of a world, which stores its spawn point, seed, time and game rules. Values
+/// Arrow doesn't use are kept, so they are written back unchanged.
+#[derive(Debug, Clone)]
+pub struct LevelData {
+    data: Map<String, Value>,
+}
+
+impl LevelData {
+    /// Creates the level data of a new world with `seed` and the spawn point `spawn`.
+    pub fn new(seed: i64, spawn: (i32, i32, i32)) -> Self {
+        let mut data = Map::new();
+
+        data.insert("version".to_string(), Value::Int(ANVIL_VERSION));
+        data.insert("LevelName".to_string(), Value::String("world".to_string()));
+        data.insert("RandomSeed".to_string(), Value::Long(seed));
+        data.insert(
+            "generatorName".to_string(),
+            Value::String("default".to_string()),
+        );
+        data.insert("Time".to_string(), Value::Long(0));
+        data.insert("DayTime".to_string(), Value::Long(0));
+        data.insert("GameRules".to_string(), Value::Compound(Map::new()));
+
+        let mut level = Self { data };
+
+        level.set_spawn(spawn);
+        level
+    }
+
+    /// Reads the level data from the NBT of `level.dat`.
+    pub fn from_nbt(nbt: &Blob) -> Result<Self> {
+        let data = nbt
+            .get("Data")
+            .and_then(compound)
+            .ok_or_else(|| NetError::InvalidWorld("level.dat without data".to_string()))?;
+
+        Ok(Self { data: data.clone() })
+    }
+
+    /// Returns the NBT of `level.dat`.
+    pub fn to_nbt(&self) -> Result<Blob> {
+        let mut nbt = Blob::new();
+
+        nbt.insert("Data", Value::Compound(self.data.clone()))?;
+
+        Ok(nbt)
+    }
+
+    /// Returns the block position players spawn at.
+    pub fn spawn(&self) -> Option<(i32, i32, i32)> {
+        let coordinate = |key| self.data.get(key).and_then(int).map(|value| value as i32);
+
+        Some((
+            coordinate("SpawnX")?,
+            coordinate("SpawnY")?,
+            coordinate("SpawnZ")?,
+        ))
+    }
+
+    pub fn set_spawn(&mut self, (x, y, z): (i32, i32, i32)) {
+        self.data.insert("SpawnX".to_string(), Value::Int(x));
+        self.data.insert("SpawnY".to_string(), Value::Int(y));
+        self.data.insert("SpawnZ".to_string(), Value::Int(z));
+    }
+
+    /// Returns the seed of the world, which is part of the world generation settings since 1.16.
+    pub fn seed(&self) -> Option<i64> {
+        self.data
+            .get("WorldGenSettings")
+            .and_then(compound)
+            .and_then(|settings| settings.get("seed"))
+            .or_else(|| self.data.get("RandomSeed"))
+            .and_then(int)
+    }
+
+    /// Returns the number of ticks the world has run for.
+    pub fn time(&self) -> i64 {
+        self.data.get("Time").and_then(int).unwrap_or(0)
+    }
+
+    /// Returns the time of day including all past days.
+    pub fn day_time(&self) -> i64 {
+        self.data
+            .get("DayTime")
+            .and_then(int)
+            .unwrap_or_else(|| self.time())
+    }
+
+    /// Sets the age of the world and the time of day, both in ticks.
+    pub fn set_time(&mut self, time: i64, day_time: i64) {
+        self.data.insert("Time".to_string(), Value::Long(time));
+        self.data
+            .insert("DayTime".to_string(), Value::Long(day_time));
+    }
+
+    /// Returns the value of the game rule `name`, e.g. `true` for `doDaylightCycle`.
+    pub fn game_rule(&self, name: &str) -> Option<&str> {
+        self.data
+            .get("GameRules")
+            .and_then(compound)
+            .and_then(|rules| rules.get(name))
+            .and_then(string)
+    }
+
+    pub fn set_game_rule(&mut self, name: &str, value: &str) {
+        let rules = self
+            .data
+            .entry("GameRules".to_string())
+            .or_insert_with(|| Value::Compound(Map::new()));
+
+        if !matches!(rules, Value::Compound(_)) {
+            *rules = Value::Compound(Map::new());
+        }
+
+        if let Value::Compound(rules) = rules {
+            rules.insert(name.to_string(), Value::String(value.to_string()));
+        }
+    }
+}
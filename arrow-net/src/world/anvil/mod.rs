@@ -0,0 +1,210 @@
+/// The conversion between the NBT of chunks and the chunk model.
+mod chunk;
+/// The `level.dat` of a world.
+pub mod level;
+/// The region files storing the chunks.
+pub mod region;
+
+pub use chunk::{decode_chunk, encode_chunk, DATA_VERSION};
+pub use level::LevelData;
+pub use region::RegionFile;
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use arrow_protocol::{block::BlockRegistry, chunk::Chunk};
+use nbt::{Blob, Map, Value};
+use parking_lot::Mutex;
+
+use crate::error::Result;
+
+/// A world in the Anvil format of vanilla servers since 1.2, i.e. a directory containing the
+/// `level.dat` and the region files in `region`.
+///
+/// Region files are read once and kept in memory. Chunks are converted between the formats of
+/// 1.8 to 1.16 and the chunk model when they are loaded, and written in the format of 1.16.5.
+pub struct AnvilWorld {
+    dir: PathBuf,
+    blocks: Arc<BlockRegistry>,
+    level: Mutex<Option<LevelData>>,
+    regions: Mutex<HashMap<(i32, i32), SharedRegion>>,
+}
+
+/// A region file shared by the threads loading and saving chunks.
+type SharedRegion = Arc<Mutex<RegionFile>>;
+
+impl AnvilWorld {
+    /// Opens the world in the directory `dir`, reading its `level.dat` if there is one. The
+    /// block states of its chunks are converted to canonical ids of `blocks`.
+    pub fn open<P: Into<PathBuf>>(dir: P, blocks: Arc<BlockRegistry>) -> Result<Self> {
+        let dir = dir.into();
+        let level = match File::open(dir.join("level.dat")) {
+            Ok(mut file) => Some(LevelData::from_nbt(&Blob::from_gzip_reader(&mut file)?)?),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            dir,
+            blocks,
+            level: Mutex::new(level),
+            regions: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the directory of the world.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the level data of the world, or `None` if it has no `level.dat` yet.
+    pub fn level(&self) -> Option<LevelData> {
+        self.level.lock().clone()
+    }
+
+    /// Replaces the level data and writes it to `level.dat`. The previous `level.dat` is kept as
+    /// `level.dat_old` like vanilla servers do.
+    pub fn save_level(&self, level: LevelData) -> Result<()> {
+        let mut bytes = vec![];
+
+        level.to_nbt()?.to_gzip_writer(&mut bytes)?;
+        fs::create_dir_all(&self.dir)?;
+
+        let path = self.dir.join("level.dat");
+
+        match fs::copy(&path, self.dir.join("level.dat_old")) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+
+        write_atomic(&path, &bytes)?;
+        *self.level.lock() = Some(level);
+
+        Ok(())
+    }
+
+    /// Loads the chunk at the chunk coordinates `x` and `z`. Returns `None` if the chunk was
+    /// never saved or didn't finish generating.
+    pub fn load_chunk(&self, x: i32, z: i32) -> Result<Option<Chunk>> {
+        let region = self.region(x, z)?;
+        let nbt = region.lock().chunk(x, z)?;
+
+        match nbt {
+            Some(nbt) => decode_chunk(&nbt, &self.blocks),
+            None => Ok(None),
+        }
+    }
+
+    /// Saves `chunks`, which are the chunk coordinates and the chunks. Every region file is
+    /// replaced atomically once all its chunks are updated.
+    pub fn save_chunks(&self, chunks: &[(i32, i32, Arc<Chunk>)]) -> Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs() as u32);
+
+        let mut regions: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+
+        for (index, (x, z, _)) in chunks.iter().enumerate() {
+            regions
+                .entry(region::region_position(*x, *z))
+                .or_default()
+                .push(index);
+        }
+
+        for indices in regions.values() {
+            let region = self.region(chunks[indices[0]].0, chunks[indices[0]].1)?;
+            let mut region = region.lock();
+
+            for (x, z, chunk) in indices.iter().map(|index| &chunks[*index]) {
+                // Keeps the entities and other data the chunk model doesn't contain.
+                let previous = region.chunk(*x, *z).ok().flatten();
+                let nbt = encode_chunk(*x, *z, chunk, &self.blocks, previous.as_ref())?;
+
+                region.set_chunk(*x, *z, &nbt, timestamp)?;
+            }
+
+            fs::create_dir_all(self.dir.join("region"))?;
+            region.save()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the region containing the chunk at the chunk coordinates `x` and `z`, reading it
+    /// if it wasn't read before.
+    fn region(&self, x: i32, z: i32) -> Result<SharedRegion> {
+        let position = region::region_position(x, z);
+        let mut regions = self.regions.lock();
+
+        if let Some(region) = regions.get(&position) {
+            return Ok(region.clone());
+        }
+
+        let region = RegionFile::open(&self.dir.join("region"), position.0, position.1)?;
+        let region = Arc::new(Mutex::new(region));
+
+        regions.insert(position, region.clone());
+
+        Ok(region)
+    }
+}
+
+/// Writes `bytes` to a temporary file next to `path` and renames it to `path`, so the file at
+/// `path` is either the old or the new one even if the server crashes.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+
+    let mut file = File::create(&temporary)?;
+
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)?;
+
+    Ok(())
+}
+
+fn compound(value: &Value) -> Option<&Map<String, Value>> {
+    match value {
+        Value::Compound(compound) => Some(compound),
+        _ => None,
+    }
+}
+
+fn list(value: &Value) -> Option<&[Value]> {
+    match value {
+        Value::List(list) => Some(list),
+        _ => None,
+    }
+}
+
+fn string(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(string) => Some(string),
+        _ => None,
+    }
+}
+
+fn byte_array(value: &Value) -> Option<&[i8]> {
+    match value {
+        Value::ByteArray(array) => Some(array),
+        _ => None,
+    }
+}
+
+/// Returns any integer, as the same value may be stored with different sizes.
+fn int(value: &Value) -> Option<i64> {
+    match value {
+        Value::Byte(value) => Some(*value as i64),
+        Value::Short(value) => Some(*value as i64),
+        Value::Int(value) => Some(*value as i64),
+        Value::Long(value) => Some(*value),
+        _ => None,
+    }
+}
@@ -0,0 +1,477 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use log::warn;
+use nbt::Blob;
+
+use super::write_atomic;
+use crate::error::{NetError, Result};
+
+/// The number of chunks in every direction of a region.
+pub const REGION_SIZE: i32 = 32;
+/// The number of chunks in a region.
+const CHUNK_COUNT: usize = (REGION_SIZE * REGION_SIZE) as usize;
+/// The size of the sectors a region file is split into.
+const SECTOR_SIZE: usize = 4096;
+/// The size of the header, which contains the locations and the timestamps of the chunks.
+const HEADER_SIZE: usize = 2 * SECTOR_SIZE;
+/// The most sectors a chunk can take up in a region file. Larger chunks are stored in a
+/// separate file since 1.15.
+const MAX_SECTORS: usize = 255;
+
+/// The compression of chunks compressed with gzip.
+const GZIP: u8 = 1;
+/// The compression of chunks compressed with zlib, which vanilla servers use.
+const ZLIB: u8 = 2;
+/// The compression of uncompressed chunks, supported since 1.15.
+const UNCOMPRESSED: u8 = 3;
+/// Set in the compression of chunks stored in a separate `c.x.z.mcc` file.
+const EXTERNAL: u8 = 0x80;
+
+/// A region file `r.x.z.mca` of the Anvil format, which stores 32 by 32 chunks as compressed
+/// NBT. The whole file is kept in memory and written back atomically by [`RegionFile::save`].
+pub struct RegionFile {
+    dir: PathBuf,
+    x: i32,
+    z: i32,
+    chunks: Vec<Option<RegionChunk>>,
+}
+
+/// A compressed chunk of a region file.
+struct RegionChunk {
+    /// The time of the last modification in seconds since the Unix epoch.
+    timestamp: u32,
+    compression: u8,
+    data: Vec<u8>,
+    /// Whether the chunk was read from a separate file.
+    external: bool,
+    /// Whether the separate file couldn't be read, so `data` is empty. The file is read again
+    /// when the chunk is requested and kept as it is by [`RegionFile::save`].
+    unread: bool,
+}
+
+impl RegionFile {
+    /// Reads the region at the region coordinates `x` and `z` from the region directory `dir`.
+    /// A region without a file has no chunks. Chunks with an invalid location are skipped, chunks
+    /// whose separate file can't be read are kept and read again when requested.
+    pub fn open(dir: &Path, x: i32, z: i32) -> Result<Self> {
+        let mut region = Self {
+            dir: dir.to_path_buf(),
+            x,
+            z,
+            chunks: (0..CHUNK_COUNT).map(|_| None).collect(),
+        };
+
+        let bytes = match fs::read(region.path()) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(region),
+            Err(e) => return Err(e.into()),
+        };
+
+        if bytes.is_empty() {
+            return Ok(region);
+        }
+
+        if bytes.len() < HEADER_SIZE {
+            return Err(NetError::InvalidWorld(format!(
+                "Truncated header of {}",
+                region.path().display()
+            )));
+        }
+
+        for index in 0..CHUNK_COUNT {
+            let location = read_u32(&bytes, index * 4);
+
+            if location == 0 {
+                continue;
+            }
+
+            match region.read_chunk(&bytes, index, location) {
+                Ok(chunk) => region.chunks[index] = Some(chunk),
+                Err(e) => {
+                    let (chunk_x, chunk_z) = region.chunk_position(index);
+
+                    warn!("Skipping chunk {} {}: {}", chunk_x, chunk_z, e);
+                }
+            }
+        }
+
+        Ok(region)
+    }
+
+    /// Returns the x coordinate of the region.
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+
+    /// Returns the z coordinate of the region.
+    pub fn z(&self) -> i32 {
+        self.z
+    }
+
+    /// Returns whether the chunk at the chunk coordinates `x` and `z` is stored in the region.
+    pub fn has_chunk(&self, x: i32, z: i32) -> bool {
+        self.chunks[chunk_index(x, z)].is_some()
+    }
+
+    /// Returns the time the chunk at the chunk coordinates `x` and `z` was last saved, in
+    /// seconds since the Unix epoch.
+    pub fn timestamp(&self, x: i32, z: i32) -> Option<u32> {
+        self.chunks[chunk_index(x, z)]
+            .as_ref()
+            .map(|chunk| chunk.timestamp)
+    }
+
+    /// Decompresses the NBT of the chunk at the chunk coordinates `x` and `z`. Returns `None` if
+    /// the chunk is not stored in the region.
+    pub fn chunk(&self, x: i32, z: i32) -> Result<Option<Blob>> {
+        let chunk = match &self.chunks[chunk_index(x, z)] {
+            Some(chunk) => chunk,
+            None => return Ok(None),
+        };
+
+        let unread;
+        let mut data = if chunk.unread {
+            unread = fs::read(self.external_path(x, z))?;
+            &unread[..]
+        } else {
+            &chunk.data[..]
+        };
+        let nbt = match chunk.compression {
+            GZIP => Blob::from_gzip_reader(&mut data)?,
+            ZLIB => Blob::from_zlib_reader(&mut data)?,
+            UNCOMPRESSED => Blob::from_reader(&mut data)?,
+            compression => {
+                return Err(NetError::InvalidWorld(format!(
+                    "Unknown compression {} of chunk {} {}",
+                    compression, x, z
+                )))
+            }
+        };
+
+        Ok(Some(nbt))
+    }
+
+    /// Compresses `nbt` with zlib and stores it as the chunk at the chunk coordinates `x` and
+    /// `z`. The region file is only written by [`RegionFile::save`].
+    pub fn set_chunk(&mut self, x: i32, z: i32, nbt: &Blob, timestamp: u32) -> Result<()> {
+        let mut data = vec![];
+
+        nbt.to_zlib_writer(&mut data)?;
+
+        let index = chunk_index(x, z);
+        let external = matches!(&self.chunks[index], Some(chunk) if chunk.external);
+
+        self.chunks[index] = Some(RegionChunk {
+            timestamp,
+            compression: ZLIB,
+            data,
+            external,
+            unread: false,
+        });
+
+        Ok(())
+    }
+
+    /// Writes the region file. The file is replaced atomically, so it is never left partly
+    /// written. Separate files of chunks that fit into the region file again are only removed
+    /// once the region file no longer refers to them.
+    pub fn save(&self) -> Result<()> {
+        let mut header = vec![0; HEADER_SIZE];
+        let mut sectors = vec![];
+        let mut stale = vec![];
+
+        for (index, chunk) in self.chunks.iter().enumerate() {
+            let chunk = match chunk {
+                Some(chunk) => chunk,
+                None => continue,
+            };
+
+            let (chunk_x, chunk_z) = self.chunk_position(index);
+            let offset = HEADER_SIZE + sectors.len();
+
+            if chunk.unread {
+                sectors.extend_from_slice(&1u32.to_be_bytes());
+                sectors.push(chunk.compression | EXTERNAL);
+            } else if chunk.data.len() + 5 > MAX_SECTORS * SECTOR_SIZE {
+                write_atomic(&self.external_path(chunk_x, chunk_z), &chunk.data)?;
+
+                sectors.extend_from_slice(&1u32.to_be_bytes());
+                sectors.push(chunk.compression | EXTERNAL);
+            } else {
+                if chunk.external {
+                    stale.push(self.external_path(chunk_x, chunk_z));
+                }
+
+                sectors.extend_from_slice(&(chunk.data.len() as u32 + 1).to_be_bytes());
+                sectors.push(chunk.compression);
+                sectors.extend_from_slice(&chunk.data);
+            }
+
+            sectors.resize(sectors.len().div_ceil(SECTOR_SIZE) * SECTOR_SIZE, 0);
+
+            let first_sector = (offset / SECTOR_SIZE) as u32;
+            let sector_count = ((HEADER_SIZE + sectors.len() - offset) / SECTOR_SIZE) as u32;
+
+            header[index * 4..index * 4 + 4]
+                .copy_from_slice(&(first_sector << 8 | sector_count).to_be_bytes());
+            header[SECTOR_SIZE + index * 4..SECTOR_SIZE + index * 4 + 4]
+                .copy_from_slice(&chunk.timestamp.to_be_bytes());
+        }
+
+        header.extend_from_slice(&sectors);
+
+        write_atomic(&self.path(), &header)?;
+
+        for path in stale {
+            if let Err(e) = remove_external(&path) {
+                warn!("Failed removing {}: {}", path.display(), e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the path of the region file.
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(format!("r.{}.{}.mca", self.x, self.z))
+    }
+
+    fn read_chunk(&self, bytes: &[u8], index: usize, location: u32) -> Result<RegionChunk> {
+        let offset = (location >> 8) as usize * SECTOR_SIZE;
+
+        if offset < HEADER_SIZE || offset + 5 > bytes.len() {
+            return Err(NetError::InvalidWorld(format!(
+                "Invalid location {:#x}",
+                location
+            )));
+        }
+
+        let length = read_u32(bytes, offset) as usize;
+
+        if length == 0 || offset + 4 + length > bytes.len() {
+            return Err(NetError::InvalidWorld(format!("Invalid length {}", length)));
+        }
+
+        let compression = bytes[offset + 4];
+        let timestamp = read_u32(bytes, SECTOR_SIZE + index * 4);
+
+        if compression & EXTERNAL == 0 {
+            return Ok(RegionChunk {
+                timestamp,
+                compression,
+                data: bytes[offset + 5..offset + 4 + length].to_vec(),
+                external: false,
+                unread: false,
+            });
+        }
+
+        let (chunk_x, chunk_z) = self.chunk_position(index);
+        let path = self.external_path(chunk_x, chunk_z);
+        let (data, unread) = match fs::read(&path) {
+            Ok(data) => (data, false),
+            Err(e) => {
+                warn!("Failed reading {}: {}", path.display(), e);
+                (vec![], true)
+            }
+        };
+
+        Ok(RegionChunk {
+            timestamp,
+            compression: compression & !EXTERNAL,
+            data,
+            external: true,
+            unread,
+        })
+    }
+
+    /// Returns the chunk coordinates of the chunk `index` of the region.
+    fn chunk_position(&self, index: usize) -> (i32, i32) {
+        (
+            self.x * REGION_SIZE + index as i32 % REGION_SIZE,
+            self.z * REGION_SIZE + index as i32 / REGION_SIZE,
+        )
+    }
+
+    /// Returns the path of the file storing the chunk at the chunk coordinates `x` and `z` if it
+    /// doesn't fit into the region file.
+    fn external_path(&self, x: i32, z: i32) -> PathBuf {
+        self.dir.join(format!("c.{}.{}.mcc", x, z))
+    }
+}
+
+/// Returns the coordinates of the region containing the chunk at the chunk coordinates `x` and
+/// `z`.
+pub fn region_position(x: i32, z: i32) -> (i32, i32) {
+    (x.div_euclid(REGION_SIZE), z.div_euclid(REGION_SIZE))
+}
+
+/// Returns the index of the chunk at the chunk coordinates `x` and `z` within its region.
+fn chunk_index(x: i32, z: i32) -> usize {
+    (x.rem_euclid(REGION_SIZE) + z.rem_euclid(REGION_SIZE) * REGION_SIZE) as usize
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+/// Removes the file of a chunk that fits into its region file again.
+fn remove_external(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nbt::Value;
+
+    use super::*;
+
+    /// Returns an empty directory for the test `name`.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("arrow-region-{}-{}", std::process::id(), name));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    fn blob(x: i32, z: i32) -> Blob {
+        let mut blob = Blob::new();
+
+        blob.insert("xPos", x).unwrap();
+        blob.insert("zPos", z).unwrap();
+
+        blob
+    }
+
+    /// Returns a chunk whose compressed data doesn't fit into a region file.
+    fn large_blob() -> Blob {
+        let mut seed = 1u32;
+        let noise = (0..MAX_SECTORS * SECTOR_SIZE + SECTOR_SIZE)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as i8
+            })
+            .collect();
+        let mut blob = Blob::new();
+
+        blob.insert("Noise", Value::ByteArray(noise)).unwrap();
+
+        blob
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = test_dir("round-trip");
+        let mut region = RegionFile::open(&dir, -1, 0).unwrap();
+
+        assert!(!region.has_chunk(-32, 0));
+
+        region.set_chunk(-32, 0, &blob(-32, 0), 100).unwrap();
+        region.set_chunk(-1, 31, &blob(-1, 31), 200).unwrap();
+        region.save().unwrap();
+
+        assert!(dir.join("r.-1.0.mca").exists());
+
+        let region = RegionFile::open(&dir, -1, 0).unwrap();
+
+        assert_eq!((region.x(), region.z()), (-1, 0));
+        assert_eq!(region.chunk(-32, 0).unwrap(), Some(blob(-32, 0)));
+        assert_eq!(region.chunk(-1, 31).unwrap(), Some(blob(-1, 31)));
+        assert_eq!(region.timestamp(-32, 0), Some(100));
+        assert_eq!(region.timestamp(-1, 31), Some(200));
+        assert_eq!(region.chunk(-2, 0).unwrap(), None);
+        assert_eq!(region_position(-1, 31), (-1, 0));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn external_chunks() {
+        let dir = test_dir("external");
+        let external = dir.join("c.1.2.mcc");
+        let mut region = RegionFile::open(&dir, 0, 0).unwrap();
+
+        region.set_chunk(1, 2, &large_blob(), 0).unwrap();
+        region.save().unwrap();
+
+        assert!(external.exists());
+
+        let mut region = RegionFile::open(&dir, 0, 0).unwrap();
+
+        assert_eq!(region.chunk(1, 2).unwrap(), Some(large_blob()));
+
+        // The separate file is kept while the region file still refers to it.
+        region.set_chunk(1, 2, &blob(1, 2), 0).unwrap();
+        fs::create_dir(dir.join("r.0.0.mca.tmp")).unwrap();
+
+        assert!(region.save().is_err());
+        assert!(external.exists());
+        assert_eq!(
+            RegionFile::open(&dir, 0, 0).unwrap().chunk(1, 2).unwrap(),
+            Some(large_blob())
+        );
+
+        fs::remove_dir(dir.join("r.0.0.mca.tmp")).unwrap();
+        region.save().unwrap();
+
+        assert!(!external.exists());
+        assert_eq!(
+            RegionFile::open(&dir, 0, 0).unwrap().chunk(1, 2).unwrap(),
+            Some(blob(1, 2))
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn unreadable_external_chunks_are_kept() {
+        let dir = test_dir("unreadable");
+        let external = dir.join("c.1.2.mcc");
+        let mut region = RegionFile::open(&dir, 0, 0).unwrap();
+
+        region.set_chunk(1, 2, &large_blob(), 7).unwrap();
+        region.save().unwrap();
+
+        let data = fs::read(&external).unwrap();
+
+        // A directory in place of the separate file can't be read.
+        fs::remove_file(&external).unwrap();
+        fs::create_dir(&external).unwrap();
+
+        let mut region = RegionFile::open(&dir, 0, 0).unwrap();
+
+        assert!(region.has_chunk(1, 2));
+        assert_eq!(region.timestamp(1, 2), Some(7));
+        assert!(region.chunk(1, 2).is_err());
+
+        region.set_chunk(0, 0, &blob(0, 0), 0).unwrap();
+        region.save().unwrap();
+
+        fs::remove_dir(&external).unwrap();
+        fs::write(&external, data).unwrap();
+
+        assert_eq!(region.chunk(1, 2).unwrap(), Some(large_blob()));
+
+        let region = RegionFile::open(&dir, 0, 0).unwrap();
+
+        assert_eq!(region.chunk(1, 2).unwrap(), Some(large_blob()));
+        assert_eq!(region.timestamp(1, 2), Some(7));
+        assert_eq!(region.chunk(0, 0).unwrap(), Some(blob(0, 0)));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,97 @@
+use arrow_protocol::packets::version_specific::types::v754::{
+    BiomeEffects, BiomeProperties, BiomeRegistryEntry,
+};
+
+/// The color of water in all biomes.
+const WATER_COLOR: i32 = 4159204;
+/// The color of the fog under water in all biomes.
+const WATER_FOG_COLOR: i32 = 329011;
+/// The color of the fog in all biomes.
+const FOG_COLOR: i32 = 12638463;
+
+/// The biomes of generated worlds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Biome {
+    Ocean,
+    Plains,
+    Desert,
+    Mountains,
+    Forest,
+    Taiga,
+    Beach,
+}
+
+impl Biome {
+    /// All biomes in the order of their ids.
+    pub const ALL: [Biome; 7] = [
+        Self::Ocean,
+        Self::Plains,
+        Self::Desert,
+        Self::Mountains,
+        Self::Forest,
+        Self::Taiga,
+        Self::Beach,
+    ];
+
+    /// Returns the numeric id of the biome, which is the same in all supported protocol versions.
+    pub fn id(self) -> i32 {
+        match self {
+            Self::Ocean => 0,
+            Self::Plains => 1,
+            Self::Desert => 2,
+            Self::Mountains => 3,
+            Self::Forest => 4,
+            Self::Taiga => 5,
+            Self::Beach => 16,
+        }
+    }
+
+    /// Returns the namespaced name of the biome.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ocean => "minecraft:ocean",
+            Self::Plains => "minecraft:plains",
+            Self::Desert => "minecraft:desert",
+            Self::Mountains => "minecraft:mountains",
+            Self::Forest => "minecraft:forest",
+            Self::Taiga => "minecraft:taiga",
+            Self::Beach => "minecraft:beach",
+        }
+    }
+
+    /// Returns the entry of the biome in the biome registry sent since protocol version 751,
+    /// using the properties of vanilla servers.
+    pub fn registry_entry(self) -> BiomeRegistryEntry {
+        let (precipitation, depth, scale, temperature, downfall, category, sky_color) = match self {
+            Self::Ocean => ("rain", -1.0, 0.1, 0.5, 0.5, "ocean", 8103167),
+            Self::Plains => ("rain", 0.125, 0.05, 0.8, 0.4, "plains", 7907327),
+            Self::Desert => ("none", 0.125, 0.05, 2.0, 0.0, "desert", 7254527),
+            Self::Mountains => ("rain", 1.0, 0.5, 0.2, 0.3, "extreme_hills", 8233727),
+            Self::Forest => ("rain", 0.1, 0.2, 0.7, 0.8, "forest", 7972607),
+            Self::Taiga => ("rain", 0.2, 0.2, 0.25, 0.8, "taiga", 8233983),
+            Self::Beach => ("rain", 0.0, 0.025, 0.8, 0.4, "beach", 7907327),
+        };
+
+        BiomeRegistryEntry {
+            name: self.name().to_string(),
+            id: self.id(),
+            element: BiomeProperties {
+                precipitation: precipitation.to_string(),
+                depth,
+                temperature,
+                scale,
+                downfall,
+                category: category.to_string(),
+                temperature_modifier: None,
+                effects: BiomeEffects {
+                    sky_color,
+                    water_fog_color: WATER_FOG_COLOR,
+                    fog_color: FOG_COLOR,
+                    water_color: WATER_COLOR,
+                    ..BiomeEffects::default()
+                },
+                particle: None,
+            },
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use arrow_protocol::{
+    block::{BlockRegistry, BlockState},
+    chunk::{Chunk, SECTION_COUNT},
+};
+
+use super::WorldGenerator;
+use crate::error::{NetError, Result};
+
+/// The layers of the default superflat world.
+pub const DEFAULT_LAYERS: &str = "bedrock,2*dirt,grass_block";
+/// The height of the world in blocks.
+const WORLD_HEIGHT: usize = SECTION_COUNT * 16;
+
+/// Generates superflat worlds, in which every column consists of the same layers of blocks.
+pub struct FlatGenerator {
+    layers: Vec<u32>,
+    chunk: Chunk,
+}
+
+impl FlatGenerator {
+    /// Creates a generator placing a layer of every block state in `layers` from the bottom of
+    /// the world. The block states are canonical ids. Returns an error if there are more layers
+    /// than the world is high.
+    pub fn new(layers: Vec<u32>) -> Result<Self> {
+        if layers.len() > WORLD_HEIGHT {
+            return Err(NetError::InvalidGenerator(format!(
+                "{} layers don't fit into the world height of {}",
+                layers.len(),
+                WORLD_HEIGHT
+            )));
+        }
+
+        let mut chunk = Chunk::new(true);
+
+        for (y, state) in layers.iter().enumerate() {
+            for z in 0..16 {
+                for x in 0..16 {
+                    chunk.set_block(x, y, z, *state);
+                }
+            }
+        }
+
+        Ok(Self { layers, chunk })
+    }
+
+    /// Parses layers like `bedrock,2*dirt,grass_block`, which are block states from the bottom
+    /// to the top separated by commas. A block state may be preceded by the number of layers of
+    /// it and a `*`. Block states without all properties use the closest state of `blocks`.
+    pub fn from_layers(layers: &str, blocks: &BlockRegistry) -> Result<Self> {
+        let mut states = vec![];
+
+        for layer in split_layers(layers) {
+            let (count, state) = match layer.split_once('*') {
+                Some((count, state)) => {
+                    let count = count.trim().parse::<usize>().map_err(|_| {
+                        NetError::InvalidGenerator(format!("Invalid layer count in {}", layer))
+                    })?;
+
+                    (count, state)
+                }
+                None => (1, layer),
+            };
+
+            let state: BlockState = state
+                .parse()
+                .map_err(|e| NetError::InvalidGenerator(format!("{}", e)))?;
+            let id = blocks
+                .closest_canonical_id(&state)
+                .ok_or_else(|| NetError::InvalidGenerator(format!("Unknown block {}", state)))?;
+
+            states.resize(states.len() + count.min(WORLD_HEIGHT + 1), id);
+        }
+
+        Self::new(states)
+    }
+
+    /// Returns the block states of the layers from the bottom to the top.
+    pub fn layers(&self) -> &[u32] {
+        &self.layers
+    }
+}
+
+impl WorldGenerator for FlatGenerator {
+    fn generate(&self, _x: i32, _z: i32) -> Chunk {
+        self.chunk.clone()
+    }
+
+    fn spawn(&self) -> (f64, f64, f64) {
+        (0.5, self.layers.len() as f64, 0.5)
+    }
+
+    fn is_flat(&self) -> bool {
+        true
+    }
+}
+
+/// Splits `layers` at the commas that don't separate the properties of a block state.
+fn split_layers(layers: &str) -> Vec<&str> {
+    let mut split = vec![];
+    let mut start = 0;
+    let mut depth = 0;
+
+    for (i, c) in layers.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ',' if depth == 0 => {
+                split.push(layers[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    split.push(layers[start..].trim());
+    split.retain(|layer| !layer.is_empty());
+    split
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(blocks: &BlockRegistry, state: &str) -> u32 {
+        blocks.canonical_id(&state.parse().unwrap()).unwrap()
+    }
+
+    /// Returns the error message of parsing `layers`.
+    fn error(layers: &str) -> String {
+        match FlatGenerator::from_layers(layers, &BlockRegistry::new()) {
+            Err(NetError::InvalidGenerator(message)) => message,
+            Err(e) => panic!("Unexpected error {} for {}", e, layers),
+            Ok(_) => panic!("{} should be invalid", layers),
+        }
+    }
+
+    #[test]
+    fn default_layers() {
+        let blocks = BlockRegistry::new();
+        let generator = FlatGenerator::from_layers(DEFAULT_LAYERS, &blocks).unwrap();
+        let dirt = canonical(&blocks, "dirt");
+
+        assert_eq!(
+            generator.layers(),
+            &[
+                canonical(&blocks, "bedrock"),
+                dirt,
+                dirt,
+                canonical(&blocks, "grass_block[snowy=false]")
+            ]
+        );
+        assert_eq!(generator.spawn(), (0.5, 4.0, 0.5));
+
+        let chunk = generator.generate(3, -7);
+
+        assert_eq!(chunk.block(15, 2, 15), dirt);
+        assert_eq!(chunk.block(0, 4, 0), 0);
+    }
+
+    #[test]
+    fn properties_containing_commas() {
+        assert_eq!(
+            split_layers(" bedrock, 2*dirt ,grass_block[snowy=true,foo=bar],,"),
+            vec!["bedrock", "2*dirt", "grass_block[snowy=true,foo=bar]"]
+        );
+
+        let blocks = BlockRegistry::new();
+        let generator = FlatGenerator::from_layers(
+            "minecraft:grass_block[snowy=true,foo=bar], 0*stone, 2 * water[level=3]",
+            &blocks,
+        )
+        .unwrap();
+        let water = canonical(&blocks, "water[level=3]");
+
+        assert_eq!(
+            generator.layers(),
+            &[canonical(&blocks, "grass_block[snowy=true]"), water, water]
+        );
+    }
+
+    #[test]
+    fn invalid_layers() {
+        assert_eq!(error("bedrock,x*dirt"), "Invalid layer count in x*dirt");
+        assert_eq!(error("-1*dirt"), "Invalid layer count in -1*dirt");
+        assert_eq!(error("*dirt"), "Invalid layer count in *dirt");
+        assert_eq!(error("2*cheese"), "Unknown block minecraft:cheese");
+        assert_eq!(error("dirt[snowy]"), "Invalid block state dirt[snowy]");
+    }
+
+    #[test]
+    fn layers_must_fit_into_the_world() {
+        let blocks = BlockRegistry::new();
+        let full = format!("{}*stone", WORLD_HEIGHT);
+
+        assert_eq!(
+            FlatGenerator::from_layers(&full, &blocks)
+                .unwrap()
+                .layers()
+                .len(),
+            WORLD_HEIGHT
+        );
+        assert_eq!(
+            error(&format!("{},dirt", full)),
+            format!(
+                "{} layers don't fit into the world height of {}",
+                WORLD_HEIGHT + 1,
+                WORLD_HEIGHT
+            )
+        );
+        // Huge counts are rejected without allocating all layers.
+        assert_eq!(
+            error("4000000000*stone"),
+            format!(
+                "{} layers don't fit into the world height of {}",
+                WORLD_HEIGHT + 1,
+                WORLD_HEIGHT
+            )
+        );
+    }
+}
@@ -0,0 +1,33 @@
+/// The generator of superflat worlds.
+mod flat;
+/// The generator of overworld terrain.
+mod noise;
+/// Perlin noise and the random numbers seeding it.
+mod perlin;
+/// The generator of empty worlds.
+mod void;
+
+pub use flat::{FlatGenerator, DEFAULT_LAYERS};
+pub use noise::{NoiseGenerator, SEA_LEVEL};
+pub use void::VoidGenerator;
+
+use arrow_protocol::chunk::Chunk;
+
+/// Generates the chunks of a world that were not generated before.
+///
+/// The block states of the generated chunks are canonical ids of the
+/// [`BlockRegistry`](arrow_protocol::block::BlockRegistry) of the server, which are converted to
+/// the ids of a protocol version when a chunk is sent.
+pub trait WorldGenerator: Send + Sync {
+    /// Generates the chunk at the chunk coordinates `x` and `z`.
+    fn generate(&self, x: i32, z: i32) -> Chunk;
+
+    /// Returns the position players spawn at.
+    fn spawn(&self) -> (f64, f64, f64);
+
+    /// Returns whether the world is a superflat world. Clients show the horizon of superflat
+    /// worlds at the bottom of the world instead of at the sea level.
+    fn is_flat(&self) -> bool {
+        false
+    }
+}
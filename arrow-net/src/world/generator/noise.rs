@@ -0,0 +1,428 @@
+use arrow_protocol::{
+    block::{BlockRegistry, BlockState},
+    chunk::{
+        storage::PalettedContainer, Chunk, ChunkSection, NibbleArray, AIR, BIOME_COUNT,
+        SECTION_COUNT, SECTION_VOLUME,
+    },
+};
+
+use super::{
+    perlin::{Octaves, Random},
+    WorldGenerator,
+};
+use crate::{
+    error::{NetError, Result},
+    world::biome::Biome,
+};
+
+/// The y coordinate of the water surface.
+pub const SEA_LEVEL: i32 = 63;
+/// Caves at and below this y coordinate are filled with lava instead of air.
+const LAVA_LEVEL: i32 = 10;
+/// The height of the world in blocks.
+const WORLD_HEIGHT: i32 = SECTION_COUNT as i32 * 16;
+/// Caves are carved where both cave noises are closer to 0 than this.
+const CAVE_THRESHOLD: f64 = 0.05;
+/// The number of chunks searched for land in every direction when looking for the spawn.
+const SPAWN_SEARCH_RADIUS: i32 = 64;
+
+/// Tells apart the random generators of the same chunk.
+const TERRAIN_SALT: u64 = 1;
+const ORE_SALT: u64 = 2;
+const DECORATION_SALT: u64 = 3;
+
+/// The block states placed by the generator as canonical ids.
+struct Blocks {
+    stone: u32,
+    granite: u32,
+    diorite: u32,
+    andesite: u32,
+    grass_block: u32,
+    dirt: u32,
+    coarse_dirt: u32,
+    podzol: u32,
+    sand: u32,
+    gravel: u32,
+    bedrock: u32,
+    water: u32,
+    lava: u32,
+    coal_ore: u32,
+    iron_ore: u32,
+    gold_ore: u32,
+    oak_sapling: u32,
+    spruce_sapling: u32,
+    birch_sapling: u32,
+}
+
+/// The ores placed in every chunk: the ore, the number of veins, the highest y coordinate of
+/// a vein and the number of blocks of a vein.
+struct Ore {
+    state: u32,
+    veins: u32,
+    max_y: u32,
+    size: u32,
+}
+
+/// The terrain of a column of blocks.
+struct Column {
+    /// The y coordinate of the highest block that isn't air or water.
+    height: i32,
+    biome: Biome,
+}
+
+/// Generates overworld terrain with hills, mountains, oceans, biomes, caves and ores from a
+/// seed. The same seed always generates the same world.
+pub struct NoiseGenerator {
+    seed: i64,
+    blocks: Blocks,
+    continents: Octaves,
+    hills: Octaves,
+    mountains: Octaves,
+    temperature: Octaves,
+    humidity: Octaves,
+    caves: (Octaves, Octaves),
+    stone: Octaves,
+    spawn: (f64, f64, f64),
+}
+
+impl NoiseGenerator {
+    /// Creates a generator for the world of `seed` placing block states of `blocks`. Returns an
+    /// error if a block the generator places is unknown.
+    pub fn new(seed: i64, blocks: &BlockRegistry) -> Result<Self> {
+        let block = |name: &str| {
+            let state: BlockState = name
+                .parse()
+                .map_err(|e| NetError::InvalidGenerator(format!("{}", e)))?;
+
+            blocks
+                .closest_canonical_id(&state)
+                .ok_or_else(|| NetError::InvalidGenerator(format!("Unknown block {}", state)))
+        };
+
+        let blocks = Blocks {
+            stone: block("minecraft:stone")?,
+            granite: block("minecraft:granite")?,
+            diorite: block("minecraft:diorite")?,
+            andesite: block("minecraft:andesite")?,
+            grass_block: block("minecraft:grass_block")?,
+            dirt: block("minecraft:dirt")?,
+            coarse_dirt: block("minecraft:coarse_dirt")?,
+            podzol: block("minecraft:podzol")?,
+            sand: block("minecraft:sand")?,
+            gravel: block("minecraft:gravel")?,
+            bedrock: block("minecraft:bedrock")?,
+            water: block("minecraft:water")?,
+            lava: block("minecraft:lava")?,
+            coal_ore: block("minecraft:coal_ore")?,
+            iron_ore: block("minecraft:iron_ore")?,
+            gold_ore: block("minecraft:gold_ore")?,
+            oak_sapling: block("minecraft:oak_sapling")?,
+            spruce_sapling: block("minecraft:spruce_sapling")?,
+            birch_sapling: block("minecraft:birch_sapling")?,
+        };
+
+        let mut random = Random::new(seed);
+        let mut generator = Self {
+            seed,
+            blocks,
+            continents: Octaves::new(&mut random, 4, 1.0 / 1024.0),
+            hills: Octaves::new(&mut random, 4, 1.0 / 128.0),
+            mountains: Octaves::new(&mut random, 3, 1.0 / 512.0),
+            temperature: Octaves::new(&mut random, 2, 1.0 / 1024.0),
+            humidity: Octaves::new(&mut random, 2, 1.0 / 768.0),
+            caves: (
+                Octaves::new(&mut random, 2, 1.0 / 64.0),
+                Octaves::new(&mut random, 2, 1.0 / 64.0),
+            ),
+            stone: Octaves::new(&mut random, 2, 1.0 / 24.0),
+            spawn: (0.5, 0.0, 0.5),
+        };
+
+        generator.spawn = generator.find_spawn();
+
+        Ok(generator)
+    }
+
+    /// Returns the seed of the world.
+    pub fn seed(&self) -> i64 {
+        self.seed
+    }
+
+    /// Returns the terrain of the column at `x` and `z`.
+    fn column(&self, x: i32, z: i32) -> Column {
+        let (x, z) = (x as f64, z as f64);
+        let continent = self.continents.sample_2d(x, z);
+        let hills = self.hills.sample_2d(x, z);
+        let mountains = (self.mountains.sample_2d(x, z) - 0.15).max(0.0);
+
+        let height = SEA_LEVEL as f64 + 4.0 + continent * 60.0 + hills * 12.0 + mountains * 180.0;
+        let height = (height as i32).clamp(LAVA_LEVEL + 10, WORLD_HEIGHT - 32);
+
+        let temperature = self.temperature.sample_2d(x, z);
+        let humidity = self.humidity.sample_2d(x, z);
+
+        let biome = if height < SEA_LEVEL - 3 {
+            Biome::Ocean
+        } else if height <= SEA_LEVEL + 1 {
+            Biome::Beach
+        } else if height > SEA_LEVEL + 40 {
+            Biome::Mountains
+        } else if temperature > 0.15 && humidity < 0.0 {
+            Biome::Desert
+        } else if temperature < -0.15 {
+            Biome::Taiga
+        } else if humidity > 0.05 {
+            Biome::Forest
+        } else {
+            Biome::Plains
+        };
+
+        Column { height, biome }
+    }
+
+    /// Returns whether the block at `x`, `y` and `z` is carved out by a cave. Caves don't reach
+    /// into the water of oceans and rivers.
+    fn is_cave(&self, x: i32, y: i32, z: i32, column: &Column) -> bool {
+        let max_y = if column.height <= SEA_LEVEL + 1 {
+            column.height - 6
+        } else {
+            column.height
+        };
+
+        if y < 5 || y > max_y {
+            return false;
+        }
+
+        let (x, y, z) = (x as f64, y as f64 * 2.0, z as f64);
+
+        self.caves.0.sample(x, y, z).abs() < CAVE_THRESHOLD
+            && self.caves.1.sample(x, y, z).abs() < CAVE_THRESHOLD
+    }
+
+    /// Returns the stone placed at `x`, `y` and `z`, which may be a blob of granite, diorite or
+    /// andesite.
+    fn stone(&self, x: i32, y: i32, z: i32) -> u32 {
+        let variants = [
+            self.blocks.granite,
+            self.blocks.diorite,
+            self.blocks.andesite,
+        ];
+
+        for (i, variant) in variants.iter().enumerate() {
+            let offset = i as f64 * 1000.0;
+
+            if self.stone.sample(x as f64 + offset, y as f64, z as f64) > 0.35 {
+                return *variant;
+            }
+        }
+
+        self.blocks.stone
+    }
+
+    /// Returns the top block and the blocks below it of a column in `biome`.
+    fn surface(&self, column: &Column, random: &mut Random) -> (u32, u32) {
+        let blocks = &self.blocks;
+
+        match column.biome {
+            Biome::Ocean if column.height < SEA_LEVEL - 12 => (blocks.gravel, blocks.gravel),
+            Biome::Ocean | Biome::Beach | Biome::Desert => (blocks.sand, blocks.sand),
+            Biome::Mountains if column.height > SEA_LEVEL + 55 => (blocks.stone, blocks.stone),
+            Biome::Taiga => match random.next_below(4) {
+                0 => (blocks.podzol, blocks.dirt),
+                1 => (blocks.coarse_dirt, blocks.dirt),
+                _ => (blocks.grass_block, blocks.dirt),
+            },
+            _ => (blocks.grass_block, blocks.dirt),
+        }
+    }
+
+    /// Returns the sapling placed on a block of grass in `biome`, if any.
+    fn sapling(&self, biome: Biome, random: &mut Random) -> Option<u32> {
+        let (chance, sapling) = match biome {
+            Biome::Forest if random.next_below(3) == 0 => (12, self.blocks.birch_sapling),
+            Biome::Forest => (12, self.blocks.oak_sapling),
+            Biome::Taiga => (16, self.blocks.spruce_sapling),
+            Biome::Plains => (96, self.blocks.oak_sapling),
+            Biome::Mountains => (64, self.blocks.spruce_sapling),
+            _ => return None,
+        };
+
+        if random.next_below(chance) == 0 {
+            Some(sapling)
+        } else {
+            None
+        }
+    }
+
+    /// Places the ore veins of the chunk at `chunk_x` and `chunk_z` into `states`. Veins are
+    /// kept within the chunk and only replace stone.
+    fn place_ores(&self, chunk_x: i32, chunk_z: i32, states: &mut [u32]) {
+        let mut random = Random::at(self.seed, chunk_x, chunk_z, ORE_SALT);
+        let ores = [
+            Ore {
+                state: self.blocks.coal_ore,
+                veins: 20,
+                max_y: 128,
+                size: 12,
+            },
+            Ore {
+                state: self.blocks.iron_ore,
+                veins: 16,
+                max_y: 64,
+                size: 8,
+            },
+            Ore {
+                state: self.blocks.gold_ore,
+                veins: 2,
+                max_y: 32,
+                size: 8,
+            },
+        ];
+
+        for ore in &ores {
+            for _ in 0..ore.veins {
+                let mut x = random.next_below(16) as i32;
+                let mut y = random.next_below(ore.max_y) as i32;
+                let mut z = random.next_below(16) as i32;
+
+                for _ in 0..ore.size {
+                    let index = block_index(x, y, z);
+
+                    if states[index] == self.blocks.stone {
+                        states[index] = ore.state;
+                    }
+
+                    match random.next_below(6) {
+                        0 => x = (x + 1).min(15),
+                        1 => x = (x - 1).max(0),
+                        2 => y = (y + 1).min(WORLD_HEIGHT - 1),
+                        3 => y = (y - 1).max(1),
+                        4 => z = (z + 1).min(15),
+                        _ => z = (z - 1).max(0),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Searches for the closest column on land around the origin that isn't carved out by a
+    /// cave, starting at the origin.
+    fn find_spawn(&self) -> (f64, f64, f64) {
+        for radius in 0..=SPAWN_SEARCH_RADIUS {
+            for chunk_x in -radius..=radius {
+                for chunk_z in -radius..=radius {
+                    if chunk_x.abs() != radius && chunk_z.abs() != radius {
+                        continue;
+                    }
+
+                    let (x, z) = (chunk_x * 16 + 8, chunk_z * 16 + 8);
+                    let column = self.column(x, z);
+
+                    if column.height > SEA_LEVEL
+                        && !self.is_cave(x, column.height, z, &column)
+                        && !self.is_cave(x, column.height - 1, z, &column)
+                    {
+                        return (x as f64 + 0.5, column.height as f64 + 1.0, z as f64 + 0.5);
+                    }
+                }
+            }
+        }
+
+        (
+            0.5,
+            self.column(0, 0).height.max(SEA_LEVEL) as f64 + 1.0,
+            0.5,
+        )
+    }
+}
+
+impl WorldGenerator for NoiseGenerator {
+    fn generate(&self, chunk_x: i32, chunk_z: i32) -> Chunk {
+        let blocks = &self.blocks;
+        let mut random = Random::at(self.seed, chunk_x, chunk_z, TERRAIN_SALT);
+        let mut decoration = Random::at(self.seed, chunk_x, chunk_z, DECORATION_SALT);
+        let mut states = vec![AIR; SECTION_COUNT * SECTION_VOLUME];
+        let mut biomes = vec![0; BIOME_COUNT];
+
+        for z in 0..16 {
+            for x in 0..16 {
+                let (world_x, world_z) = (chunk_x * 16 + x, chunk_z * 16 + z);
+                let column = self.column(world_x, world_z);
+                let (top, filler) = self.surface(&column, &mut random);
+                let depth = 3 + random.next_below(2) as i32;
+
+                for y in 0..=column.height.max(SEA_LEVEL) {
+                    let state = if y == 0 || y < 5 && random.next_below(5) as i32 >= y {
+                        blocks.bedrock
+                    } else if y > column.height {
+                        blocks.water
+                    } else if self.is_cave(world_x, y, world_z, &column) {
+                        if y <= LAVA_LEVEL {
+                            blocks.lava
+                        } else {
+                            AIR
+                        }
+                    } else if y == column.height {
+                        top
+                    } else if y > column.height - depth {
+                        filler
+                    } else {
+                        self.stone(world_x, y, world_z)
+                    };
+
+                    states[block_index(x, y, z)] = state;
+                }
+
+                if x % 4 == 1 && z % 4 == 1 {
+                    for y in 0..BIOME_COUNT / 16 {
+                        biomes[y << 4 | (z as usize >> 2) << 2 | x as usize >> 2] =
+                            column.biome.id();
+                    }
+                }
+
+                // Saplings only grow on grass and podzol that wasn't carved out by a cave.
+                let grows = top == blocks.grass_block || top == blocks.podzol;
+
+                if grows && states[block_index(x, column.height, z)] == top {
+                    if let Some(sapling) = self.sapling(column.biome, &mut decoration) {
+                        states[block_index(x, column.height + 1, z)] = sapling;
+                    }
+                }
+            }
+        }
+
+        self.place_ores(chunk_x, chunk_z, &mut states);
+
+        let mut chunk = Chunk::new(true);
+
+        for (index, section) in states.chunks(SECTION_VOLUME).enumerate() {
+            if section.iter().all(|state| *state == AIR) {
+                continue;
+            }
+
+            let states = PalettedContainer::from_values(SECTION_VOLUME, section.iter().copied());
+
+            chunk.set_section(
+                index,
+                Some(ChunkSection::from_parts(
+                    states,
+                    NibbleArray::new(0),
+                    NibbleArray::new(15),
+                )),
+            );
+        }
+
+        chunk.set_biomes(biomes);
+
+        chunk
+    }
+
+    fn spawn(&self) -> (f64, f64, f64) {
+        self.spawn
+    }
+}
+
+/// Returns the index of the block at `x`, `y` and `z` within the block states of a chunk.
+fn block_index(x: i32, y: i32, z: i32) -> usize {
+    (y << 8 | z << 4 | x) as usize
+}
@@ -0,0 +1,182 @@
+/// A SplitMix64 random number generator. Unlike the generators of `rand`, its output for a seed
+/// never changes, which keeps generated worlds the same across versions of Arrow.
+pub struct Random(u64);
+
+/// Improved Perlin noise with a permutation and an offset chosen by a [`Random`]. Values are
+/// roughly within -1 and 1.
+pub struct Perlin {
+    permutation: [u8; 512],
+    offset: (f64, f64, f64),
+}
+
+/// Octaves of Perlin noise, each with twice the frequency and half the amplitude of the one
+/// before. Values are roughly within -1 and 1.
+pub struct Octaves {
+    octaves: Vec<Perlin>,
+    frequency: f64,
+}
+
+impl Random {
+    pub fn new(seed: i64) -> Self {
+        Self(seed as u64)
+    }
+
+    /// Creates a generator for the position `x` and `z` in the world of `seed`. `salt` tells
+    /// apart the generators of the same position.
+    pub fn at(seed: i64, x: i32, z: i32, salt: u64) -> Self {
+        let mut random = Self::new(seed);
+        let hash = random.next_u64()
+            ^ (x as u64).wrapping_mul(0x9E3779B97F4A7C15)
+            ^ (z as u64).wrapping_mul(0xC2B2AE3D27D4EB4F)
+            ^ salt.wrapping_mul(0x165667B19E3779F9);
+
+        Self(hash)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+
+        let mut z = self.0;
+        z = (z ^ z >> 30).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ z >> 27).wrapping_mul(0x94D049BB133111EB);
+        z ^ z >> 31
+    }
+
+    /// Returns a number from 0 up to but not including 1.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a number from 0 up to but not including `bound`.
+    pub fn next_below(&mut self, bound: u32) -> u32 {
+        (((self.next_u64() >> 32) * bound as u64) >> 32) as u32
+    }
+}
+
+impl Perlin {
+    pub fn new(random: &mut Random) -> Self {
+        let mut permutation = [0; 512];
+
+        for (i, value) in permutation.iter_mut().take(256).enumerate() {
+            *value = i as u8;
+        }
+
+        for i in (1..256).rev() {
+            let j = random.next_below(i as u32 + 1) as usize;
+
+            permutation.swap(i, j);
+        }
+
+        let (low, high) = permutation.split_at_mut(256);
+        high.copy_from_slice(low);
+
+        let offset = (
+            random.next_f64() * 256.0,
+            random.next_f64() * 256.0,
+            random.next_f64() * 256.0,
+        );
+
+        Self {
+            permutation,
+            offset,
+        }
+    }
+
+    /// Returns the noise at `x`, `y` and `z`.
+    pub fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+        let (x, y, z) = (x + self.offset.0, y + self.offset.1, z + self.offset.2);
+        let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+        let (x, y, z) = (x - x0, y - y0, z - z0);
+        let xi = (x0 as i64 & 255) as usize;
+        let yi = (y0 as i64 & 255) as usize;
+        let zi = (z0 as i64 & 255) as usize;
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+        let p = &self.permutation;
+
+        let a = p[xi] as usize + yi;
+        let aa = p[a] as usize + zi;
+        let ab = p[a + 1] as usize + zi;
+        let b = p[xi + 1] as usize + yi;
+        let ba = p[b] as usize + zi;
+        let bb = p[b + 1] as usize + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, grad(p[aa], x, y, z), grad(p[ba], x - 1.0, y, z)),
+                lerp(
+                    u,
+                    grad(p[ab], x, y - 1.0, z),
+                    grad(p[bb], x - 1.0, y - 1.0, z),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(p[aa + 1], x, y, z - 1.0),
+                    grad(p[ba + 1], x - 1.0, y, z - 1.0),
+                ),
+                lerp(
+                    u,
+                    grad(p[ab + 1], x, y - 1.0, z - 1.0),
+                    grad(p[bb + 1], x - 1.0, y - 1.0, z - 1.0),
+                ),
+            ),
+        )
+    }
+}
+
+impl Octaves {
+    /// Creates `count` octaves, the first one sampled with `frequency`.
+    pub fn new(random: &mut Random, count: usize, frequency: f64) -> Self {
+        Self {
+            octaves: (0..count).map(|_| Perlin::new(random)).collect(),
+            frequency,
+        }
+    }
+
+    /// Returns the noise at `x`, `y` and `z`.
+    pub fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+        let mut frequency = self.frequency;
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut max = 0.0;
+
+        for octave in &self.octaves {
+            total += octave.sample(x * frequency, y * frequency, z * frequency) * amplitude;
+            max += amplitude;
+            frequency *= 2.0;
+            amplitude /= 2.0;
+        }
+
+        total / max
+    }
+
+    /// Returns the noise at `x` and `z`.
+    pub fn sample_2d(&self, x: f64, z: f64) -> f64 {
+        self.sample(x, 0.0, z)
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// Returns the dot product of the gradient chosen by `hash` and the distance `x`, `y` and `z`.
+fn grad(hash: u8, x: f64, y: f64, z: f64) -> f64 {
+    let hash = hash & 15;
+    let u = if hash < 8 { x } else { y };
+    let v = match hash {
+        0..=3 => y,
+        12 | 14 => x,
+        _ => z,
+    };
+
+    (if hash & 1 == 0 { u } else { -u }) + (if hash & 2 == 0 { v } else { -v })
+}
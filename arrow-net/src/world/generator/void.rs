@@ -0,0 +1,53 @@
+use arrow_protocol::chunk::Chunk;
+
+use super::WorldGenerator;
+
+/// The y coordinate of the spawn platform.
+const PLATFORM_Y: usize = 64;
+/// The number of blocks the spawn platform extends from its center in every direction.
+const PLATFORM_RADIUS: i32 = 16;
+
+/// Generates worlds without any blocks apart from an optional platform around the spawn.
+pub struct VoidGenerator {
+    platform: Option<u32>,
+}
+
+impl VoidGenerator {
+    /// Creates a generator placing a square platform of the block state `platform` around the
+    /// spawn. The block state is a canonical id.
+    pub fn new(platform: Option<u32>) -> Self {
+        Self { platform }
+    }
+
+    /// Returns the block state of the spawn platform.
+    pub fn platform(&self) -> Option<u32> {
+        self.platform
+    }
+}
+
+impl WorldGenerator for VoidGenerator {
+    fn generate(&self, x: i32, z: i32) -> Chunk {
+        let mut chunk = Chunk::new(true);
+        let platform = match self.platform {
+            Some(platform) => platform,
+            None => return chunk,
+        };
+
+        for block_z in 0..16 {
+            for block_x in 0..16 {
+                let world_x = x * 16 + block_x as i32;
+                let world_z = z * 16 + block_z as i32;
+
+                if world_x.abs() <= PLATFORM_RADIUS && world_z.abs() <= PLATFORM_RADIUS {
+                    chunk.set_block(block_x, PLATFORM_Y, block_z, platform);
+                }
+            }
+        }
+
+        chunk
+    }
+
+    fn spawn(&self) -> (f64, f64, f64) {
+        (0.5, PLATFORM_Y as f64 + 1.0, 0.5)
+    }
+}
@@ -0,0 +1,185 @@
+/// The worlds of vanilla servers.
+pub mod anvil;
+/// The biomes of generated worlds.
+pub mod biome;
+/// The generators creating the chunks of new worlds.
+pub mod generator;
+/// The threads generating chunks.
+mod pool;
+/// The seeds of generated worlds.
+pub mod seed;
+
+use std::{collections::HashSet, sync::Arc, thread};
+
+use arrow_protocol::chunk::Chunk;
+use dashmap::{mapref::entry::Entry, DashMap};
+use log::error;
+use parking_lot::{Mutex, RwLock};
+
+use crate::error::{NetError, Result};
+use anvil::AnvilWorld;
+use generator::WorldGenerator;
+use pool::WorkerPool;
+
+/// The chunks of the world. Chunks are loaded from the [`AnvilWorld`] storing the world or
+/// generated the first time they are needed and kept afterwards. Their block states are
+/// canonical ids of the [`BlockRegistry`](arrow_protocol::block::BlockRegistry) of the server.
+pub struct World {
+    generator: RwLock<Arc<dyn WorldGenerator>>,
+    storage: RwLock<Option<Arc<AnvilWorld>>>,
+    spawn: RwLock<Option<(f64, f64, f64)>>,
+    chunks: DashMap<(i32, i32), Arc<Chunk>>,
+    modified: Mutex<HashSet<(i32, i32)>>,
+    pool: WorkerPool,
+}
+
+impl World {
+    /// Creates a world without chunks using `generator`. Chunks are generated by a thread for
+    /// every CPU.
+    pub fn new(generator: Arc<dyn WorldGenerator>) -> Self {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+
+        Self {
+            generator: RwLock::new(generator),
+            storage: RwLock::new(None),
+            spawn: RwLock::new(None),
+            chunks: DashMap::new(),
+            modified: Mutex::new(HashSet::new()),
+            pool: WorkerPool::new(threads),
+        }
+    }
+
+    /// Returns the generator of the chunks.
+    pub fn generator(&self) -> Arc<dyn WorldGenerator> {
+        self.generator.read().clone()
+    }
+
+    /// Replaces the generator of the chunks. Chunks generated before are dropped.
+    pub fn set_generator(&self, generator: Arc<dyn WorldGenerator>) {
+        *self.generator.write() = generator;
+        self.chunks.clear();
+        self.modified.lock().clear();
+    }
+
+    /// Returns the world chunks are loaded from and saved to.
+    pub fn storage(&self) -> Option<Arc<AnvilWorld>> {
+        self.storage.read().clone()
+    }
+
+    /// Replaces the world chunks are loaded from and saved to. Without one chunks are only kept
+    /// in memory. Chunks loaded or generated before are dropped.
+    pub fn set_storage(&self, storage: Option<Arc<AnvilWorld>>) {
+        *self.storage.write() = storage;
+        self.chunks.clear();
+        self.modified.lock().clear();
+    }
+
+    /// Returns the chunk at the chunk coordinates `x` and `z`, loading or generating it on a
+    /// worker thread if needed. A chunk whose generation panicked is replaced with an empty one.
+    pub async fn chunk(&self, x: i32, z: i32) -> Arc<Chunk> {
+        if let Some(chunk) = self.chunks.get(&(x, z)) {
+            return chunk.clone();
+        }
+
+        let generator = self.generator();
+        let storage = self.storage();
+        let loaded = self.pool.run(move || {
+            if let Some(storage) = &storage {
+                match storage.load_chunk(x, z) {
+                    Ok(Some(chunk)) => return (chunk, false),
+                    Ok(None) => {}
+                    Err(e) => {
+                        error!("Failed loading the chunk at {} {}: {}", x, z, e);
+
+                        // Not saved, so the broken chunk isn't overwritten.
+                        return (generator.generate(x, z), false);
+                    }
+                }
+            }
+
+            (generator.generate(x, z), storage.is_some())
+        });
+
+        let (chunk, modified) = match loaded.await {
+            Some(loaded) => loaded,
+            None => {
+                error!("Generating the chunk at {} {} failed.", x, z);
+
+                (Chunk::new(true), false)
+            }
+        };
+
+        // Generated without holding a lock, a chunk generated in the meantime wins.
+        match self.chunks.entry((x, z)) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => {
+                if modified {
+                    self.modified.lock().insert((x, z));
+                }
+
+                entry.insert(Arc::new(chunk)).value().clone()
+            }
+        }
+    }
+
+    /// Replaces the chunk at the chunk coordinates `x` and `z`. The chunk is saved by the next
+    /// [`World::save`].
+    pub fn set_chunk(&self, x: i32, z: i32, chunk: Chunk) {
+        self.chunks.insert((x, z), Arc::new(chunk));
+        self.modified.lock().insert((x, z));
+    }
+
+    /// Saves the chunks that were generated or replaced since the last save on a worker thread
+    /// and returns their number. Does nothing if the world has no storage.
+    pub async fn save(&self) -> Result<usize> {
+        let storage = match self.storage() {
+            Some(storage) => storage,
+            None => return Ok(0),
+        };
+
+        let positions: Vec<(i32, i32)> = self.modified.lock().drain().collect();
+        let chunks: Vec<(i32, i32, Arc<Chunk>)> = positions
+            .iter()
+            .filter_map(|(x, z)| Some((*x, *z, self.chunks.get(&(*x, *z))?.clone())))
+            .collect();
+        let count = chunks.len();
+
+        let saved = self
+            .pool
+            .run(move || storage.save_chunks(&chunks))
+            .await
+            .unwrap_or_else(|| Err(NetError::IoError("Saving the chunks panicked".to_string())));
+
+        if let Err(e) = saved {
+            // Saved again the next time.
+            self.modified.lock().extend(positions);
+
+            return Err(e);
+        }
+
+        Ok(count)
+    }
+
+    /// Returns the number of chunks generated at the same time.
+    pub fn generator_threads(&self) -> usize {
+        self.pool.threads()
+    }
+
+    /// Returns the position players spawn at, which is the spawn of the generator unless it was
+    /// set.
+    pub fn spawn(&self) -> (f64, f64, f64) {
+        match *self.spawn.read() {
+            Some(spawn) => spawn,
+            None => self.generator().spawn(),
+        }
+    }
+
+    pub fn set_spawn(&self, spawn: Option<(f64, f64, f64)>) {
+        *self.spawn.write() = spawn;
+    }
+
+    /// Returns whether the world is a superflat world.
+    pub fn is_flat(&self) -> bool {
+        self.generator().is_flat()
+    }
+}
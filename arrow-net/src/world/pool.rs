@@ -0,0 +1,71 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc},
+    thread,
+};
+
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
+
+/// A job run by a worker thread.
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A fixed number of threads running CPU heavy work like generating chunks, so it doesn't block
+/// the threads of the async runtime running the tick loop and the clients.
+pub struct WorkerPool {
+    sender: Mutex<mpsc::Sender<Job>>,
+    threads: usize,
+}
+
+impl WorkerPool {
+    /// Starts a pool of `threads` worker threads, at least one. The threads stop once the pool is
+    /// dropped.
+    pub fn new(threads: usize) -> Self {
+        let threads = threads.max(1);
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for i in 0..threads {
+            let receiver = receiver.clone();
+
+            thread::Builder::new()
+                .name(format!("worker-{}", i))
+                .spawn(move || loop {
+                    let job = match receiver.lock().recv() {
+                        Ok(job) => job,
+                        Err(_) => return,
+                    };
+
+                    // A panicking job drops its result sender, which is reported by `run`.
+                    let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                })
+                .unwrap();
+        }
+
+        Self {
+            sender: Mutex::new(sender),
+            threads,
+        }
+    }
+
+    /// Returns the number of worker threads.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Runs `f` on a worker thread and returns its result, or `None` if `f` panicked.
+    pub async fn run<T, F>(&self, f: F) -> Option<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            let _ = sender.send(f());
+        });
+
+        self.sender.lock().send(job).ok()?;
+
+        receiver.await.ok()
+    }
+}
@@ -0,0 +1,61 @@
+use sha2::{Digest, Sha256};
+
+/// Parses the seed of a world like vanilla servers do. Numbers are used as they are, other text
+/// is hashed like Java strings and an empty seed is replaced with a random one.
+pub fn parse_seed(seed: &str) -> i64 {
+    let seed = seed.trim();
+
+    if seed.is_empty() {
+        return rand::random();
+    }
+
+    match seed.parse() {
+        Ok(seed) => seed,
+        Err(_) => seed
+            .encode_utf16()
+            .fold(0i32, |hash, c| hash.wrapping_mul(31).wrapping_add(c as i32))
+            as i64,
+    }
+}
+
+/// Returns the hashed seed sent in the Join Game packet, which clients use for biome noise. It
+/// is the first 8 bytes of the SHA-256 hash of the seed, both in little endian order as vanilla
+/// servers compute it.
+pub fn hashed_seed(seed: i64) -> i64 {
+    let hash = Sha256::digest(&seed.to_le_bytes());
+    let mut prefix = [0; 8];
+
+    prefix.copy_from_slice(&hash[..8]);
+
+    i64::from_le_bytes(prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_seeds() {
+        assert_eq!(parse_seed("1234567890"), 1234567890);
+        assert_eq!(parse_seed(" -4172144997902289642 "), -4172144997902289642);
+        assert_eq!(parse_seed("-9223372036854775808"), i64::MIN);
+    }
+
+    #[test]
+    fn text_seeds() {
+        assert_eq!(parse_seed("hello"), 99162322);
+        assert_eq!(parse_seed("Arrow"), 63538153);
+        // Too large for a long, so it is hashed like text.
+        assert_eq!(parse_seed("9223372036854775808"), -1773151197);
+        // Characters outside the BMP are hashed as their UTF-16 surrogates.
+        assert_eq!(parse_seed("Häßlich 🏹"), 1221362008);
+    }
+
+    #[test]
+    fn hashed_seeds() {
+        assert_eq!(hashed_seed(0), 8794265229978523055);
+        assert_eq!(hashed_seed(1), -6467378160175308932);
+        // The seed of the title screen panorama.
+        assert_eq!(hashed_seed(-4172144997902289642), 2159143436479834350);
+    }
+}
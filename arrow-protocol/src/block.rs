@@ -0,0 +1,879 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+use serde::Deserialize;
+
+use crate::packets::error::PacketError;
+
+/// The protocol versions using numeric ids of the form `id << 4 | meta`.
+pub const LEGACY_PROTOCOL_VERSIONS: RangeInclusive<i32> = 47..=392;
+/// The protocol versions using the ids of the global palette introduced by the flattening.
+pub const FLATTENED_PROTOCOL_VERSIONS: RangeInclusive<i32> = 393..=754;
+
+/// The block states known without loading any tables and their ids since the flattening. These
+/// ids are the same in all supported releases since 1.13.
+const BUILTIN_FLATTENED: &[(&str, u32)] = &[
+    ("minecraft:air", 0),
+    ("minecraft:stone", 1),
+    ("minecraft:granite", 2),
+    ("minecraft:polished_granite", 3),
+    ("minecraft:diorite", 4),
+    ("minecraft:polished_diorite", 5),
+    ("minecraft:andesite", 6),
+    ("minecraft:polished_andesite", 7),
+    ("minecraft:grass_block[snowy=true]", 8),
+    ("minecraft:grass_block[snowy=false]", 9),
+    ("minecraft:dirt", 10),
+    ("minecraft:coarse_dirt", 11),
+    ("minecraft:podzol[snowy=true]", 12),
+    ("minecraft:podzol[snowy=false]", 13),
+    ("minecraft:cobblestone", 14),
+    ("minecraft:oak_planks", 15),
+    ("minecraft:spruce_planks", 16),
+    ("minecraft:birch_planks", 17),
+    ("minecraft:jungle_planks", 18),
+    ("minecraft:acacia_planks", 19),
+    ("minecraft:dark_oak_planks", 20),
+    ("minecraft:oak_sapling[stage=0]", 21),
+    ("minecraft:oak_sapling[stage=1]", 22),
+    ("minecraft:spruce_sapling[stage=0]", 23),
+    ("minecraft:spruce_sapling[stage=1]", 24),
+    ("minecraft:birch_sapling[stage=0]", 25),
+    ("minecraft:birch_sapling[stage=1]", 26),
+    ("minecraft:jungle_sapling[stage=0]", 27),
+    ("minecraft:jungle_sapling[stage=1]", 28),
+    ("minecraft:acacia_sapling[stage=0]", 29),
+    ("minecraft:acacia_sapling[stage=1]", 30),
+    ("minecraft:dark_oak_sapling[stage=0]", 31),
+    ("minecraft:dark_oak_sapling[stage=1]", 32),
+    ("minecraft:bedrock", 33),
+    ("minecraft:sand", 66),
+    ("minecraft:red_sand", 67),
+    ("minecraft:gravel", 68),
+    ("minecraft:gold_ore", 69),
+    ("minecraft:iron_ore", 70),
+    ("minecraft:coal_ore", 71),
+];
+/// The default states of the built-in blocks since the flattening that aren't their lowest id.
+const BUILTIN_FLATTENED_DEFAULTS: &[(&str, u32)] =
+    &[("minecraft:grass_block", 9), ("minecraft:podzol", 13)];
+/// The first ids of the 16 levels of water and lava since the flattening.
+const BUILTIN_FLATTENED_FLUIDS: &[(&str, u32)] = &[("minecraft:water", 34), ("minecraft:lava", 50)];
+
+/// The block states known without loading any tables and their ids and metadata before the
+/// flattening.
+const BUILTIN_LEGACY: &[(&str, u32, u32)] = &[
+    ("minecraft:air", 0, 0),
+    ("minecraft:stone", 1, 0),
+    ("minecraft:granite", 1, 1),
+    ("minecraft:polished_granite", 1, 2),
+    ("minecraft:diorite", 1, 3),
+    ("minecraft:polished_diorite", 1, 4),
+    ("minecraft:andesite", 1, 5),
+    ("minecraft:polished_andesite", 1, 6),
+    ("minecraft:grass_block[snowy=false]", 2, 0),
+    ("minecraft:dirt", 3, 0),
+    ("minecraft:coarse_dirt", 3, 1),
+    ("minecraft:podzol[snowy=false]", 3, 2),
+    ("minecraft:cobblestone", 4, 0),
+    ("minecraft:oak_planks", 5, 0),
+    ("minecraft:spruce_planks", 5, 1),
+    ("minecraft:birch_planks", 5, 2),
+    ("minecraft:jungle_planks", 5, 3),
+    ("minecraft:acacia_planks", 5, 4),
+    ("minecraft:dark_oak_planks", 5, 5),
+    ("minecraft:oak_sapling[stage=0]", 6, 0),
+    ("minecraft:spruce_sapling[stage=0]", 6, 1),
+    ("minecraft:birch_sapling[stage=0]", 6, 2),
+    ("minecraft:jungle_sapling[stage=0]", 6, 3),
+    ("minecraft:acacia_sapling[stage=0]", 6, 4),
+    ("minecraft:dark_oak_sapling[stage=0]", 6, 5),
+    ("minecraft:oak_sapling[stage=1]", 6, 8),
+    ("minecraft:spruce_sapling[stage=1]", 6, 9),
+    ("minecraft:birch_sapling[stage=1]", 6, 10),
+    ("minecraft:jungle_sapling[stage=1]", 6, 11),
+    ("minecraft:acacia_sapling[stage=1]", 6, 12),
+    ("minecraft:dark_oak_sapling[stage=1]", 6, 13),
+    ("minecraft:bedrock", 7, 0),
+    ("minecraft:sand", 12, 0),
+    ("minecraft:red_sand", 12, 1),
+    ("minecraft:gravel", 13, 0),
+    ("minecraft:gold_ore", 14, 0),
+    ("minecraft:iron_ore", 15, 0),
+    ("minecraft:coal_ore", 16, 0),
+];
+/// The ids of the stationary water and lava before the flattening. The metadata is the level.
+const BUILTIN_LEGACY_FLUIDS: &[(&str, u32)] = &[("minecraft:water", 9), ("minecraft:lava", 11)];
+
+/// A block state as named since the flattening, e.g. `minecraft:grass_block[snowy=false]`. It is
+/// the same in every protocol version, so it is used to convert between their numeric ids.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockState {
+    name: String,
+    properties: BTreeMap<String, String>,
+}
+
+/// The numeric ids of the block states in a protocol version.
+#[derive(Debug, Clone, Default)]
+pub struct BlockTable {
+    ids: HashMap<BlockState, u32>,
+    states: HashMap<u32, BlockState>,
+    blocks: HashMap<String, Vec<BlockState>>,
+    defaults: HashMap<String, u32>,
+}
+
+/// Converts block states to the numeric ids of every supported protocol version and back.
+///
+/// Every known block state also gets a canonical id, which is used to store blocks independent
+/// of a protocol version. Canonical ids are only valid for the registry they came from, air
+/// always has the canonical id 0.
+#[derive(Debug, Clone)]
+pub struct BlockRegistry {
+    states: Vec<BlockState>,
+    canonical_ids: HashMap<BlockState, u32>,
+    tables: Vec<VersionTable>,
+    fallback: BlockState,
+}
+
+/// The numeric ids of a protocol version range in terms of canonical ids.
+#[derive(Debug, Clone)]
+struct VersionTable {
+    versions: RangeInclusive<i32>,
+    /// The numeric ids of the canonical states in the table.
+    exact: HashMap<u32, u32>,
+    /// The canonical id of the state returned for every numeric id.
+    states: HashMap<u32, u32>,
+    /// The canonical ids of the states of every block in the table.
+    blocks: HashMap<String, Vec<u32>>,
+    /// The numeric id of the default state of every block in the table.
+    defaults: HashMap<String, u32>,
+    /// The numeric ids of all canonical states, using the closest state or the fallback block
+    /// for the ones not in the table.
+    ids: Vec<u32>,
+}
+
+/// A block of a data generator report.
+#[derive(Deserialize)]
+struct ReportBlock {
+    #[serde(default)]
+    states: Vec<ReportState>,
+}
+
+/// A block state of a data generator report.
+#[derive(Deserialize)]
+struct ReportState {
+    id: u32,
+    #[serde(default)]
+    default: bool,
+    #[serde(default)]
+    properties: BTreeMap<String, String>,
+}
+
+impl BlockState {
+    /// Creates the block state of the block `name` without properties. The `minecraft:`
+    /// namespace is added if `name` has none.
+    pub fn new(name: &str) -> Self {
+        let name = if name.contains(':') {
+            name.to_string()
+        } else {
+            format!("minecraft:{}", name)
+        };
+
+        Self {
+            name,
+            properties: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the state with the property `key` set to `value`.
+    pub fn with<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns the namespaced name of the block.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the value of the property `key`.
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.get(key).map(|value| value.as_str())
+    }
+
+    /// Returns the properties of the state ordered by their names.
+    pub fn properties(&self) -> &BTreeMap<String, String> {
+        &self.properties
+    }
+}
+
+impl Display for BlockState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        if !self.properties.is_empty() {
+            let properties: Vec<String> = self
+                .properties
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+
+            write!(f, "[{}]", properties.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for BlockState {
+    type Err = PacketError;
+
+    /// Parses a block state like `minecraft:grass_block[snowy=false]`. The namespace is optional.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || PacketError::SerdeError(format!("Invalid block state {}", s));
+        let s = s.trim();
+
+        let (name, properties) = match s.find('[') {
+            Some(i) if s.ends_with(']') => (&s[..i], Some(&s[i + 1..s.len() - 1])),
+            Some(_) => return Err(invalid()),
+            None => (s, None),
+        };
+
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ']') {
+            return Err(invalid());
+        }
+
+        let mut state = Self::new(&name.to_lowercase());
+
+        for property in properties.into_iter().flat_map(|p| p.split(',')) {
+            match property.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() && !value.trim().is_empty() => {
+                    state = state.with(key.trim(), value.trim());
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(state)
+    }
+}
+
+impl BlockTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the `blocks.json` report of the vanilla data generator of a release since 1.13.
+    pub fn from_report(json: &str) -> Result<Self, PacketError> {
+        let report: BTreeMap<String, ReportBlock> =
+            serde_json::from_str(json).map_err(|e| PacketError::SerdeError(format!("{}", e)))?;
+        let mut table = Self::new();
+
+        for (name, block) in report {
+            for state in block.states {
+                let mut block_state = BlockState::new(&name);
+                block_state.properties = state.properties;
+
+                table.insert(block_state, state.id);
+
+                if state.default {
+                    table.defaults.insert(BlockState::new(&name).name, state.id);
+                }
+            }
+        }
+
+        Ok(table)
+    }
+
+    /// Parses a table of a release before 1.13. It is a JSON object mapping `id:meta` to the
+    /// block state, e.g. `{"2:0": "minecraft:grass_block[snowy=false]"}`. Block states that are
+    /// not listed use the closest listed state of the same block.
+    pub fn from_legacy(json: &str) -> Result<Self, PacketError> {
+        let entries: BTreeMap<String, String> =
+            serde_json::from_str(json).map_err(|e| PacketError::SerdeError(format!("{}", e)))?;
+        let mut table = Self::new();
+
+        for (key, state) in entries {
+            let id = key
+                .split_once(':')
+                .and_then(|(id, meta)| Some((id.parse::<u32>().ok()?, meta.parse::<u32>().ok()?)))
+                .filter(|(_, meta)| *meta < 16)
+                .map(|(id, meta)| id << 4 | meta)
+                .ok_or_else(|| PacketError::SerdeError(format!("Invalid legacy id {}", key)))?;
+
+            table.insert(state.parse()?, id);
+        }
+
+        Ok(table)
+    }
+
+    /// Adds `state` with the numeric `id`. If several states share an id, the first one is
+    /// returned for it. The state with the lowest id of a block is its default state.
+    pub fn insert(&mut self, state: BlockState, id: u32) {
+        let default = self.defaults.entry(state.name.clone()).or_insert(id);
+        *default = (*default).min(id);
+
+        self.states.entry(id).or_insert_with(|| state.clone());
+
+        if self.ids.insert(state.clone(), id).is_none() {
+            self.blocks
+                .entry(state.name.clone())
+                .or_default()
+                .push(state);
+        }
+    }
+
+    /// Returns the id of `state`, or `None` if the state is not in the table.
+    pub fn id(&self, state: &BlockState) -> Option<u32> {
+        self.ids.get(state).copied()
+    }
+
+    /// Returns the state with the numeric `id`.
+    pub fn state(&self, id: u32) -> Option<&BlockState> {
+        self.states.get(&id)
+    }
+
+    /// Returns the id of `state` or else of the state of the same block sharing the most
+    /// properties with it, preferring the default state of the block. Missing properties count as
+    /// the ones of the default state. Returns `None` if the block is not in the table.
+    pub fn closest_id(&self, state: &BlockState) -> Option<u32> {
+        if let Some(id) = self.id(state) {
+            return Some(id);
+        }
+
+        let default = *self.defaults.get(&state.name)?;
+        let candidates = self.blocks.get(&state.name)?;
+
+        closest(
+            state,
+            default,
+            self.states.get(&default),
+            candidates.iter().map(|other| (other, self.ids[other])),
+        )
+    }
+
+    /// Returns all states in the table.
+    pub fn states(&self) -> impl Iterator<Item = &BlockState> {
+        self.ids.keys()
+    }
+
+    /// Returns the number of states in the table.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns whether the table contains no states.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns the table of the block states known without loading any tables in protocol
+    /// versions since 1.13.
+    pub fn builtin_flattened() -> Self {
+        let mut table = Self::new();
+
+        for (state, id) in BUILTIN_FLATTENED {
+            table.insert(state.parse().unwrap(), *id);
+        }
+
+        for (name, first) in BUILTIN_FLATTENED_FLUIDS {
+            for level in 0..16 {
+                table.insert(
+                    BlockState::new(name).with("level", level.to_string()),
+                    first + level,
+                );
+            }
+        }
+
+        for (name, id) in BUILTIN_FLATTENED_DEFAULTS {
+            table.defaults.insert(name.to_string(), *id);
+        }
+
+        table
+    }
+
+    /// Returns the table of the block states known without loading any tables in protocol
+    /// versions before 1.13.
+    pub fn builtin_legacy() -> Self {
+        let mut table = Self::new();
+
+        for (state, id, meta) in BUILTIN_LEGACY {
+            table.insert(state.parse().unwrap(), id << 4 | meta);
+        }
+
+        for (name, id) in BUILTIN_LEGACY_FLUIDS {
+            for level in 0..16 {
+                table.insert(
+                    BlockState::new(name).with("level", level.to_string()),
+                    id << 4 | level,
+                );
+            }
+        }
+
+        table
+    }
+}
+
+impl BlockRegistry {
+    /// Creates a registry with the built-in tables for all supported protocol versions and
+    /// `minecraft:stone` as the fallback block.
+    pub fn new() -> Self {
+        let mut registry = Self {
+            states: vec![],
+            canonical_ids: HashMap::new(),
+            tables: vec![],
+            fallback: BlockState::new("stone"),
+        };
+
+        registry.intern(BlockState::new("air"));
+        registry.add_table(LEGACY_PROTOCOL_VERSIONS, BlockTable::builtin_legacy());
+        registry.add_table(FLATTENED_PROTOCOL_VERSIONS, BlockTable::builtin_flattened());
+
+        registry
+    }
+
+    /// Uses `table` for the protocol versions in `versions`. Tables added later take precedence
+    /// over the ones added before.
+    pub fn add_table(&mut self, versions: RangeInclusive<i32>, table: BlockTable) {
+        // Interned in the order of their ids, so canonical ids don't depend on the hash order.
+        let mut states: Vec<(u32, BlockState)> = table
+            .ids
+            .into_iter()
+            .map(|(state, id)| (id, state))
+            .collect();
+
+        states.sort_unstable();
+
+        let mut version_table = VersionTable {
+            versions,
+            exact: HashMap::with_capacity(states.len()),
+            states: HashMap::with_capacity(table.states.len()),
+            blocks: HashMap::new(),
+            defaults: table.defaults,
+            ids: vec![],
+        };
+
+        for (id, state) in states {
+            let name = state.name.clone();
+            let canonical = self.intern(state);
+
+            version_table.exact.insert(canonical, id);
+            version_table
+                .blocks
+                .entry(name)
+                .or_default()
+                .push(canonical);
+        }
+
+        for (id, state) in table.states {
+            version_table.states.insert(id, self.canonical_ids[&state]);
+        }
+
+        self.tables.push(version_table);
+        self.update_ids();
+    }
+
+    /// Returns the block used in place of blocks that don't exist in a protocol version.
+    pub fn fallback(&self) -> &BlockState {
+        &self.fallback
+    }
+
+    /// Sets the block used in place of blocks that don't exist in a protocol version. If the
+    /// fallback doesn't exist either, air is used.
+    pub fn set_fallback(&mut self, fallback: BlockState) {
+        self.intern(fallback.clone());
+        self.fallback = fallback;
+
+        for table in &mut self.tables {
+            table.ids.clear();
+        }
+
+        self.update_ids();
+    }
+
+    /// Returns the canonical id of `state`, or `None` if the state is not in any table.
+    pub fn canonical_id(&self, state: &BlockState) -> Option<u32> {
+        self.canonical_ids.get(state).copied()
+    }
+
+    /// Returns the state with the canonical `id`.
+    pub fn state(&self, id: u32) -> Option<&BlockState> {
+        self.states.get(id as usize)
+    }
+
+    /// Returns the number of block states with a canonical id.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Returns whether no block states are known. Never true, as air is always known.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Returns the numeric id of `state` in `protocol_version`. Blocks that don't exist in the
+    /// protocol version are replaced with the [fallback block](BlockRegistry::fallback).
+    pub fn to_id(&self, state: &BlockState, protocol_version: i32) -> u32 {
+        let table = match self.table(protocol_version) {
+            Some(table) => table,
+            None => return 0,
+        };
+
+        match self.canonical_id(state) {
+            Some(id) => table.ids[id as usize],
+            None => self.resolve(table, state),
+        }
+    }
+
+    /// Returns the numeric id of the state with the canonical `id` in `protocol_version`. Blocks
+    /// that don't exist in the protocol version are replaced with the
+    /// [fallback block](BlockRegistry::fallback).
+    pub fn canonical_to_id(&self, id: u32, protocol_version: i32) -> u32 {
+        self.table(protocol_version)
+            .and_then(|table| table.ids.get(id as usize).copied())
+            .unwrap_or(0)
+    }
+
+    /// Returns the state with the numeric `id` in `protocol_version`.
+    pub fn from_id(&self, id: u32, protocol_version: i32) -> Option<&BlockState> {
+        let table = self.table(protocol_version)?;
+
+        self.state(*table.states.get(&id)?)
+    }
+
+    /// Returns the canonical id of the state with the numeric `id` in `protocol_version`.
+    pub fn id_to_canonical(&self, id: u32, protocol_version: i32) -> Option<u32> {
+        self.table(protocol_version)?.states.get(&id).copied()
+    }
+
+    /// Returns the canonical id of `state` or else of the closest state of the same block in the
+    /// table with the highest precedence containing the block. Returns `None` if the block is not
+    /// in any table.
+    pub fn closest_canonical_id(&self, state: &BlockState) -> Option<u32> {
+        if let Some(id) = self.canonical_id(state) {
+            return Some(id);
+        }
+
+        self.tables.iter().rev().find_map(|table| {
+            let id = self.closest_id(table, state)?;
+
+            table.states.get(&id).copied()
+        })
+    }
+
+    fn table(&self, protocol_version: i32) -> Option<&VersionTable> {
+        self.tables
+            .iter()
+            .rev()
+            .find(|table| table.versions.contains(&protocol_version))
+    }
+
+    /// Returns the canonical id of `state`, giving it one if it has none yet.
+    fn intern(&mut self, state: BlockState) -> u32 {
+        if let Some(id) = self.canonical_ids.get(&state) {
+            return *id;
+        }
+
+        let id = self.states.len() as u32;
+
+        self.canonical_ids.insert(state.clone(), id);
+        self.states.push(state);
+
+        id
+    }
+
+    /// Resolves the ids of the canonical states that were added since the last update in every
+    /// table.
+    fn update_ids(&mut self) {
+        let mut tables = std::mem::take(&mut self.tables);
+
+        for table in &mut tables {
+            let ids: Vec<u32> = (table.ids.len()..self.states.len())
+                .map(|id| match table.exact.get(&(id as u32)) {
+                    Some(id) => *id,
+                    None => self.resolve(table, &self.states[id]),
+                })
+                .collect();
+
+            table.ids.extend(ids);
+        }
+
+        self.tables = tables;
+    }
+
+    /// Returns the numeric id of the closest state to `state` in `table`, or else of the closest
+    /// state to the fallback block, or else of air.
+    fn resolve(&self, table: &VersionTable, state: &BlockState) -> u32 {
+        self.closest_id(table, state)
+            .or_else(|| self.closest_id(table, &self.fallback))
+            .unwrap_or(0)
+    }
+
+    fn closest_id(&self, table: &VersionTable, state: &BlockState) -> Option<u32> {
+        if let Some(id) = self.canonical_id(state).and_then(|id| table.exact.get(&id)) {
+            return Some(*id);
+        }
+
+        let default = *table.defaults.get(&state.name)?;
+        let default_state = table
+            .states
+            .get(&default)
+            .map(|id| &self.states[*id as usize]);
+        let candidates = table.blocks.get(&state.name)?;
+
+        closest(
+            state,
+            default,
+            default_state,
+            candidates
+                .iter()
+                .map(|id| (&self.states[*id as usize], table.exact[id])),
+        )
+    }
+}
+
+impl Default for BlockRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the id of the candidate sharing the most properties with `state`, preferring the
+/// `default` id and then the lowest one. Missing properties count as the ones of the
+/// `default_state`.
+fn closest<'a, I: Iterator<Item = (&'a BlockState, u32)>>(
+    state: &BlockState,
+    default: u32,
+    default_state: Option<&BlockState>,
+    candidates: I,
+) -> Option<u32> {
+    let property = |key: &str| {
+        state
+            .property(key)
+            .or_else(|| default_state.and_then(|default| default.property(key)))
+    };
+
+    candidates
+        .max_by_key(|(other, id)| {
+            let shared = other
+                .properties
+                .iter()
+                .filter(|(key, value)| property(key) == Some(value.as_str()))
+                .count();
+
+            (shared, *id == default, std::cmp::Reverse(*id))
+        })
+        .map(|(_, id)| id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `blocks.json` report of air and a furnace whose default state isn't its lowest id.
+    const REPORT: &str = r#"{
+        "minecraft:air": { "states": [{ "id": 0, "default": true }] },
+        "minecraft:furnace": {
+            "properties": { "facing": ["north", "south"], "lit": ["true", "false"] },
+            "states": [
+                { "id": 3373, "properties": { "facing": "north", "lit": "true" } },
+                { "id": 3374, "properties": { "facing": "north", "lit": "false" }, "default": true },
+                { "id": 3375, "properties": { "facing": "south", "lit": "true" } },
+                { "id": 3376, "properties": { "facing": "south", "lit": "false" } }
+            ]
+        }
+    }"#;
+    /// A legacy table of air and some states of a furnace.
+    const LEGACY: &str = r#"{
+        "0:0": "minecraft:air",
+        "61:2": "minecraft:furnace[facing=north,lit=false]",
+        "61:3": "furnace[lit=false, facing=south]",
+        "62:2": "minecraft:furnace[facing=north,lit=true]"
+    }"#;
+
+    fn state(s: &str) -> BlockState {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn builtin_ids() {
+        let registry = BlockRegistry::new();
+        let states = [
+            ("minecraft:air", 0, 0),
+            ("minecraft:grass_block[snowy=false]", 2 << 4, 9),
+            ("minecraft:podzol[snowy=false]", 3 << 4 | 2, 13),
+            ("minecraft:dark_oak_sapling[stage=1]", 6 << 4 | 13, 32),
+            ("minecraft:water[level=3]", 9 << 4 | 3, 37),
+        ];
+
+        for (s, legacy, flattened) in states.iter().copied() {
+            let state = state(s);
+
+            assert_eq!(registry.to_id(&state, 47), legacy, "{}", s);
+            assert_eq!(registry.to_id(&state, 340), legacy, "{}", s);
+            assert_eq!(registry.to_id(&state, 393), flattened, "{}", s);
+            assert_eq!(registry.to_id(&state, 754), flattened, "{}", s);
+            assert_eq!(registry.from_id(legacy, 47), Some(&state));
+            assert_eq!(registry.from_id(flattened, 754), Some(&state));
+
+            let canonical = registry.id_to_canonical(legacy, 340).unwrap();
+
+            assert_eq!(registry.canonical_id(&state), Some(canonical));
+            assert_eq!(registry.canonical_to_id(canonical, 47), legacy);
+            assert_eq!(registry.canonical_to_id(canonical, 754), flattened);
+        }
+
+        assert_eq!(registry.canonical_id(&BlockState::new("air")), Some(0));
+        assert_eq!(registry.from_id(1 << 4 | 15, 47), None);
+        assert_eq!(registry.to_id(&BlockState::new("stone"), 46), 0);
+        assert_eq!(registry.from_id(1, 755), None);
+    }
+
+    #[test]
+    fn closest_states() {
+        let registry = BlockRegistry::new();
+
+        // Before 1.13 grass and podzol can't be snowy.
+        assert_eq!(
+            registry.to_id(&state("grass_block[snowy=true]"), 47),
+            2 << 4
+        );
+        assert_eq!(registry.to_id(&state("podzol[snowy=true]"), 47), 3 << 4 | 2);
+        assert_eq!(registry.to_id(&state("podzol[snowy=true]"), 393), 12);
+
+        // Missing properties are the ones of the default state.
+        assert_eq!(registry.to_id(&BlockState::new("grass_block"), 393), 9);
+        assert_eq!(registry.to_id(&BlockState::new("oak_sapling"), 47), 6 << 4);
+        assert_eq!(
+            registry.to_id(&state("oak_sapling[stage=1,age=7]"), 754),
+            22
+        );
+
+        assert_eq!(
+            registry.closest_canonical_id(&BlockState::new("grass_block")),
+            registry.canonical_id(&state("grass_block[snowy=false]"))
+        );
+        assert_eq!(
+            registry.closest_canonical_id(&BlockState::new("arrow:missing")),
+            None
+        );
+    }
+
+    #[test]
+    fn fallback() {
+        let mut registry = BlockRegistry::new();
+        let mut table = BlockTable::new();
+
+        table.insert(state("minecraft:end_rod[facing=up]"), 9000);
+        registry.add_table(393..=754, table);
+
+        let end_rod = state("end_rod[facing=up]");
+        let missing = BlockState::new("arrow:missing");
+
+        assert_eq!(registry.fallback(), &BlockState::new("stone"));
+        assert_eq!(registry.to_id(&end_rod, 754), 9000);
+        assert_eq!(registry.to_id(&end_rod, 340), 1 << 4);
+        assert_eq!(registry.to_id(&missing, 340), 1 << 4);
+        // The end rod table replaced the built-in table, which had stone.
+        assert_eq!(registry.to_id(&missing, 754), 0);
+
+        registry.set_fallback(BlockState::new("dirt"));
+
+        assert_eq!(registry.to_id(&end_rod, 340), 3 << 4);
+        assert_eq!(registry.to_id(&missing, 340), 3 << 4);
+
+        let canonical = registry.canonical_id(&end_rod).unwrap();
+
+        assert_eq!(registry.canonical_to_id(canonical, 47), 3 << 4);
+
+        registry.set_fallback(missing.clone());
+
+        assert_eq!(registry.canonical_to_id(canonical, 47), 0);
+        assert_eq!(registry.to_id(&missing, 47), 0);
+    }
+
+    #[test]
+    fn report_table() {
+        let table = BlockTable::from_report(REPORT).unwrap();
+
+        assert_eq!(table.len(), 5);
+        assert_eq!(table.id(&BlockState::new("air")), Some(0));
+        assert_eq!(
+            table.id(&state("furnace[facing=south,lit=false]")),
+            Some(3376)
+        );
+        assert_eq!(
+            table.state(3373),
+            Some(&state("furnace[facing=north,lit=true]"))
+        );
+        assert_eq!(table.id(&BlockState::new("furnace")), None);
+        assert_eq!(table.closest_id(&BlockState::new("furnace")), Some(3374));
+        assert_eq!(
+            table.closest_id(&state("furnace[facing=south]")),
+            Some(3376)
+        );
+        assert_eq!(table.closest_id(&BlockState::new("stone")), None);
+
+        assert!(BlockTable::from_report("[]").is_err());
+    }
+
+    #[test]
+    fn legacy_table() {
+        let table = BlockTable::from_legacy(LEGACY).unwrap();
+
+        assert_eq!(table.len(), 4);
+        assert_eq!(
+            table.id(&state("furnace[facing=south,lit=false]")),
+            Some(61 << 4 | 3)
+        );
+        assert_eq!(
+            table.state(62 << 4 | 2),
+            Some(&state("furnace[facing=north,lit=true]"))
+        );
+        // Without a report the default state is the one with the lowest id.
+        assert_eq!(
+            table.closest_id(&BlockState::new("furnace")),
+            Some(61 << 4 | 2)
+        );
+        assert_eq!(
+            table.closest_id(&state("furnace[facing=south,lit=true]")),
+            Some(61 << 4 | 3)
+        );
+
+        assert!(BlockTable::from_legacy(r#"{"61:16": "furnace"}"#).is_err());
+        assert!(BlockTable::from_legacy(r#"{"furnace": "furnace"}"#).is_err());
+        assert!(BlockTable::from_legacy(r#"{"61:2": "furnace[facing"}"#).is_err());
+    }
+
+    #[test]
+    fn registry_with_tables() {
+        let mut registry = BlockRegistry::new();
+
+        registry.add_table(
+            LEGACY_PROTOCOL_VERSIONS,
+            BlockTable::from_legacy(LEGACY).unwrap(),
+        );
+        registry.add_table(
+            FLATTENED_PROTOCOL_VERSIONS,
+            BlockTable::from_report(REPORT).unwrap(),
+        );
+
+        let furnace = state("furnace[facing=south,lit=true]");
+
+        assert_eq!(registry.to_id(&furnace, 340), 61 << 4 | 3);
+        assert_eq!(registry.to_id(&furnace, 393), 3375);
+        assert_eq!(registry.to_id(&BlockState::new("furnace"), 754), 3374);
+        assert_eq!(
+            registry.from_id(62 << 4 | 2, 47),
+            Some(&state("furnace[facing=north,lit=true]"))
+        );
+        assert_eq!(registry.from_id(2 << 4, 47), None);
+        // Neither table has stone, so air is used in its place.
+        assert_eq!(registry.to_id(&BlockState::new("stone"), 47), 0);
+    }
+}
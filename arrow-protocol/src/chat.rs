@@ -0,0 +1,911 @@
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::packets::error::PacketError;
+
+/// The first protocol version (1.16) able to display hex colors.
+pub const HEX_COLOR_PROTOCOL_VERSION: i32 = 735;
+
+/// The character starting a legacy formatting code.
+pub const SECTION_SIGN: char = '§';
+
+/// The named colors with their name, legacy formatting code and RGB value.
+const NAMED_COLORS: [(Color, &str, char, u32); 16] = [
+    (Color::Black, "black", '0', 0x000000),
+    (Color::DarkBlue, "dark_blue", '1', 0x0000AA),
+    (Color::DarkGreen, "dark_green", '2', 0x00AA00),
+    (Color::DarkAqua, "dark_aqua", '3', 0x00AAAA),
+    (Color::DarkRed, "dark_red", '4', 0xAA0000),
+    (Color::DarkPurple, "dark_purple", '5', 0xAA00AA),
+    (Color::Gold, "gold", '6', 0xFFAA00),
+    (Color::Gray, "gray", '7', 0xAAAAAA),
+    (Color::DarkGray, "dark_gray", '8', 0x555555),
+    (Color::Blue, "blue", '9', 0x5555FF),
+    (Color::Green, "green", 'a', 0x55FF55),
+    (Color::Aqua, "aqua", 'b', 0x55FFFF),
+    (Color::Red, "red", 'c', 0xFF5555),
+    (Color::LightPurple, "light_purple", 'd', 0xFF55FF),
+    (Color::Yellow, "yellow", 'e', 0xFFFF55),
+    (Color::White, "white", 'f', 0xFFFFFF),
+];
+
+/// The English translations of the keys sent by Arrow, used to convert components to text.
+const TRANSLATIONS: [(&str, &str); 3] = [
+    ("chat.type.text", "<%s> %s"),
+    ("multiplayer.player.joined", "%s joined the game"),
+    ("multiplayer.player.left", "%s left the game"),
+];
+
+/// A [chat component](https://wiki.vg/Chat) as used by the MOTD, disconnect reasons and chat
+/// messages.
+///
+/// Components are (de)serialized as JSON. Plain strings, numbers and arrays are accepted when
+/// deserializing like vanilla clients do.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChatComponent {
+    /// The content of this component.
+    #[serde(flatten)]
+    pub content: ChatContent,
+    /// The style of this component. It is inherited by the `extra` components.
+    #[serde(flatten)]
+    pub style: Style,
+    /// The components following this one.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extra: Vec<ChatComponent>,
+}
+
+/// The content of a [`ChatComponent`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ChatContent {
+    /// Plain text.
+    Text {
+        /// The text to display.
+        text: String,
+    },
+    /// A text translated by the client.
+    Translate {
+        /// The translation key, e.g. `chat.type.text`.
+        translate: String,
+        /// The components inserted into the translation.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        with: Vec<ChatComponent>,
+    },
+    /// The score of an entity in an objective.
+    Score {
+        /// The score to display.
+        score: Score,
+    },
+    /// The names of the entities matched by a selector.
+    Selector {
+        /// The entity selector, e.g. `@p`.
+        selector: String,
+    },
+    /// The key bound to a control.
+    Keybind {
+        /// The name of the control, e.g. `key.jump`.
+        keybind: String,
+    },
+}
+
+impl Default for ChatContent {
+    fn default() -> Self {
+        Self::Text {
+            text: String::new(),
+        }
+    }
+}
+
+/// The score shown by [`ChatContent::Score`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Score {
+    /// The name of the score holder or a selector.
+    pub name: String,
+    /// The name of the objective.
+    pub objective: String,
+    /// The resolved value. Sent by the server instead of looking the score up on the client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// The style of a [`ChatComponent`]. Fields that are `None` are inherited from the parent.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Style {
+    /// The color of the text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+    /// Whether the text is bold.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bold: Option<bool>,
+    /// Whether the text is italic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    /// Whether the text is underlined.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underlined: Option<bool>,
+    /// Whether the text is struck through.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strikethrough: Option<bool>,
+    /// Whether the text is obfuscated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obfuscated: Option<bool>,
+    /// The resource location of the font. Only used by 1.16 and above.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    /// The text inserted into the chat input when the component is shift-clicked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insertion: Option<String>,
+    /// The action run when the component is clicked.
+    #[serde(
+        rename = "clickEvent",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub click_event: Option<ClickEvent>,
+    /// The tooltip shown when hovering over the component.
+    #[serde(
+        rename = "hoverEvent",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub hover_event: Option<HoverEvent>,
+}
+
+/// The color of a [`ChatComponent`].
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    DarkBlue,
+    DarkGreen,
+    DarkAqua,
+    DarkRed,
+    DarkPurple,
+    Gold,
+    Gray,
+    DarkGray,
+    Blue,
+    Green,
+    Aqua,
+    Red,
+    LightPurple,
+    Yellow,
+    White,
+    /// Resets the color to the default of the client.
+    Reset,
+    /// A RGB color. Only displayed by 1.16 and above.
+    Hex(u32),
+}
+
+/// The action run when a [`ChatComponent`] is clicked.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "action", content = "value", rename_all = "snake_case")]
+pub enum ClickEvent {
+    /// Opens the URL in the browser.
+    OpenUrl(String),
+    /// Sends the text as a chat message or command.
+    RunCommand(String),
+    /// Replaces the chat input with the text.
+    SuggestCommand(String),
+    /// Turns to the page of a book.
+    ChangePage(String),
+    /// Copies the text to the clipboard. Only used by 1.15 and above.
+    CopyToClipboard(String),
+}
+
+/// The tooltip shown when hovering over a [`ChatComponent`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "RawHoverEvent", into = "RawHoverEvent")]
+pub enum HoverEvent {
+    /// Shows a component.
+    ShowText(Box<ChatComponent>),
+    /// Shows the tooltip of an item given as SNBT.
+    ShowItem(String),
+    /// Shows the name, type and uuid of an entity given as SNBT.
+    ShowEntity(String),
+}
+
+/// The representation of a [`HoverEvent`] in JSON. 1.16 calls the value `contents`.
+#[derive(Serialize, Deserialize)]
+struct RawHoverEvent {
+    action: String,
+    #[serde(alias = "contents")]
+    value: Value,
+}
+
+/// The representation of a [`ChatComponent`] as JSON object.
+#[derive(Deserialize)]
+struct ComponentObject {
+    #[serde(flatten)]
+    content: ChatContent,
+    #[serde(flatten)]
+    style: Style,
+    #[serde(default)]
+    extra: Vec<ChatComponent>,
+}
+
+impl ChatComponent {
+    /// Creates a component containing `text` without any style.
+    pub fn text<S: Into<String>>(text: S) -> Self {
+        Self {
+            content: ChatContent::Text { text: text.into() },
+            style: Style::default(),
+            extra: vec![],
+        }
+    }
+
+    /// Creates a component translating `key` with the components `with` inserted.
+    pub fn translate<S: Into<String>>(key: S, with: Vec<ChatComponent>) -> Self {
+        Self {
+            content: ChatContent::Translate {
+                translate: key.into(),
+                with,
+            },
+            style: Style::default(),
+            extra: vec![],
+        }
+    }
+
+    /// Sets the color of this component.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.style.color = Some(color);
+        self
+    }
+
+    /// Sets the click event of this component.
+    pub fn with_click_event(mut self, click_event: ClickEvent) -> Self {
+        self.style.click_event = Some(click_event);
+        self
+    }
+
+    /// Sets the hover event of this component.
+    pub fn with_hover_event(mut self, hover_event: HoverEvent) -> Self {
+        self.style.hover_event = Some(hover_event);
+        self
+    }
+
+    /// Appends `component` to the `extra` components.
+    pub fn append(mut self, component: ChatComponent) -> Self {
+        self.extra.push(component);
+        self
+    }
+
+    /// Parses a component from JSON.
+    pub fn from_json(json: &str) -> Result<Self, PacketError> {
+        serde_json::from_str(json).map_err(|_| PacketError::ParsingJsonFailed)
+    }
+
+    /// Serializes this component as JSON that can be displayed by `protocol_version`.
+    pub fn to_json(&self, protocol_version: i32) -> Result<String, PacketError> {
+        serde_json::to_string(&self.for_protocol_version(protocol_version))
+            .map_err(|_| PacketError::BuildingJsonFailed)
+    }
+
+    /// Returns a copy of this component that can be displayed by `protocol_version`. Before 1.16
+    /// hex colors are replaced by the nearest named color.
+    pub fn for_protocol_version(&self, protocol_version: i32) -> Self {
+        let mut component = self.clone();
+
+        if protocol_version < HEX_COLOR_PROTOCOL_VERSION {
+            component.downgrade_colors();
+        }
+
+        component
+    }
+
+    fn downgrade_colors(&mut self) {
+        if let Some(color) = self.style.color {
+            self.style.color = Some(color.to_named());
+        }
+
+        if let Some(HoverEvent::ShowText(text)) = &mut self.style.hover_event {
+            text.downgrade_colors();
+        }
+
+        if let ChatContent::Translate { with, .. } = &mut self.content {
+            with.iter_mut().for_each(Self::downgrade_colors);
+        }
+
+        self.extra.iter_mut().for_each(Self::downgrade_colors);
+    }
+
+    /// Parses a string using legacy `§` formatting codes. A color code resets the formatting and
+    /// unknown codes are dropped.
+    pub fn from_legacy(legacy: &str) -> Self {
+        let mut components = vec![];
+        let mut style = Style::default();
+        let mut text = String::new();
+        let mut chars = legacy.chars();
+
+        while let Some(c) = chars.next() {
+            if c != SECTION_SIGN {
+                text.push(c);
+                continue;
+            }
+
+            let code = match chars.next() {
+                Some(code) => code.to_ascii_lowercase(),
+                None => break,
+            };
+
+            if !text.is_empty() {
+                components.push(Self {
+                    content: ChatContent::Text {
+                        text: std::mem::take(&mut text),
+                    },
+                    style: style.clone(),
+                    extra: vec![],
+                });
+            }
+
+            match code {
+                'k' => style.obfuscated = Some(true),
+                'l' => style.bold = Some(true),
+                'm' => style.strikethrough = Some(true),
+                'n' => style.underlined = Some(true),
+                'o' => style.italic = Some(true),
+                'r' => style = Style::default(),
+                code => {
+                    if let Some(color) = Color::from_code(code) {
+                        style = Style {
+                            color: Some(color),
+                            ..Style::default()
+                        };
+                    }
+                }
+            }
+        }
+
+        if !text.is_empty() {
+            components.push(Self {
+                content: ChatContent::Text { text },
+                style,
+                extra: vec![],
+            });
+        }
+
+        if components.len() == 1 {
+            components.remove(0)
+        } else {
+            Self {
+                extra: components,
+                ..Self::text("")
+            }
+        }
+    }
+
+    /// Converts this component to a string using legacy `§` formatting codes. Hex colors are
+    /// replaced by the nearest named color and events are dropped. Translations are resolved as
+    /// described in [`ChatComponent::to_plain_text`] and keybinds are written as their key.
+    pub fn to_legacy(&self) -> String {
+        let mut legacy = String::new();
+
+        self.write_legacy(&Style::default(), &mut Style::default(), &mut legacy);
+
+        legacy
+    }
+
+    fn write_legacy(&self, parent: &Style, current: &mut Style, legacy: &mut String) {
+        let style = self.style.inherit(parent);
+        let text = self.content_text();
+
+        if !text.is_empty() {
+            let formatting = style.legacy_formatting();
+
+            if &formatting != current {
+                match formatting.color {
+                    Some(color) if color != Color::Reset => {
+                        legacy.push(SECTION_SIGN);
+                        legacy.push(color.code().unwrap_or('r'));
+                    }
+                    _ => {
+                        legacy.push(SECTION_SIGN);
+                        legacy.push('r');
+                    }
+                }
+
+                for (enabled, code) in [
+                    (formatting.obfuscated, 'k'),
+                    (formatting.bold, 'l'),
+                    (formatting.strikethrough, 'm'),
+                    (formatting.underlined, 'n'),
+                    (formatting.italic, 'o'),
+                ] {
+                    if enabled == Some(true) {
+                        legacy.push(SECTION_SIGN);
+                        legacy.push(code);
+                    }
+                }
+
+                *current = formatting;
+            }
+
+            legacy.push_str(&text);
+        }
+
+        for component in &self.extra {
+            component.write_legacy(&style, current, legacy);
+        }
+    }
+
+    /// Returns the text of this component and all `extra` components without any formatting.
+    /// Translations of the keys sent by Arrow are written in English with their arguments
+    /// inserted, other keys are followed by their arguments in parentheses.
+    pub fn to_plain_text(&self) -> String {
+        let mut text = self.content_text();
+
+        for component in &self.extra {
+            text.push_str(&component.to_plain_text());
+        }
+
+        text
+    }
+
+    fn content_text(&self) -> String {
+        match &self.content {
+            ChatContent::Text { text } => text.clone(),
+            ChatContent::Translate { translate, with } => translate_text(translate, with),
+            ChatContent::Score { score } => score.value.clone().unwrap_or_default(),
+            ChatContent::Selector { selector } => selector.clone(),
+            ChatContent::Keybind { keybind } => keybind.clone(),
+        }
+    }
+}
+
+/// Inserts the plain text of `with` into the English translation of `key`.
+fn translate_text(key: &str, with: &[ChatComponent]) -> String {
+    let args: Vec<String> = with.iter().map(ChatComponent::to_plain_text).collect();
+
+    match TRANSLATIONS.iter().find(|(k, _)| *k == key) {
+        Some((_, format)) => insert_arguments(format, &args),
+        None if args.is_empty() => key.to_string(),
+        None => format!("{}({})", key, args.join(", ")),
+    }
+}
+
+/// Replaces the `%s`, `%1$s` and `%%` placeholders of `format`. Missing arguments are left empty.
+fn insert_arguments(format: &str, args: &[String]) -> String {
+    let mut text = String::new();
+    let mut next = 0;
+    let mut rest = format;
+
+    while let Some(i) = rest.find('%') {
+        text.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+
+        if let Some(r) = rest.strip_prefix('%') {
+            text.push('%');
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix('s') {
+            text.push_str(args.get(next).map_or("", String::as_str));
+            next += 1;
+            rest = r;
+        } else if let Some((index, r)) = rest.split_once("$s") {
+            match index.parse::<usize>() {
+                Ok(index) if index > 0 => {
+                    text.push_str(args.get(index - 1).map_or("", String::as_str));
+                    rest = r;
+                }
+                _ => text.push('%'),
+            }
+        } else {
+            text.push('%');
+        }
+    }
+
+    text.push_str(rest);
+    text
+}
+
+impl Default for ChatComponent {
+    fn default() -> Self {
+        Self::text("")
+    }
+}
+
+impl From<&str> for ChatComponent {
+    fn from(text: &str) -> Self {
+        Self::text(text)
+    }
+}
+
+impl From<String> for ChatComponent {
+    fn from(text: String) -> Self {
+        Self::text(text)
+    }
+}
+
+impl Display for ChatComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_plain_text())
+    }
+}
+
+impl<'de> Deserialize<'de> for ChatComponent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::String(text) => Ok(Self::text(text)),
+            Value::Bool(b) => Ok(Self::text(b.to_string())),
+            Value::Number(n) => Ok(Self::text(n.to_string())),
+            Value::Array(components) => {
+                let mut components = components
+                    .into_iter()
+                    .map(|c| Self::deserialize(c).map_err(D::Error::custom));
+                let mut first = components
+                    .next()
+                    .ok_or_else(|| D::Error::custom("empty chat component array"))??;
+
+                for component in components {
+                    first.extra.push(component?);
+                }
+
+                Ok(first)
+            }
+            object @ Value::Object(_) => {
+                let object = ComponentObject::deserialize(object).map_err(D::Error::custom)?;
+
+                Ok(Self {
+                    content: object.content,
+                    style: object.style,
+                    extra: object.extra,
+                })
+            }
+            Value::Null => Err(D::Error::custom("chat component is null")),
+        }
+    }
+}
+
+impl Style {
+    /// Returns this style with the fields that are `None` taken from `parent`.
+    pub fn inherit(&self, parent: &Style) -> Style {
+        Style {
+            color: self.color.or(parent.color),
+            bold: self.bold.or(parent.bold),
+            italic: self.italic.or(parent.italic),
+            underlined: self.underlined.or(parent.underlined),
+            strikethrough: self.strikethrough.or(parent.strikethrough),
+            obfuscated: self.obfuscated.or(parent.obfuscated),
+            font: self.font.clone().or_else(|| parent.font.clone()),
+            insertion: self.insertion.clone().or_else(|| parent.insertion.clone()),
+            click_event: self
+                .click_event
+                .clone()
+                .or_else(|| parent.click_event.clone()),
+            hover_event: self
+                .hover_event
+                .clone()
+                .or_else(|| parent.hover_event.clone()),
+        }
+    }
+
+    /// Returns the part of this style that can be expressed by legacy formatting codes.
+    fn legacy_formatting(&self) -> Style {
+        let enabled = |b: Option<bool>| b.filter(|b| *b);
+
+        Style {
+            color: self
+                .color
+                .map(Color::to_named)
+                .filter(|c| *c != Color::Reset),
+            bold: enabled(self.bold),
+            italic: enabled(self.italic),
+            underlined: enabled(self.underlined),
+            strikethrough: enabled(self.strikethrough),
+            obfuscated: enabled(self.obfuscated),
+            ..Style::default()
+        }
+    }
+}
+
+impl Color {
+    /// Returns the named color of the legacy formatting `code`.
+    pub fn from_code(code: char) -> Option<Color> {
+        NAMED_COLORS
+            .iter()
+            .find(|(_, _, c, _)| *c == code)
+            .map(|(color, _, _, _)| *color)
+    }
+
+    /// Returns the legacy formatting code of this color. Hex colors have none.
+    pub fn code(&self) -> Option<char> {
+        match self {
+            Color::Reset => Some('r'),
+            color => NAMED_COLORS
+                .iter()
+                .find(|(c, _, _, _)| c == color)
+                .map(|(_, _, code, _)| *code),
+        }
+    }
+
+    /// Returns the RGB value of this color. [`Color::Reset`] is white.
+    pub fn rgb(&self) -> u32 {
+        match self {
+            Color::Hex(rgb) => *rgb,
+            Color::Reset => 0xFFFFFF,
+            color => NAMED_COLORS
+                .iter()
+                .find(|(c, _, _, _)| c == color)
+                .map_or(0xFFFFFF, |(_, _, _, rgb)| *rgb),
+        }
+    }
+
+    /// Returns the named color nearest to this one. Named colors are returned unchanged.
+    pub fn to_named(self) -> Color {
+        let rgb = match self {
+            Color::Hex(rgb) => rgb,
+            color => return color,
+        };
+        let channels = |rgb: u32| {
+            [
+                ((rgb >> 16) & 0xFF) as i32,
+                ((rgb >> 8) & 0xFF) as i32,
+                (rgb & 0xFF) as i32,
+            ]
+        };
+        let [r, g, b] = channels(rgb);
+
+        NAMED_COLORS
+            .iter()
+            .min_by_key(|(_, _, _, named)| {
+                let [nr, ng, nb] = channels(*named);
+
+                (r - nr).pow(2) + (g - ng).pow(2) + (b - nb).pow(2)
+            })
+            .map_or(Color::White, |(color, _, _, _)| *color)
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Color::Hex(rgb) => write!(f, "#{:06X}", rgb),
+            Color::Reset => write!(f, "reset"),
+            color => {
+                let name = NAMED_COLORS
+                    .iter()
+                    .find(|(c, _, _, _)| c == color)
+                    .map_or("white", |(_, name, _, _)| *name);
+
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = PacketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(hex) = s.strip_prefix('#') {
+            return u32::from_str_radix(hex, 16)
+                .ok()
+                .filter(|_| hex.len() == 6)
+                .map(Color::Hex)
+                .ok_or_else(|| PacketError::SerdeError(format!("Invalid hex color {}", s)));
+        }
+
+        if s == "reset" {
+            return Ok(Color::Reset);
+        }
+
+        NAMED_COLORS
+            .iter()
+            .find(|(_, name, _, _)| *name == s)
+            .map(|(color, _, _, _)| *color)
+            .ok_or_else(|| PacketError::SerdeError(format!("Invalid color {}", s)))
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+impl TryFrom<RawHoverEvent> for HoverEvent {
+    type Error = String;
+
+    fn try_from(raw: RawHoverEvent) -> Result<Self, Self::Error> {
+        let snbt = |value: Value| match value {
+            Value::String(s) => s,
+            value => value.to_string(),
+        };
+
+        match raw.action.as_str() {
+            "show_text" => ChatComponent::deserialize(raw.value)
+                .map(|c| HoverEvent::ShowText(Box::new(c)))
+                .map_err(|e| format!("{}", e)),
+            "show_item" => Ok(HoverEvent::ShowItem(snbt(raw.value))),
+            "show_entity" => Ok(HoverEvent::ShowEntity(snbt(raw.value))),
+            action => Err(format!("Unknown hover event action {}", action)),
+        }
+    }
+}
+
+impl From<HoverEvent> for RawHoverEvent {
+    fn from(event: HoverEvent) -> Self {
+        let (action, value) = match event {
+            HoverEvent::ShowText(text) => (
+                "show_text",
+                serde_json::to_value(text).unwrap_or(Value::Null),
+            ),
+            HoverEvent::ShowItem(item) => ("show_item", Value::String(item)),
+            HoverEvent::ShowEntity(entity) => ("show_entity", Value::String(entity)),
+        };
+
+        Self {
+            action: action.to_string(),
+            value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translations_insert_their_arguments() {
+        let joined = ChatComponent::translate("multiplayer.player.joined", vec!["Notch".into()])
+            .with_color(Color::Yellow);
+        let chat = ChatComponent::translate(
+            "chat.type.text",
+            vec![
+                "Notch".into(),
+                ChatComponent::text("Hello").append("!".into()),
+            ],
+        );
+
+        assert_eq!(joined.to_plain_text(), "Notch joined the game");
+        assert_eq!(joined.to_legacy(), "§eNotch joined the game");
+        assert_eq!(chat.to_string(), "<Notch> Hello!");
+    }
+
+    #[test]
+    fn unknown_translations_keep_their_key() {
+        let death = ChatComponent::translate("death.attack.fall", vec!["Notch".into()]);
+
+        assert_eq!(death.to_plain_text(), "death.attack.fall(Notch)");
+        assert_eq!(
+            ChatComponent::translate("gui.done", vec![]).to_plain_text(),
+            "gui.done"
+        );
+    }
+
+    #[test]
+    fn translation_placeholders() {
+        let args = ["a".to_string(), "b".to_string()];
+
+        assert_eq!(insert_arguments("<%s> %s", &args), "<a> b");
+        assert_eq!(insert_arguments("%2$s %1$s %s", &args), "b a a");
+        assert_eq!(insert_arguments("100%% %s %s %s", &args), "100% a b ");
+        assert_eq!(insert_arguments("%d %0$s %", &args), "%d %0$s %");
+    }
+
+    #[test]
+    fn legacy_round_trip() {
+        let legacy = "§c§lError§6 gold";
+        let component = ChatComponent::from_legacy(legacy);
+        let mut error = ChatComponent::text("Error").with_color(Color::Red);
+        error.style.bold = Some(true);
+
+        // The color code resets the bold formatting.
+        assert_eq!(
+            component,
+            ChatComponent::text("")
+                .append(error)
+                .append(ChatComponent::text(" gold").with_color(Color::Gold))
+        );
+        assert_eq!(component.to_legacy(), legacy);
+
+        let component = ChatComponent::from_legacy("plain §lbold§r plain");
+
+        assert_eq!(component.to_legacy(), "plain §r§lbold§r plain");
+        assert_eq!(
+            ChatComponent::from_legacy(&component.to_legacy()),
+            component
+        );
+
+        assert_eq!(
+            ChatComponent::from_legacy("§A§xHi§"),
+            ChatComponent::text("Hi").with_color(Color::Green)
+        );
+        assert_eq!(
+            ChatComponent::text("Hex")
+                .with_color(Color::Hex(0xFF0000))
+                .to_legacy(),
+            "§4Hex"
+        );
+    }
+
+    #[test]
+    fn hex_colors_are_downgraded_before_1_16() {
+        let hover =
+            |color| HoverEvent::ShowText(Box::new(ChatComponent::text("b").with_color(color)));
+        let component = |text, hover, with| {
+            ChatComponent::text("a")
+                .with_color(text)
+                .with_hover_event(hover)
+                .append(ChatComponent::translate(
+                    "chat.type.text",
+                    vec![ChatComponent::text("c").with_color(with)],
+                ))
+        };
+        let hex = component(
+            Color::Hex(0xFC5454),
+            hover(Color::Hex(0x0000A0)),
+            Color::Hex(0x50FF50),
+        );
+        let named = component(Color::Red, hover(Color::DarkBlue), Color::Green);
+
+        assert_eq!(
+            hex.for_protocol_version(HEX_COLOR_PROTOCOL_VERSION - 1),
+            named
+        );
+        assert_eq!(hex.for_protocol_version(HEX_COLOR_PROTOCOL_VERSION), hex);
+        assert_eq!(named.for_protocol_version(47), named);
+        assert_eq!(
+            hex.to_json(HEX_COLOR_PROTOCOL_VERSION - 1).unwrap(),
+            named.to_json(HEX_COLOR_PROTOCOL_VERSION).unwrap()
+        );
+        assert!(hex
+            .to_json(HEX_COLOR_PROTOCOL_VERSION)
+            .unwrap()
+            .contains("\"color\":\"#0000A0\""));
+    }
+
+    #[test]
+    fn events_json_round_trip() {
+        let component = ChatComponent::text("Click")
+            .with_click_event(ClickEvent::RunCommand("/help".to_string()))
+            .with_hover_event(HoverEvent::ShowText(Box::new(
+                ChatComponent::text("Help").with_color(Color::Gold),
+            )));
+        let json = component.to_json(HEX_COLOR_PROTOCOL_VERSION).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"text":"Click","clickEvent":{"action":"run_command","value":"/help"},"hoverEvent":{"action":"show_text","value":{"color":"gold","text":"Help"}}}"#
+        );
+        assert_eq!(ChatComponent::from_json(&json).unwrap(), component);
+
+        // 1.16 calls the value of hover events `contents`.
+        let item = ChatComponent::from_json(
+            r#"{"text":"","hoverEvent":{"action":"show_item","contents":"{id:\"minecraft:stone\"}"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            item,
+            ChatComponent::text("")
+                .with_hover_event(HoverEvent::ShowItem("{id:\"minecraft:stone\"}".to_string()))
+        );
+        assert!(ChatComponent::from_json(
+            r#"{"text":"","hoverEvent":{"action":"show_achievement","value":"a"}}"#
+        )
+        .is_err());
+    }
+}
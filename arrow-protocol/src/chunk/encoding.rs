@@ -0,0 +1,375 @@
+use std::{
+    fmt::Display,
+    io::{Cursor, Read},
+};
+
+use super::{
+    storage::{BitStorage, PalettedContainer},
+    Chunk, ChunkSection, NibbleArray, SECTION_COUNT, SECTION_VOLUME,
+};
+use crate::{
+    packets::error::PacketError,
+    serde::varint::{read_varint, write_varint},
+};
+
+/// The most bits per block a section palette is sent with. Sections needing more bits are sent
+/// with the global palette, which are the block states themselves.
+const MAX_PALETTE_BITS: u8 = 8;
+/// The bits every entry of a heightmap takes up.
+const HEIGHTMAP_BITS: u8 = 9;
+
+impl Chunk {
+    /// Encodes the sections and, before protocol version 573, the biomes of the chunk as the data
+    /// of the Chunk Data packet for `protocol_version`. Returns the bit mask of the sections
+    /// contained in the data. Sections containing only air are left out.
+    ///
+    /// Before protocol version 477 the light of the sections is part of the data. Biomes are
+    /// sent per column before protocol version 573, using the lowest biome of every column.
+    pub fn encode_data(&self, protocol_version: i32) -> (i32, Vec<u8>) {
+        let sections: Vec<(usize, &ChunkSection)> = self
+            .sections
+            .iter()
+            .enumerate()
+            .filter_map(|(i, section)| match section {
+                Some(section) if section.block_count() > 0 => Some((i, section)),
+                _ => None,
+            })
+            .collect();
+        let mask = sections.iter().fold(0, |mask, (i, _)| mask | 1 << i);
+        let mut data = vec![];
+
+        if protocol_version < 107 {
+            for (_, section) in &sections {
+                for state in section.states.values() {
+                    data.extend_from_slice(&(state as u16).to_le_bytes());
+                }
+            }
+
+            for (_, section) in &sections {
+                data.extend_from_slice(section.block_light.as_bytes());
+            }
+
+            if self.sky_light {
+                for (_, section) in &sections {
+                    data.extend_from_slice(section.sky_light.as_bytes());
+                }
+            }
+        } else {
+            for (_, section) in &sections {
+                if protocol_version >= 477 {
+                    data.extend_from_slice(&(section.block_count() as i16).to_be_bytes());
+                }
+
+                write_states(&section.states, protocol_version, &mut data);
+
+                if protocol_version < 477 {
+                    data.extend_from_slice(section.block_light.as_bytes());
+
+                    if self.sky_light {
+                        data.extend_from_slice(section.sky_light.as_bytes());
+                    }
+                }
+            }
+        }
+
+        if protocol_version < 393 {
+            data.extend(self.column_biomes().map(|biome| biome as u8));
+        } else if protocol_version < 573 {
+            for biome in self.column_biomes() {
+                data.extend_from_slice(&biome.to_be_bytes());
+            }
+        }
+
+        (mask, data)
+    }
+
+    /// Decodes the data of a Chunk Data packet for `protocol_version` containing the sections in
+    /// `mask` and, before protocol version 573, the biomes.
+    ///
+    /// Since protocol version 477 the light is sent in Update Light packets, so those chunks are
+    /// assumed to have sky light and their sections get the default light. Before that, whether
+    /// the dimension has sky light is determined from the size of the data.
+    pub fn decode_data(protocol_version: i32, mask: i32, data: &[u8]) -> Result<Self, PacketError> {
+        if protocol_version >= 477 {
+            return Self::decode_sections(protocol_version, mask, data, true);
+        }
+
+        Self::decode_sections(protocol_version, mask, data, true)
+            .or_else(|_| Self::decode_sections(protocol_version, mask, data, false))
+    }
+
+    /// Encodes the heightmap of the chunk as the long array of the `MOTION_BLOCKING` heightmap
+    /// sent since protocol version 477.
+    pub fn encode_heightmap(&self, protocol_version: i32) -> Vec<i64> {
+        let mut storage = BitStorage::new(HEIGHTMAP_BITS, 16 * 16, protocol_version < 735);
+
+        for (i, height) in self.heightmap().into_iter().enumerate() {
+            storage.set(i, height as u32);
+        }
+
+        storage.data().iter().map(|long| *long as i64).collect()
+    }
+
+    /// Returns the biome of every column, ordered by z and x.
+    fn column_biomes(&self) -> impl Iterator<Item = i32> + '_ {
+        (0..16 * 16).map(move |i| self.biome(i % 16, 0, i / 16))
+    }
+
+    fn decode_sections(
+        protocol_version: i32,
+        mask: i32,
+        data: &[u8],
+        sky_light: bool,
+    ) -> Result<Self, PacketError> {
+        let mut chunk = Self::new(sky_light);
+        let mut reader = Cursor::new(data);
+        let indices: Vec<usize> = (0..SECTION_COUNT).filter(|i| mask & 1 << i != 0).collect();
+
+        if protocol_version < 107 {
+            for i in &indices {
+                let bytes = read_bytes(&mut reader, SECTION_VOLUME * 2)?;
+                let states = bytes
+                    .chunks(2)
+                    .map(|state| u16::from_le_bytes([state[0], state[1]]) as u32);
+
+                chunk.section_mut(*i).states =
+                    PalettedContainer::from_values(SECTION_VOLUME, states);
+            }
+
+            for i in &indices {
+                chunk.section_mut(*i).block_light = read_nibble_array(&mut reader)?;
+            }
+
+            if sky_light {
+                for i in &indices {
+                    chunk.section_mut(*i).sky_light = read_nibble_array(&mut reader)?;
+                }
+            }
+        } else {
+            for i in indices {
+                if protocol_version >= 477 {
+                    read_bytes(&mut reader, 2)?;
+                }
+
+                let section = chunk.section_mut(i);
+
+                section.states = read_states(protocol_version, &mut reader)?;
+
+                if protocol_version < 477 {
+                    section.block_light = read_nibble_array(&mut reader)?;
+
+                    if sky_light {
+                        section.sky_light = read_nibble_array(&mut reader)?;
+                    }
+                }
+            }
+        }
+
+        if protocol_version < 573 {
+            let biomes: Vec<i32> = if protocol_version < 393 {
+                read_bytes(&mut reader, 16 * 16)?
+                    .into_iter()
+                    .map(|biome| biome as i32)
+                    .collect()
+            } else {
+                read_bytes(&mut reader, 16 * 16 * 4)?
+                    .chunks(4)
+                    .map(|biome| i32::from_be_bytes([biome[0], biome[1], biome[2], biome[3]]))
+                    .collect()
+            };
+
+            for y in (0..256).step_by(4) {
+                for z in (0..16).step_by(4) {
+                    for x in (0..16).step_by(4) {
+                        chunk.set_biome(x, y, z, biomes[z * 16 + x]);
+                    }
+                }
+            }
+        }
+
+        if reader.position() as usize != data.len() {
+            return Err(PacketError::SerdeError(format!(
+                "{} unexpected bytes after the chunk data",
+                data.len() - reader.position() as usize
+            )));
+        }
+
+        Ok(chunk)
+    }
+}
+
+/// Returns the bit mask of the present light arrays.
+pub(crate) fn light_mask(light: &[Option<NibbleArray>]) -> i32 {
+    light
+        .iter()
+        .enumerate()
+        .filter(|(_, array)| array.is_some())
+        .fold(0, |mask, (i, _)| mask | 1 << i)
+}
+
+/// Writes the present light arrays of an Update Light packet, each prefixed by its length.
+pub(crate) fn write_light_arrays(light: &[Option<NibbleArray>], data: &mut Vec<u8>) {
+    for array in light.iter().flatten() {
+        write_varint(NibbleArray::LEN as i32, &mut *data).unwrap();
+        data.extend_from_slice(array.as_bytes());
+    }
+}
+
+/// Reads the `count` light arrays of an Update Light packet in `mask` from `reader`. The arrays
+/// in `empty_mask` are set to zero.
+pub(crate) fn read_light_arrays<R: Read>(
+    count: usize,
+    mask: i32,
+    empty_mask: i32,
+    mut reader: R,
+) -> Result<Vec<Option<NibbleArray>>, PacketError> {
+    let mut light = vec![None; count];
+
+    for (i, array) in light.iter_mut().enumerate() {
+        if mask & 1 << i != 0 {
+            let len = read_varint(&mut reader).map_err(error)?;
+
+            if len as usize != NibbleArray::LEN {
+                return Err(PacketError::SerdeError(format!(
+                    "Invalid light array length {}",
+                    len
+                )));
+            }
+
+            *array = Some(read_nibble_array(&mut reader)?);
+        } else if empty_mask & 1 << i != 0 {
+            *array = Some(NibbleArray::new(0));
+        }
+    }
+
+    Ok(light)
+}
+
+/// Writes the bits per block, the palette and the packed block states of a section.
+fn write_states(states: &PalettedContainer, protocol_version: i32, data: &mut Vec<u8>) {
+    let spanning = protocol_version < 735;
+    let bits = PalettedContainer::bits_for(states.palette().len());
+
+    let storage = if bits <= MAX_PALETTE_BITS {
+        let mut storage = BitStorage::new(bits, states.len(), spanning);
+
+        for (i, index) in states.indices().values().enumerate() {
+            storage.set(i, index);
+        }
+
+        data.push(bits);
+        write_varint(states.palette().len() as i32, &mut *data).unwrap();
+
+        for state in states.palette() {
+            write_varint(*state as i32, &mut *data).unwrap();
+        }
+
+        storage
+    } else {
+        let bits = global_palette_bits(protocol_version);
+        let mut storage = BitStorage::new(bits, states.len(), spanning);
+
+        for (i, state) in states.values().enumerate() {
+            storage.set(i, state);
+        }
+
+        data.push(bits);
+
+        // The length of the palette is still sent without a palette before protocol version 393.
+        if protocol_version < 393 {
+            write_varint(0, &mut *data).unwrap();
+        }
+
+        storage
+    };
+
+    write_varint(storage.data().len() as i32, &mut *data).unwrap();
+
+    for long in storage.data() {
+        data.extend_from_slice(&long.to_be_bytes());
+    }
+}
+
+/// Reads the bits per block, the palette and the packed block states of a section.
+fn read_states<R: Read>(
+    protocol_version: i32,
+    mut reader: R,
+) -> Result<PalettedContainer, PacketError> {
+    let bits = read_bytes(&mut reader, 1)?[0];
+
+    let palette = if bits <= MAX_PALETTE_BITS || protocol_version < 393 {
+        let len = read_varint(&mut reader).map_err(error)?;
+        let mut palette = Vec::with_capacity(len.clamp(0, SECTION_VOLUME as i32) as usize);
+
+        for _ in 0..len {
+            palette.push(read_varint(&mut reader).map_err(error)? as u32);
+        }
+
+        Some(palette).filter(|_| bits <= MAX_PALETTE_BITS)
+    } else {
+        None
+    };
+
+    let len = read_varint(&mut reader).map_err(error)?;
+
+    if !(0..=SECTION_VOLUME as i32).contains(&len) {
+        return Err(PacketError::SerdeError(format!(
+            "Invalid number of longs {}",
+            len
+        )));
+    }
+
+    let longs = read_bytes(&mut reader, len as usize * 8)?
+        .chunks(8)
+        .map(|long| {
+            u64::from_be_bytes([
+                long[0], long[1], long[2], long[3], long[4], long[5], long[6], long[7],
+            ])
+        })
+        .collect();
+    let storage = BitStorage::from_data(bits, SECTION_VOLUME, protocol_version < 735, longs)
+        .ok_or_else(|| {
+            PacketError::SerdeError(format!("{} longs don't fit {} bits per block", len, bits))
+        })?;
+
+    let states = match palette {
+        Some(palette) => storage
+            .values()
+            .map(|index| {
+                palette.get(index as usize).copied().ok_or_else(|| {
+                    PacketError::SerdeError(format!("Invalid palette index {}", index))
+                })
+            })
+            .collect::<Result<Vec<u32>, PacketError>>()?,
+        None => storage.values().collect(),
+    };
+
+    Ok(PalettedContainer::from_values(SECTION_VOLUME, states))
+}
+
+/// Returns the bits per block of the global palette of `protocol_version`.
+fn global_palette_bits(protocol_version: i32) -> u8 {
+    if protocol_version >= 735 {
+        15
+    } else if protocol_version >= 393 {
+        14
+    } else {
+        13
+    }
+}
+
+fn read_nibble_array<R: Read>(reader: R) -> Result<NibbleArray, PacketError> {
+    Ok(NibbleArray(read_bytes(reader, NibbleArray::LEN)?))
+}
+
+fn read_bytes<R: Read>(mut reader: R, len: usize) -> Result<Vec<u8>, PacketError> {
+    let mut bytes = vec![0; len];
+
+    reader.read_exact(&mut bytes).map_err(error)?;
+
+    Ok(bytes)
+}
+
+fn error<E: Display>(e: E) -> PacketError {
+    PacketError::SerdeError(format!("{}", e))
+}
@@ -0,0 +1,319 @@
+/// The encodings of chunks in the Chunk Data packet of the supported protocol versions.
+mod encoding;
+/// Bit-packed storage of block states and heightmaps.
+pub mod storage;
+
+#[cfg(test)]
+mod tests;
+
+pub(crate) use encoding::{light_mask, read_light_arrays, write_light_arrays};
+use storage::PalettedContainer;
+
+/// The number of sections stacked in a chunk.
+pub const SECTION_COUNT: usize = 16;
+/// The number of blocks in a section.
+pub const SECTION_VOLUME: usize = 16 * 16 * 16;
+/// The number of biomes of a chunk, one for every 4x4x4 blocks.
+pub const BIOME_COUNT: usize = 4 * 4 * 64;
+/// The number of sections light is sent for since protocol version 477, including the ones
+/// directly below and above the chunk.
+pub const LIGHT_SECTION_COUNT: usize = SECTION_COUNT + 2;
+/// The block state of air in every protocol version.
+pub const AIR: u32 = 0;
+/// The numeric id of the plains biome.
+pub const PLAINS: i32 = 1;
+
+/// A column of 16 by 256 by 16 blocks.
+///
+/// Block states are the numeric ids clients of the protocol version the chunk is sent with
+/// understand: `id << 4 | meta` before protocol version 393 and the ids of the global palette
+/// since then.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    sections: Vec<Option<ChunkSection>>,
+    biomes: Vec<i32>,
+    sky_light: bool,
+}
+
+/// A cube of 16 by 16 by 16 blocks of a [`Chunk`] and their light.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkSection {
+    states: PalettedContainer,
+    block_light: NibbleArray,
+    sky_light: NibbleArray,
+}
+
+/// A light level from 0 to 15 for every block of a section, packed into 2048 bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NibbleArray(Vec<u8>);
+
+impl Chunk {
+    /// Creates a chunk filled with air in the plains biome. `sky_light` is whether the dimension
+    /// of the chunk has sky light.
+    pub fn new(sky_light: bool) -> Self {
+        Self {
+            sections: vec![None; SECTION_COUNT],
+            biomes: vec![PLAINS; BIOME_COUNT],
+            sky_light,
+        }
+    }
+
+    /// Returns whether the dimension of the chunk has sky light.
+    pub fn has_sky_light(&self) -> bool {
+        self.sky_light
+    }
+
+    /// Returns the block state at `x`, `y` and `z` within the chunk.
+    pub fn block(&self, x: usize, y: usize, z: usize) -> u32 {
+        match &self.sections[y / 16] {
+            Some(section) => section.block(x, y % 16, z),
+            None => AIR,
+        }
+    }
+
+    /// Sets the block state at `x`, `y` and `z` within the chunk.
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, state: u32) {
+        if state == AIR && self.sections[y / 16].is_none() {
+            return;
+        }
+
+        self.section_mut(y / 16).set_block(x, y % 16, z, state);
+    }
+
+    /// Returns the section `index` from the bottom if it contains any blocks or light.
+    pub fn section(&self, index: usize) -> Option<&ChunkSection> {
+        self.sections[index].as_ref()
+    }
+
+    /// Returns the section `index` from the bottom, creating an empty one if there is none.
+    pub fn section_mut(&mut self, index: usize) -> &mut ChunkSection {
+        self.sections[index].get_or_insert_with(ChunkSection::new)
+    }
+
+    /// Replaces the section `index` from the bottom.
+    pub fn set_section(&mut self, index: usize, section: Option<ChunkSection>) {
+        self.sections[index] = section;
+    }
+
+    /// Returns the biome at `x`, `y` and `z` within the chunk. All blocks in the same 4x4x4 cube
+    /// share a biome.
+    pub fn biome(&self, x: usize, y: usize, z: usize) -> i32 {
+        self.biomes[biome_index(x, y, z)]
+    }
+
+    /// Sets the biome of the 4x4x4 cube containing `x`, `y` and `z`.
+    pub fn set_biome(&mut self, x: usize, y: usize, z: usize, biome: i32) {
+        self.biomes[biome_index(x, y, z)] = biome;
+    }
+
+    /// Returns the biomes of all 4x4x4 cubes ordered by y, z and x.
+    pub fn biomes(&self) -> &[i32] {
+        &self.biomes
+    }
+
+    /// Replaces all biomes, ordered by y, z and x. Returns `false` if there are not exactly
+    /// [`BIOME_COUNT`] biomes.
+    pub fn set_biomes(&mut self, biomes: Vec<i32>) -> bool {
+        if biomes.len() != BIOME_COUNT {
+            return false;
+        }
+
+        self.biomes = biomes;
+
+        true
+    }
+
+    /// Returns the height above the highest block that isn't air for every column, ordered by z
+    /// and x.
+    pub fn heightmap(&self) -> Vec<u16> {
+        let mut heightmap = vec![0; 16 * 16];
+
+        for (index, height) in heightmap.iter_mut().enumerate() {
+            let (x, z) = (index % 16, index / 16);
+
+            for (i, section) in self.sections.iter().enumerate().rev() {
+                let section = match section {
+                    Some(section) => section,
+                    None => continue,
+                };
+
+                if let Some(y) = (0..16).rev().find(|y| section.block(x, *y, z) != AIR) {
+                    *height = (i * 16 + y + 1) as u16;
+                    break;
+                }
+            }
+        }
+
+        heightmap
+    }
+
+    /// Returns the chunk with every block state replaced by `f(state)`, e.g. to convert the
+    /// canonical ids of a [`BlockRegistry`](crate::block::BlockRegistry) to the ids of a protocol
+    /// version.
+    pub fn map_states<F: FnMut(u32) -> u32>(&self, mut f: F) -> Self {
+        let sections = self
+            .sections
+            .iter()
+            .map(|section| {
+                section.as_ref().map(|section| ChunkSection {
+                    states: section.states.map(&mut f),
+                    block_light: section.block_light.clone(),
+                    sky_light: section.sky_light.clone(),
+                })
+            })
+            .collect();
+
+        Self {
+            sections,
+            biomes: self.biomes.clone(),
+            sky_light: self.sky_light,
+        }
+    }
+
+    /// Returns the sky light and the block light of the [`LIGHT_SECTION_COUNT`] sections sent
+    /// since protocol version 477, starting below the chunk. Sections without blocks are fully
+    /// lit by the sky. There is no sky light in dimensions without one.
+    pub fn light(&self) -> (Vec<Option<NibbleArray>>, Vec<Option<NibbleArray>>) {
+        let mut sky_light = vec![None; LIGHT_SECTION_COUNT];
+        let mut block_light = vec![None; LIGHT_SECTION_COUNT];
+
+        for (i, section) in self.sections.iter().enumerate() {
+            match section {
+                Some(section) => {
+                    sky_light[i + 1] = Some(section.sky_light.clone());
+                    block_light[i + 1] = Some(section.block_light.clone());
+                }
+                None => sky_light[i + 1] = Some(NibbleArray::new(15)),
+            }
+        }
+
+        if !self.sky_light {
+            return (vec![None; LIGHT_SECTION_COUNT], block_light);
+        }
+
+        sky_light[LIGHT_SECTION_COUNT - 1] = Some(NibbleArray::new(15));
+
+        (sky_light, block_light)
+    }
+}
+
+impl ChunkSection {
+    /// Creates a section filled with air, no block light and full sky light.
+    pub fn new() -> Self {
+        Self {
+            states: PalettedContainer::new(SECTION_VOLUME, AIR),
+            block_light: NibbleArray::new(0),
+            sky_light: NibbleArray::new(15),
+        }
+    }
+
+    /// Creates a section from its block states and light.
+    pub fn from_parts(
+        states: PalettedContainer,
+        block_light: NibbleArray,
+        sky_light: NibbleArray,
+    ) -> Self {
+        Self {
+            states,
+            block_light,
+            sky_light,
+        }
+    }
+
+    /// Returns the block state at `x`, `y` and `z` within the section.
+    pub fn block(&self, x: usize, y: usize, z: usize) -> u32 {
+        self.states.get(block_index(x, y, z))
+    }
+
+    /// Sets the block state at `x`, `y` and `z` within the section.
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, state: u32) {
+        self.states.set(block_index(x, y, z), state);
+    }
+
+    /// Returns the block states ordered by y, z and x.
+    pub fn states(&self) -> &PalettedContainer {
+        &self.states
+    }
+
+    /// Returns the number of blocks that aren't air.
+    pub fn block_count(&self) -> usize {
+        self.states.values().filter(|state| *state != AIR).count()
+    }
+
+    /// Returns the light emitted by blocks.
+    pub fn block_light(&self) -> &NibbleArray {
+        &self.block_light
+    }
+
+    pub fn block_light_mut(&mut self) -> &mut NibbleArray {
+        &mut self.block_light
+    }
+
+    /// Returns the light coming from the sky.
+    pub fn sky_light(&self) -> &NibbleArray {
+        &self.sky_light
+    }
+
+    pub fn sky_light_mut(&mut self) -> &mut NibbleArray {
+        &mut self.sky_light
+    }
+}
+
+impl Default for ChunkSection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NibbleArray {
+    /// The number of bytes of a nibble array.
+    pub const LEN: usize = SECTION_VOLUME / 2;
+
+    /// Creates an array with every light level set to `level`.
+    pub fn new(level: u8) -> Self {
+        let level = level & 0xF;
+
+        Self(vec![level << 4 | level; Self::LEN])
+    }
+
+    /// Creates an array from its packed bytes. Returns `None` if there are not
+    /// [`NibbleArray::LEN`] bytes.
+    pub fn from_bytes(bytes: Vec<u8>) -> Option<Self> {
+        if bytes.len() == Self::LEN {
+            Some(Self(bytes))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the packed bytes. The light level of every even index is in the lower half of a
+    /// byte.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the light level at `x`, `y` and `z` within the section.
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u8 {
+        let index = block_index(x, y, z);
+
+        self.0[index / 2] >> (index % 2 * 4) & 0xF
+    }
+
+    /// Sets the light level at `x`, `y` and `z` within the section.
+    pub fn set(&mut self, x: usize, y: usize, z: usize, level: u8) {
+        let index = block_index(x, y, z);
+        let shift = index % 2 * 4;
+
+        self.0[index / 2] = self.0[index / 2] & !(0xF << shift) | (level & 0xF) << shift;
+    }
+}
+
+/// Returns the index of the block at `x`, `y` and `z` within a section.
+fn block_index(x: usize, y: usize, z: usize) -> usize {
+    y << 8 | z << 4 | x
+}
+
+/// Returns the index of the biome of the block at `x`, `y` and `z` within a chunk.
+fn biome_index(x: usize, y: usize, z: usize) -> usize {
+    (y >> 2) << 4 | (z >> 2) << 2 | x >> 2
+}
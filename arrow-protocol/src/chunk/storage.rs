@@ -0,0 +1,237 @@
+/// The number of bits every palette index takes up at least.
+const MIN_PALETTE_BITS: u8 = 4;
+
+/// Values of a fixed number of bits packed into longs, starting at the least significant bit.
+///
+/// Before protocol version 735 values may span two longs. Since then every long holds as many
+/// whole values as fit and the remaining bits are unused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitStorage {
+    bits: u8,
+    len: usize,
+    spanning: bool,
+    data: Vec<u64>,
+}
+
+/// A fixed number of values stored as indices into a palette of the distinct values in use. The
+/// indices take up as few bits as the size of the palette allows, but at least 4.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PalettedContainer {
+    palette: Vec<u32>,
+    storage: BitStorage,
+}
+
+impl BitStorage {
+    /// Creates a storage of `len` zeros taking up `bits` bits each.
+    pub fn new(bits: u8, len: usize, spanning: bool) -> Self {
+        Self {
+            bits,
+            len,
+            spanning,
+            data: vec![0; Self::longs_needed(bits, len, spanning)],
+        }
+    }
+
+    /// Creates a storage of `len` values taking up `bits` bits each from the packed `data`.
+    /// Returns `None` if `data` does not have the length the values need.
+    pub fn from_data(bits: u8, len: usize, spanning: bool, data: Vec<u64>) -> Option<Self> {
+        if bits == 0 || bits > 32 || data.len() != Self::longs_needed(bits, len, spanning) {
+            return None;
+        }
+
+        Some(Self {
+            bits,
+            len,
+            spanning,
+            data,
+        })
+    }
+
+    /// Returns the number of longs `len` values of `bits` bits need.
+    pub fn longs_needed(bits: u8, len: usize, spanning: bool) -> usize {
+        let bits = bits as usize;
+
+        if spanning {
+            (len * bits).div_ceil(64)
+        } else {
+            let per_long = 64 / bits;
+
+            len.div_ceil(per_long)
+        }
+    }
+
+    /// Returns the number of bits every value takes up.
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns whether values may span two longs.
+    pub fn is_spanning(&self) -> bool {
+        self.spanning
+    }
+
+    /// Returns the packed longs.
+    pub fn data(&self) -> &[u64] {
+        &self.data
+    }
+
+    /// Returns the value at `index`.
+    pub fn get(&self, index: usize) -> u32 {
+        let (long, offset) = self.position(index);
+        let mut value = self.data[long] >> offset;
+
+        if offset + self.bits as usize > 64 {
+            value |= self.data[long + 1] << (64 - offset);
+        }
+
+        (value & self.mask()) as u32
+    }
+
+    /// Sets the value at `index` to `value`. Bits of `value` that don't fit are dropped.
+    pub fn set(&mut self, index: usize, value: u32) {
+        let (long, offset) = self.position(index);
+        let mask = self.mask();
+        let value = value as u64 & mask;
+
+        self.data[long] = self.data[long] & !(mask << offset) | value << offset;
+
+        if offset + self.bits as usize > 64 {
+            let shift = 64 - offset;
+
+            self.data[long + 1] = self.data[long + 1] & !(mask >> shift) | value >> shift;
+        }
+    }
+
+    /// Returns all values in order.
+    pub fn values(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.len).map(move |i| self.get(i))
+    }
+
+    /// Returns the index of the long `index` starts in and the bit it starts at.
+    fn position(&self, index: usize) -> (usize, usize) {
+        assert!(index < self.len, "index {} out of bounds", index);
+
+        let bits = self.bits as usize;
+
+        if self.spanning {
+            let bit = index * bits;
+
+            (bit / 64, bit % 64)
+        } else {
+            let per_long = 64 / bits;
+
+            (index / per_long, index % per_long * bits)
+        }
+    }
+
+    fn mask(&self) -> u64 {
+        (1 << self.bits) - 1
+    }
+}
+
+impl PalettedContainer {
+    /// Creates a container of `len` values that are all `value`.
+    pub fn new(len: usize, value: u32) -> Self {
+        Self {
+            palette: vec![value],
+            storage: BitStorage::new(MIN_PALETTE_BITS, len, false),
+        }
+    }
+
+    /// Creates a container from `values`.
+    pub fn from_values<I: IntoIterator<Item = u32>>(len: usize, values: I) -> Self {
+        let mut values = values.into_iter();
+        let mut container = Self::new(len, values.next().unwrap_or_default());
+
+        for (i, value) in values.take(len.saturating_sub(1)).enumerate() {
+            container.set(i + 1, value);
+        }
+
+        container
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Returns whether there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    /// Returns the distinct values that were stored in the container. Values that were
+    /// overwritten since stay in the palette.
+    pub fn palette(&self) -> &[u32] {
+        &self.palette
+    }
+
+    /// Returns the palette indices of the values.
+    pub fn indices(&self) -> &BitStorage {
+        &self.storage
+    }
+
+    /// Returns the value at `index`.
+    pub fn get(&self, index: usize) -> u32 {
+        self.palette[self.storage.get(index) as usize]
+    }
+
+    /// Sets the value at `index` to `value`, growing the palette if needed.
+    pub fn set(&mut self, index: usize, value: u32) {
+        let palette_index = match self.palette.iter().position(|v| *v == value) {
+            Some(i) => i,
+            None => {
+                self.palette.push(value);
+
+                if self.palette.len() > 1 << self.storage.bits() {
+                    self.resize(self.storage.bits() + 1);
+                }
+
+                self.palette.len() - 1
+            }
+        };
+
+        self.storage.set(index, palette_index as u32);
+    }
+
+    /// Returns all values in order.
+    pub fn values(&self) -> impl Iterator<Item = u32> + '_ {
+        self.storage.values().map(move |i| self.palette[i as usize])
+    }
+
+    /// Returns the container with every value replaced by `f(value)`. Only the palette is mapped,
+    /// so values mapped to the same value keep separate palette entries.
+    pub fn map<F: FnMut(u32) -> u32>(&self, f: F) -> Self {
+        Self {
+            palette: self.palette.iter().copied().map(f).collect(),
+            storage: self.storage.clone(),
+        }
+    }
+
+    /// Returns the number of bits the indices of a palette of `len` values take up.
+    pub fn bits_for(len: usize) -> u8 {
+        let bits = (usize::BITS - len.saturating_sub(1).leading_zeros()) as u8;
+
+        bits.max(MIN_PALETTE_BITS)
+    }
+
+    fn resize(&mut self, bits: u8) {
+        let mut storage = BitStorage::new(bits, self.storage.len(), false);
+
+        for (i, value) in self.storage.values().enumerate() {
+            storage.set(i, value);
+        }
+
+        self.storage = storage;
+    }
+}
@@ -0,0 +1,338 @@
+use std::sync::Arc;
+
+use super::{
+    storage::{BitStorage, PalettedContainer},
+    Chunk, SECTION_VOLUME,
+};
+use crate::{
+    packets::{PacketKind, State},
+    serde::varint::write_varint,
+};
+
+/// The first longs of the palette indices of [`chunk`] when values span two longs.
+const SPANNING_STATES: [u64; 2] = [0xD62D_4941_CC52_0C41, 0x83DC];
+/// The first longs of the palette indices of [`chunk`] when they don't span two longs.
+const NON_SPANNING_STATES: [u64; 2] = [0x062D_4941_CC52_0C41, 0x0008_3DCD];
+
+/// Returns a chunk with the states 1 to 16 in the first row of its lowest section, block light 15
+/// in its first block and biome 2 in the second layer of biomes.
+fn chunk() -> Chunk {
+    let mut chunk = Chunk::new(true);
+
+    for x in 0..16 {
+        chunk.set_block(x, 0, 0, x as u32 + 1);
+    }
+
+    chunk.section_mut(0).block_light_mut().set(0, 0, 0, 15);
+    chunk.set_biome(0, 4, 0, 2);
+
+    chunk
+}
+
+/// Returns a chunk with the states 1 to 5 in the first row of its lowest section. The section had
+/// the states 1 to 256, which stay in its palette and are too many for a section palette.
+fn global_palette_chunk() -> Chunk {
+    let mut chunk = Chunk::new(true);
+
+    for i in 0..256 {
+        chunk.set_block(i % 16, 0, i / 16, i as u32 + 1);
+    }
+
+    for i in 5..256 {
+        chunk.set_block(i % 16, 0, i / 16, 0);
+    }
+
+    chunk
+}
+
+/// Returns `longs` followed by zeros up to `len` longs as big-endian bytes.
+fn longs(longs: &[u64], len: usize) -> Vec<u8> {
+    let mut bytes: Vec<u8> = longs.iter().flat_map(|long| long.to_be_bytes()).collect();
+
+    bytes.resize(len * 8, 0);
+    bytes
+}
+
+/// Returns the block light and the full sky light of a section whose first block has the block
+/// light `first`.
+fn light(first: u8) -> Vec<u8> {
+    let mut light = vec![0; 2048];
+
+    light[0] = first;
+    light.extend(vec![0xFF; 2048]);
+    light
+}
+
+/// Returns the palette of [`chunk`] with its bits per block.
+fn palette() -> Vec<u8> {
+    let mut palette = vec![5, 17];
+
+    palette.extend(0..=16);
+    palette
+}
+
+/// Returns `count` biomes as big-endian ints, where the biome at `index` is 2 and all others 1.
+fn int_biomes(count: usize, index: Option<usize>) -> Vec<u8> {
+    (0..count)
+        .flat_map(|i| if Some(i) == index { 2i32 } else { 1 }.to_be_bytes())
+        .collect()
+}
+
+fn assert_same_blocks(decoded: &Chunk, chunk: &Chunk) {
+    let states =
+        |chunk: &Chunk| -> Vec<u32> { chunk.section(0).unwrap().states().values().collect() };
+
+    assert_eq!(states(decoded), states(chunk));
+    assert!(decoded.section(1).is_none());
+}
+
+#[test]
+fn chunk_data_raw_shorts() {
+    let chunk = chunk();
+    let (mask, data) = chunk.encode_data(47);
+
+    let mut expected: Vec<u8> = (1..=16).flat_map(|state| vec![state, 0]).collect();
+    expected.resize(SECTION_VOLUME * 2, 0);
+    expected.extend(light(0x0F));
+    expected.extend(vec![1; 256]);
+
+    assert_eq!(mask, 1);
+    assert_eq!(data, expected);
+
+    let decoded = Chunk::decode_data(47, mask, &data).unwrap();
+
+    assert_same_blocks(&decoded, &chunk);
+    assert_eq!(decoded.section(0).unwrap().block_light().get(0, 0, 0), 15);
+}
+
+#[test]
+fn chunk_data_palette() {
+    let chunk = chunk();
+
+    let mut expected = palette();
+    expected.extend(&[0xC0, 0x02]);
+    expected.extend(longs(&SPANNING_STATES, 320));
+    expected.extend(light(0x0F));
+    expected.extend(vec![1; 256]);
+
+    for pv in [107, 340].iter().copied() {
+        let (mask, data) = chunk.encode_data(pv);
+
+        assert_eq!(mask, 1, "{}", pv);
+        assert_eq!(data, expected, "{}", pv);
+        assert_same_blocks(&Chunk::decode_data(pv, mask, &data).unwrap(), &chunk);
+    }
+
+    // Since 1.13 the column biomes are ints.
+    expected.truncate(expected.len() - 256);
+    expected.extend(int_biomes(256, None));
+
+    assert_eq!(chunk.encode_data(393), (1, expected));
+}
+
+#[test]
+fn chunk_data_global_palette() {
+    let chunk = global_palette_chunk();
+
+    // Before 1.13 the empty palette is still prefixed by its length.
+    let mut expected = vec![13, 0, 0xC0, 0x06];
+    expected.extend(longs(&[0x0050_0200_0C00_4001], 832));
+    expected.extend(light(0));
+    expected.extend(vec![1; 256]);
+
+    assert_eq!(chunk.encode_data(340), (1, expected));
+
+    let mut expected = vec![14, 0x80, 0x07];
+    expected.extend(longs(&[0x0500_1000_3000_8001], 896));
+    expected.extend(light(0));
+    expected.extend(int_biomes(256, None));
+
+    let (mask, data) = chunk.encode_data(393);
+
+    assert_eq!(data, expected);
+    assert_same_blocks(&Chunk::decode_data(393, mask, &data).unwrap(), &chunk);
+}
+
+#[test]
+fn chunk_data_without_light() {
+    let chunk = chunk();
+
+    let mut expected = vec![0, 16];
+    expected.extend(palette());
+    expected.extend(&[0xC0, 0x02]);
+    expected.extend(longs(&SPANNING_STATES, 320));
+    expected.extend(int_biomes(256, None));
+
+    let (mask, data) = chunk.encode_data(477);
+
+    assert_eq!(data, expected);
+
+    let decoded = Chunk::decode_data(477, mask, &data).unwrap();
+
+    assert_same_blocks(&decoded, &chunk);
+    assert_eq!(decoded.section(0).unwrap().block_light().get(0, 0, 0), 0);
+}
+
+#[test]
+fn heightmaps() {
+    let chunk = chunk();
+    let heightmap = |longs: &[u64], len| {
+        let mut heightmap: Vec<i64> = longs.iter().map(|long| *long as i64).collect();
+
+        heightmap.resize(len, 0);
+        heightmap
+    };
+
+    assert_eq!(
+        chunk.encode_heightmap(477),
+        heightmap(&[0x8040_2010_0804_0201, 0x4020_1008_0402_0100, 0x80], 36)
+    );
+    assert_eq!(
+        chunk.encode_heightmap(735),
+        heightmap(&[0x0040_2010_0804_0201, 0x0040_2010_0804_0201, 0x201], 37)
+    );
+}
+
+/// Encodes the Chunk Data packet of [`chunk`] for `protocol_version`, checks that it ends with
+/// `biomes`, the sections using `states` and no block entities and decodes it again.
+fn assert_chunk_packet(protocol_version: i32, biomes: Vec<u8>, states: Vec<u8>) {
+    let chunk = chunk();
+    let packet = PacketKind::ChunkData {
+        x: 1,
+        z: -1,
+        chunk: Arc::new(chunk.clone()),
+    }
+    .into_packet(protocol_version)
+    .unwrap();
+    let bytes = packet.data_bytes().unwrap();
+
+    let mut data = vec![0, 16];
+    data.extend(palette());
+    data.extend(states);
+
+    let mut expected = biomes;
+    write_varint(data.len() as i32, &mut expected).unwrap();
+    expected.extend(data);
+    expected.push(0);
+
+    assert_eq!(&bytes[..8], &[0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert!(bytes.ends_with(&expected), "{}", protocol_version);
+
+    let id = packet.self_id(protocol_version);
+
+    match PacketKind::from_bytes(State::Play, false, protocol_version, id, bytes).unwrap() {
+        PacketKind::ChunkData {
+            x: 1,
+            z: -1,
+            chunk: decoded,
+        } => {
+            assert_same_blocks(&decoded, &chunk);
+            assert_eq!(decoded.biomes(), chunk.biomes());
+        }
+        other => panic!("Unexpected packet {} in {}", other, protocol_version),
+    }
+}
+
+#[test]
+fn chunk_data_3d_biomes() {
+    let mut states = vec![0xC0, 0x02];
+    states.extend(longs(&SPANNING_STATES, 320));
+
+    assert_chunk_packet(573, int_biomes(1024, Some(16)), states);
+}
+
+#[test]
+fn chunk_data_non_spanning() {
+    let mut states = vec![0xD6, 0x02];
+    states.extend(longs(&NON_SPANNING_STATES, 342));
+
+    assert_chunk_packet(735, int_biomes(1024, Some(16)), states.clone());
+
+    // Since 1.16.2 the biomes are VarInts prefixed by their number.
+    let mut biomes = vec![0x80, 0x08];
+    biomes.extend((0..1024).map(|i| if i == 16 { 2 } else { 1 }));
+
+    assert_chunk_packet(751, biomes, states);
+}
+
+#[test]
+fn spanning_bit_storage() {
+    let mut storage = BitStorage::new(5, 64, true);
+
+    assert_eq!(storage.data().len(), 5);
+
+    storage.set(12, 0b10111);
+
+    assert_eq!(storage.data()[..2], [0x7000_0000_0000_0000, 0b1]);
+    assert_eq!(storage.get(12), 0b10111);
+
+    storage.set(11, 31);
+    storage.set(13, 31);
+
+    assert_eq!(storage.get(12), 0b10111);
+    assert_eq!(storage.get(11), 31);
+    assert_eq!(storage.get(13), 31);
+
+    storage.set(12, 0);
+
+    assert_eq!(storage.data()[..2], [0x0F80_0000_0000_0000, 0b11_1110]);
+}
+
+#[test]
+fn non_spanning_bit_storage() {
+    let mut storage = BitStorage::new(5, 64, false);
+
+    assert_eq!(storage.data().len(), 6);
+
+    storage.set(11, 31);
+    storage.set(12, 0b10111);
+
+    // The top 4 bits of every long are unused.
+    assert_eq!(storage.data()[..2], [0x0F80_0000_0000_0000, 0b10111]);
+    assert_eq!(storage.get(11), 31);
+    assert_eq!(storage.get(12), 0b10111);
+
+    storage.set(11, 0x3F);
+
+    assert_eq!(storage.get(11), 31);
+    assert_eq!(storage.get(12), 0b10111);
+}
+
+#[test]
+fn bit_storage_lengths() {
+    assert_eq!(BitStorage::longs_needed(4, SECTION_VOLUME, true), 256);
+    assert_eq!(BitStorage::longs_needed(4, SECTION_VOLUME, false), 256);
+    assert_eq!(BitStorage::longs_needed(5, SECTION_VOLUME, true), 320);
+    assert_eq!(BitStorage::longs_needed(5, SECTION_VOLUME, false), 342);
+
+    assert!(BitStorage::from_data(5, 64, true, vec![0; 5]).is_some());
+    assert!(BitStorage::from_data(5, 64, true, vec![0; 6]).is_none());
+    assert!(BitStorage::from_data(5, 64, false, vec![0; 6]).is_some());
+    assert!(BitStorage::from_data(0, 64, false, vec![]).is_none());
+}
+
+#[test]
+fn palette_bits() {
+    assert_eq!(PalettedContainer::bits_for(0), 4);
+    assert_eq!(PalettedContainer::bits_for(1), 4);
+    assert_eq!(PalettedContainer::bits_for(2), 4);
+    assert_eq!(PalettedContainer::bits_for(16), 4);
+    assert_eq!(PalettedContainer::bits_for(17), 5);
+    assert_eq!(PalettedContainer::bits_for(256), 8);
+    assert_eq!(PalettedContainer::bits_for(257), 9);
+
+    let mut container = PalettedContainer::new(SECTION_VOLUME, 0);
+
+    assert_eq!(container.indices().bits(), 4);
+
+    for i in 1..16 {
+        container.set(i, i as u32);
+    }
+
+    assert_eq!(container.indices().bits(), 4);
+
+    container.set(16, 16);
+
+    assert_eq!(container.indices().bits(), 5);
+    assert!(container.values().take(17).eq(0..17));
+}
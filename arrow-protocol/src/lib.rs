@@ -9,7 +9,7 @@
 //!
 //! let packet = PacketKind::LoginStart("Foo".to_string());
 //! // 754 is the protocol version of the 1.16.5 version.
-//! let bytes = packet.clone().into_packet(754).data_bytes().unwrap();
+//! let bytes = packet.clone().into_packet(754).unwrap().data_bytes().unwrap();
 //!
 //! assert_eq!(bytes, vec![3, 70, 111, 111]);
 //!
@@ -20,6 +20,15 @@
 
 #[deny(missing_docs)]
 
+/// Block states and their numeric ids in the supported protocol versions.
+pub mod block;
+
+/// The chat components used for the MOTD, disconnect reasons and chat messages.
+pub mod chat;
+
+/// The chunk data model sent in Chunk Data packets.
+pub mod chunk;
+
 /// The packets of the minecraft protocol.
 pub mod packets;
 
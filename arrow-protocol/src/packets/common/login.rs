@@ -3,7 +3,7 @@ pub mod serverbound {
     use serde::{Deserialize, Serialize};
 
     use crate::{
-        packets::{error::PacketError, Packet},
+        packets::{error::PacketError, types::LengthPrefixedVec, Packet},
         serde::ser::Serializer,
     };
 
@@ -48,4 +48,204 @@ pub mod serverbound {
             Self::id(protocol_version)
         }
     }
+
+    /// The [Encryption Response](https://wiki.vg/Protocol#Encryption_Response) packet.
+    ///
+    /// # Fields
+    /// `shared_secret` is the shared secret encrypted with the public key of the server.
+    /// `verify_token` is the verify token encrypted with the same public key.
+    #[derive(Serialize, Deserialize)]
+    pub struct EncryptionResponse<'a> {
+        /// The encrypted shared secret.
+        #[serde(borrow)]
+        pub shared_secret: LengthPrefixedVec<'a, u8>,
+        /// The encrypted verify token.
+        #[serde(borrow)]
+        pub verify_token: LengthPrefixedVec<'a, u8>,
+    }
+
+    impl<'a> EncryptionResponse<'a> {
+        /// Creates a new Encryption Response packet.
+        pub fn new(shared_secret: Vec<u8>, verify_token: Vec<u8>) -> Self {
+            Self {
+                shared_secret: LengthPrefixedVec::new(shared_secret),
+                verify_token: LengthPrefixedVec::new(verify_token),
+            }
+        }
+    }
+
+    impl<'a> Packet for EncryptionResponse<'a> {
+        fn id(version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if (385..391).contains(&version) {
+                0x02
+            } else {
+                0x01
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
+
+/// The clientbound login packets.
+pub mod clientbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        packets::{error::PacketError, types::LengthPrefixedVec, Packet},
+        serde::{ser::Serializer, varint::VarInt},
+    };
+
+    /// The [Encryption Request](https://wiki.vg/Protocol#Encryption_Request) packet.
+    ///
+    /// # Fields
+    /// `server_id` is empty for vanilla servers.
+    /// `public_key` is the DER encoded public key of the server.
+    /// `verify_token` is a random sequence of bytes the client has to send back encrypted.
+    #[derive(Serialize, Deserialize)]
+    pub struct EncryptionRequest<'a> {
+        /// The server id, empty for vanilla servers.
+        pub server_id: String,
+        /// The DER encoded public key of the server.
+        #[serde(borrow)]
+        pub public_key: LengthPrefixedVec<'a, u8>,
+        /// The random verify token.
+        #[serde(borrow)]
+        pub verify_token: LengthPrefixedVec<'a, u8>,
+    }
+
+    impl<'a> EncryptionRequest<'a> {
+        /// Creates a new Encryption Request packet.
+        pub fn new(server_id: String, public_key: Vec<u8>, verify_token: Vec<u8>) -> Self {
+            Self {
+                server_id,
+                public_key: LengthPrefixedVec::new(public_key),
+                verify_token: LengthPrefixedVec::new(verify_token),
+            }
+        }
+    }
+
+    impl<'a> Packet for EncryptionRequest<'a> {
+        fn id(version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if (385..391).contains(&version) {
+                0x02
+            } else {
+                0x01
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Set Compression](https://wiki.vg/Protocol#Set_Compression) packet.
+    ///
+    /// # Fields
+    /// `threshold` is the minimum size of a packet before it gets compressed. A negative value
+    /// disables compression.
+    #[derive(Serialize, Deserialize)]
+    pub struct SetCompression {
+        /// The maximum size of a packet before it is compressed.
+        pub threshold: VarInt,
+    }
+
+    impl SetCompression {
+        /// Creates a new Set Compression packet from the threshold.
+        pub fn new(threshold: VarInt) -> Self {
+            Self { threshold }
+        }
+    }
+
+    impl Packet for SetCompression {
+        fn id(version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if (385..391).contains(&version) {
+                0x04
+            } else {
+                0x03
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Disconnect](https://wiki.vg/Protocol#Disconnect_.28login.29) packet of the login
+    /// state.
+    ///
+    /// # Fields
+    /// `reason` is the reason shown to the player as a JSON chat component.
+    #[derive(Serialize, Deserialize)]
+    pub struct Disconnect {
+        /// The reason as a JSON chat component.
+        pub reason: String,
+    }
+
+    impl Disconnect {
+        /// Creates a new Disconnect packet from the reason.
+        pub fn new(reason: String) -> Self {
+            Self { reason }
+        }
+    }
+
+    impl Packet for Disconnect {
+        fn id(version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if (385..391).contains(&version) {
+                0x01
+            } else {
+                0x00
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
 }
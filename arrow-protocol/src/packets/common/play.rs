@@ -39,8 +39,641 @@ pub mod clientbound {
                 0x3A
             } else if version >= 318 {
                 0x39
-            } else {
+            } else if version >= 107 {
                 0x37
+            } else {
+                0x09
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Disconnect](https://wiki.vg/Protocol#Disconnect_.28play.29) packet of the play state.
+    #[derive(Serialize, Deserialize)]
+    pub struct Disconnect {
+        /// The reason shown to the player as a JSON chat component.
+        pub reason: String,
+    }
+
+    impl Disconnect {
+        /// create a new [Disconnect] packet
+        pub fn new(reason: String) -> Self {
+            Self { reason }
+        }
+    }
+
+    impl Packet for Disconnect {
+        fn id(version: i32) -> i32 {
+            if version >= 751 {
+                0x19
+            } else if version >= 721 {
+                0x1A
+            } else if version >= 550 {
+                0x1B
+            } else if version >= 477 {
+                0x1A
+            } else if version >= 393 {
+                0x1B
+            } else if version >= 107 {
+                0x1A
+            } else {
+                0x40
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Time Update](https://wiki.vg/Protocol#Time_Update) packet.
+    #[derive(Serialize, Deserialize)]
+    pub struct TimeUpdate {
+        /// The age of the world in ticks. It is not changed by server commands.
+        pub world_age: i64,
+        /// The time of day in ticks. The sun stands still if it is negative.
+        pub time_of_day: i64,
+    }
+
+    impl TimeUpdate {
+        /// create a new [TimeUpdate] packet
+        pub fn new(world_age: i64, time_of_day: i64) -> Self {
+            Self {
+                world_age,
+                time_of_day,
+            }
+        }
+    }
+
+    impl Packet for TimeUpdate {
+        fn id(version: i32) -> i32 {
+            if version >= 735 {
+                0x4E
+            } else if version >= 573 {
+                0x4F
+            } else if version >= 477 {
+                0x4E
+            } else if version >= 393 {
+                0x4A
+            } else if version >= 338 {
+                0x47
+            } else if version >= 335 {
+                0x46
+            } else if version >= 107 {
+                0x44
+            } else {
+                0x03
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
+
+/// All common serverbound `play` packets.
+pub mod serverbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        packets::{error::PacketError, types::RemainingBytes, Packet},
+        serde::{ser::Serializer, varint::VarInt},
+    };
+
+    /// The [Chat Message](https://wiki.vg/Protocol#Chat_Message_.28serverbound.29) packet.
+    #[derive(Serialize, Deserialize)]
+    pub struct ChatMessage {
+        /// The raw message. Commands start with a `/`.
+        pub message: String,
+    }
+
+    impl ChatMessage {
+        /// create a new [ChatMessage] packet
+        pub fn new(message: String) -> Self {
+            Self { message }
+        }
+    }
+
+    impl Packet for ChatMessage {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 477 {
+                0x03
+            } else if protocol_version >= 338 {
+                0x02
+            } else if protocol_version >= 335 {
+                0x03
+            } else if protocol_version >= 107 {
+                0x02
+            } else {
+                0x01
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Client Status](https://wiki.vg/Protocol#Client_Status) packet.
+    #[derive(Serialize, Deserialize)]
+    pub struct ClientStatus {
+        /// 0: perform respawn, 1: request stats.
+        pub action_id: VarInt,
+    }
+
+    impl ClientStatus {
+        /// create a new [ClientStatus] packet
+        pub fn new(action_id: VarInt) -> Self {
+            Self { action_id }
+        }
+    }
+
+    impl Packet for ClientStatus {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 477 {
+                0x04
+            } else if protocol_version >= 338 {
+                0x03
+            } else if protocol_version >= 335 {
+                0x04
+            } else if protocol_version >= 107 {
+                0x03
+            } else {
+                0x16
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Player Movement](https://wiki.vg/Protocol#Player_Movement) packet. Called Player before 1.14.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerMovement {
+        /// True if the client is on the ground.
+        pub on_ground: bool,
+    }
+
+    impl PlayerMovement {
+        /// create a new [PlayerMovement] packet
+        pub fn new(on_ground: bool) -> Self {
+            Self { on_ground }
+        }
+    }
+
+    impl Packet for PlayerMovement {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 735 {
+                0x15
+            } else if protocol_version >= 477 {
+                0x14
+            } else if protocol_version >= 393 {
+                0x0F
+            } else if protocol_version >= 338 {
+                0x0C
+            } else if protocol_version >= 335 {
+                0x0D
+            } else if protocol_version >= 107 {
+                0x0F
+            } else {
+                0x03
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Player Position](https://wiki.vg/Protocol#Player_Position) packet.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerPosition {
+        /// Absolute position.
+        pub x: f64,
+        /// Absolute feet position, normally head y - 1.62.
+        pub feet_y: f64,
+        /// Absolute position.
+        pub z: f64,
+        /// True if the client is on the ground.
+        pub on_ground: bool,
+    }
+
+    impl PlayerPosition {
+        /// create a new [PlayerPosition] packet
+        pub fn new(x: f64, feet_y: f64, z: f64, on_ground: bool) -> Self {
+            Self {
+                x,
+                feet_y,
+                z,
+                on_ground,
+            }
+        }
+    }
+
+    impl Packet for PlayerPosition {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 735 {
+                0x12
+            } else if protocol_version >= 477 {
+                0x11
+            } else if protocol_version >= 393 {
+                0x10
+            } else if protocol_version >= 338 {
+                0x0D
+            } else if protocol_version >= 335 {
+                0x0E
+            } else if protocol_version >= 107 {
+                0x0C
+            } else {
+                0x04
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Player Rotation](https://wiki.vg/Protocol#Player_Rotation) packet. Called Player Look before 1.14.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerRotation {
+        /// Absolute rotation on the x axis, in degrees.
+        pub yaw: f32,
+        /// Absolute rotation on the y axis, in degrees.
+        pub pitch: f32,
+        /// True if the client is on the ground.
+        pub on_ground: bool,
+    }
+
+    impl PlayerRotation {
+        /// create a new [PlayerRotation] packet
+        pub fn new(yaw: f32, pitch: f32, on_ground: bool) -> Self {
+            Self {
+                yaw,
+                pitch,
+                on_ground,
+            }
+        }
+    }
+
+    impl Packet for PlayerRotation {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 735 {
+                0x14
+            } else if protocol_version >= 477 {
+                0x13
+            } else if protocol_version >= 393 {
+                0x12
+            } else if protocol_version >= 338 {
+                0x0F
+            } else if protocol_version >= 335 {
+                0x10
+            } else if protocol_version >= 107 {
+                0x0E
+            } else {
+                0x05
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Player Position And Rotation](https://wiki.vg/Protocol#Player_Position_And_Rotation_.28serverbound.29) packet. Called Player Position And Look before 1.14.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerPositionAndRotation {
+        /// Absolute position.
+        pub x: f64,
+        /// Absolute feet position, normally head y - 1.62.
+        pub feet_y: f64,
+        /// Absolute position.
+        pub z: f64,
+        /// Absolute rotation on the x axis, in degrees.
+        pub yaw: f32,
+        /// Absolute rotation on the y axis, in degrees.
+        pub pitch: f32,
+        /// True if the client is on the ground.
+        pub on_ground: bool,
+    }
+
+    impl PlayerPositionAndRotation {
+        /// create a new [PlayerPositionAndRotation] packet
+        pub fn new(x: f64, feet_y: f64, z: f64, yaw: f32, pitch: f32, on_ground: bool) -> Self {
+            Self {
+                x,
+                feet_y,
+                z,
+                yaw,
+                pitch,
+                on_ground,
+            }
+        }
+    }
+
+    impl Packet for PlayerPositionAndRotation {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 735 {
+                0x13
+            } else if protocol_version >= 477 {
+                0x12
+            } else if protocol_version >= 393 {
+                0x11
+            } else if protocol_version >= 338 {
+                0x0E
+            } else if protocol_version >= 335 {
+                0x0F
+            } else if protocol_version >= 107 {
+                0x0D
+            } else {
+                0x06
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Held Item Change](https://wiki.vg/Protocol#Held_Item_Change_.28serverbound.29) packet.
+    #[derive(Serialize, Deserialize)]
+    pub struct HeldItemChange {
+        /// The slot which the player has selected (0–8).
+        pub slot: i16,
+    }
+
+    impl HeldItemChange {
+        /// create a new [HeldItemChange] packet
+        pub fn new(slot: i16) -> Self {
+            Self { slot }
+        }
+    }
+
+    impl Packet for HeldItemChange {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 751 {
+                0x25
+            } else if protocol_version >= 735 {
+                0x24
+            } else if protocol_version >= 477 {
+                0x23
+            } else if protocol_version >= 393 {
+                0x21
+            } else if protocol_version >= 335 {
+                0x1A
+            } else if protocol_version >= 107 {
+                0x17
+            } else {
+                0x09
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Plugin Message](https://wiki.vg/Protocol#Plugin_Message_.28serverbound.29) packet.
+    #[derive(Serialize, Deserialize)]
+    pub struct PluginMessage {
+        /// Name of the plugin channel used to send the data.
+        pub channel: String,
+        /// Any data, depending on the channel.
+        pub data: RemainingBytes,
+    }
+
+    impl PluginMessage {
+        /// create a new [PluginMessage] packet
+        pub fn new(channel: String, data: RemainingBytes) -> Self {
+            Self { channel, data }
+        }
+    }
+
+    impl Packet for PluginMessage {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 477 {
+                0x0B
+            } else if protocol_version >= 393 {
+                0x0A
+            } else if protocol_version >= 338 {
+                0x09
+            } else if protocol_version >= 335 {
+                0x0A
+            } else if protocol_version >= 107 {
+                0x09
+            } else {
+                0x17
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Entity Action](https://wiki.vg/Protocol#Entity_Action) packet.
+    #[derive(Serialize, Deserialize)]
+    pub struct EntityAction {
+        /// The player's Entity ID.
+        pub entity_id: VarInt,
+        /// The action. The meaning of the ids changed with protocol version 107.
+        pub action_id: VarInt,
+        /// Only used by the horse jump actions, in the range 0 to 100.
+        pub jump_boost: VarInt,
+    }
+
+    impl EntityAction {
+        /// create a new [EntityAction] packet
+        pub fn new(entity_id: VarInt, action_id: VarInt, jump_boost: VarInt) -> Self {
+            Self {
+                entity_id,
+                action_id,
+                jump_boost,
+            }
+        }
+    }
+
+    impl Packet for EntityAction {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 735 {
+                0x1C
+            } else if protocol_version >= 477 {
+                0x1B
+            } else if protocol_version >= 393 {
+                0x19
+            } else if protocol_version >= 335 {
+                0x15
+            } else if protocol_version >= 107 {
+                0x14
+            } else {
+                0x0B
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Close Window](https://wiki.vg/Protocol#Close_Window_.28serverbound.29) packet.
+    #[derive(Serialize, Deserialize)]
+    pub struct CloseWindow {
+        /// The id of the window that was closed. 0 for the player inventory.
+        pub window_id: u8,
+    }
+
+    impl CloseWindow {
+        /// create a new [CloseWindow] packet
+        pub fn new(window_id: u8) -> Self {
+            Self { window_id }
+        }
+    }
+
+    impl Packet for CloseWindow {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 477 {
+                0x0A
+            } else if protocol_version >= 393 {
+                0x09
+            } else if protocol_version >= 338 {
+                0x08
+            } else if protocol_version >= 335 {
+                0x09
+            } else if protocol_version >= 107 {
+                0x08
+            } else {
+                0x0D
             }
         }
 
@@ -2,6 +2,8 @@ use uuid::Uuid;
 
 use serde::{Deserialize, Serialize};
 
+use crate::chat::ChatComponent;
+
 /// struct of [ResponseData] in [clientbound::Response]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ResponseData {
@@ -9,10 +11,11 @@ pub struct ResponseData {
     pub version: VersionData,
     /// [PlayerData]
     pub players: PlayerData,
-    /// [Description]
-    pub description: DescriptionData,
+    /// Also known as the MOTD (Message of the day) of the server
+    pub description: ChatComponent,
     /// favicon: PNG in base64, prepend: data:image/png;base64,
-    pub favicon: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<String>,
 }
 
 /// struct of [VersionData] in [ResponseData]
@@ -32,18 +35,10 @@ pub struct PlayerData {
     /// number of online players
     pub online: i32,
     /// array of [SinglePlayerData]
+    #[serde(default)]
     pub sample: Vec<SinglePlayerData>,
 }
 
-/// struct for [DescriptionData] in [ResponseData]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
-pub struct DescriptionData {
-    /// Also known as the MOTD (Message of the day) of the serve
-    ///
-    /// No color support currently
-    pub text: String,
-}
-
 /// struct for [SinglePlayerData] in [PlayerData]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct SinglePlayerData {
@@ -153,6 +148,11 @@ pub mod clientbound {
             };
             Ok(Self { json_response })
         }
+
+        /// parses the json response of this [Response] packet
+        pub fn data(&self) -> Result<ResponseData, PacketError> {
+            serde_json::from_str(&self.json_response).map_err(|_| PacketError::ParsingJsonFailed)
+        }
     }
 
     impl Packet for Response {
@@ -13,6 +13,10 @@ pub enum PacketError {
     InvalidPacketId(i32, State),
     /// Returned when converting to a json string fails
     BuildingJsonFailed,
+    /// Returned when parsing a json string fails
+    ParsingJsonFailed,
+    /// Returned when a packet doesn't exist in the protocol version.
+    UnsupportedPacket(&'static str, i32),
 }
 
 impl From<SerdeError> for PacketError {
@@ -33,6 +37,16 @@ impl Display for PacketError {
             Self::BuildingJsonFailed => {
                 write!(f, "Building json string failed")
             }
+            Self::ParsingJsonFailed => {
+                write!(f, "Parsing json string failed")
+            }
+            Self::UnsupportedPacket(name, protocol_version) => {
+                write!(
+                    f,
+                    "Packet {} doesn't exist in protocol version {}",
+                    name, protocol_version
+                )
+            }
         }
     }
 }
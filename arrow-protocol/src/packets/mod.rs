@@ -7,7 +7,10 @@ pub mod types;
 /// All version specific packets and types.
 pub mod version_specific;
 
-use std::fmt::Display;
+#[cfg(test)]
+mod tests;
+
+use std::{convert::TryFrom, fmt::Display, io::Cursor, sync::Arc};
 
 use serde::Deserialize;
 use uuid::Uuid;
@@ -15,13 +18,22 @@ use uuid::Uuid;
 use self::{
     common::*,
     error::PacketError,
-    types::{Difficulty, Gamemode, LevelType, Recipe},
+    types::{
+        ChatPosition, CommandNode, Difficulty, Gamemode, LevelType, Position, Recipe,
+        RemainingBytes, Slot,
+    },
     version_specific::types::{
         v47::Dimension,
+        v477::Heightmaps,
         v754::{DimensionCodec, DimensionType},
     },
 };
-use crate::serde::{de::Deserializer, varint::VarInt};
+use crate::{
+    chat::ChatComponent,
+    chunk::{self, Chunk, NibbleArray, LIGHT_SECTION_COUNT},
+    serde::{de::Deserializer, varint::VarInt},
+};
+use version_specific::play::{v107, v315, v339, v351, v393, v402, v47, v477};
 
 /// A trait giving functions to get the packet id and serialize it.
 pub trait Packet {
@@ -38,6 +50,7 @@ pub trait Packet {
 }
 
 /// A multi-version representation for packets.
+#[derive(Debug, Clone, PartialEq)]
 pub enum PacketKind {
     /// The [Handshake](https://wiki.vg/Protocol#Handshake) packet.
     Handshake {
@@ -54,6 +67,28 @@ pub enum PacketKind {
     LoginStart(String),
     /// The [Login Success](https://wiki.vg/Protocol#Login_Success) packet.
     LoginSuccess(Uuid, String),
+    /// The [Encryption Request](https://wiki.vg/Protocol#Encryption_Request) packet.
+    EncryptionRequest {
+        /// The server id, empty for vanilla servers.
+        server_id: String,
+        /// The DER encoded public key of the server.
+        public_key: Vec<u8>,
+        /// The random verify token.
+        verify_token: Vec<u8>,
+    },
+    /// The [Encryption Response](https://wiki.vg/Protocol#Encryption_Response) packet.
+    EncryptionResponse {
+        /// The shared secret, encrypted with the public key of the server.
+        shared_secret: Vec<u8>,
+        /// The verify token, encrypted with the public key of the server.
+        verify_token: Vec<u8>,
+    },
+    /// The [Set Compression](https://wiki.vg/Protocol#Set_Compression) packet. Contains the
+    /// threshold, a negative value disables compression.
+    SetCompression(i32),
+    /// The [Disconnect](https://wiki.vg/Protocol#Disconnect_.28login.29) packet of the login
+    /// state. Contains the reason.
+    LoginDisconnect(ChatComponent),
     /// The [Request](https://wiki.vg/Protocol#Status) packet
     StatusRequest,
     /// The [Response](https://wiki.vg/Protocol#Status) packet
@@ -107,9 +142,258 @@ pub enum PacketKind {
     HeldItemChange(i8),
     /// The [ServerDifficulty](https://wiki.vg/Protocol#Server_Difficulty) packet.
     ServerDifficulty(Difficulty, bool),
+    /// The clientbound [Keep Alive](https://wiki.vg/Protocol#Keep_Alive_.28clientbound.29) packet.
+    /// The id is a VarInt before protocol version 339.
+    ClientboundKeepAlive(i64),
+    /// The [Disconnect](https://wiki.vg/Protocol#Disconnect_.28play.29) packet of the play state.
+    /// Contains the reason.
+    PlayDisconnect(ChatComponent),
+    /// The clientbound [Chat Message](https://wiki.vg/Protocol#Chat_Message_.28clientbound.29)
+    /// packet.
+    ClientboundChatMessage {
+        /// The message to display.
+        message: ChatComponent,
+        /// Where the message is displayed.
+        position: ChatPosition,
+        /// The uuid of the sender, nil for system messages. Only sent to protocol version 735 and
+        /// above.
+        sender: Uuid,
+    },
+    /// The [Declare Commands](https://wiki.vg/Protocol#Declare_Commands) packet. Only sent to
+    /// protocol version 393 and above.
+    DeclareCommands {
+        /// All nodes of the command tree.
+        nodes: Vec<CommandNode>,
+        /// The index of the root node in `nodes`.
+        root_index: i32,
+    },
+    /// The clientbound [Tab-Complete](https://wiki.vg/Protocol#Tab-Complete_.28clientbound.29)
+    /// packet. Only the matches are sent before protocol version 393, those clients replace the
+    /// last word with them.
+    ClientboundTabComplete {
+        /// The id of the request this packet answers.
+        transaction_id: i32,
+        /// The start of the text to replace.
+        start: i32,
+        /// The length of the text to replace.
+        length: i32,
+        /// The eligible values to insert.
+        matches: Vec<String>,
+    },
+    /// The clientbound
+    /// [Player Position And Look](https://wiki.vg/Protocol#Player_Position_And_Look_.28clientbound.29)
+    /// packet teleporting the player.
+    PlayerPositionAndLook {
+        /// The x coordinate.
+        x: f64,
+        /// The y coordinate of the feet.
+        y: f64,
+        /// The z coordinate.
+        z: f64,
+        /// The yaw in degrees.
+        yaw: f32,
+        /// The pitch in degrees.
+        pitch: f32,
+        /// Bit field of the values that are relative to the current ones.
+        flags: i8,
+        /// The id the client confirms the teleport with. Only sent to protocol version 107 and
+        /// above.
+        teleport_id: i32,
+    },
+    /// The [Time Update](https://wiki.vg/Protocol#Time_Update) packet.
+    TimeUpdate {
+        /// The age of the world in ticks.
+        world_age: i64,
+        /// The time of day in ticks. A negative time stops the daylight cycle on the client.
+        time_of_day: i64,
+    },
+    /// The [Chunk Data](https://wiki.vg/Protocol#Chunk_Data) packet. Always contains the whole
+    /// chunk.
+    ChunkData {
+        /// The x coordinate of the chunk.
+        x: i32,
+        /// The z coordinate of the chunk.
+        z: i32,
+        /// The chunk. Shared because the same chunk is usually sent to many players.
+        chunk: Arc<Chunk>,
+    },
+    /// The [Unload Chunk](https://wiki.vg/Protocol#Unload_Chunk) packet. Sent as an empty
+    /// [`ChunkData`](PacketKind::ChunkData) packet before protocol version 107.
+    UnloadChunk {
+        /// The x coordinate of the chunk.
+        x: i32,
+        /// The z coordinate of the chunk.
+        z: i32,
+    },
+    /// The [Update Light](https://wiki.vg/Protocol#Update_Light) packet. Only sent to protocol
+    /// version 477 and above, before that the light is part of the chunk data.
+    UpdateLight {
+        /// The x coordinate of the chunk.
+        x: i32,
+        /// The z coordinate of the chunk.
+        z: i32,
+        /// The sky light of the sections, starting below the chunk. Sections that are `None`
+        /// keep their light.
+        sky_light: Vec<Option<NibbleArray>>,
+        /// The block light of the sections, starting below the chunk. Sections that are `None`
+        /// keep their light.
+        block_light: Vec<Option<NibbleArray>>,
+    },
+    /// The [Update View Position](https://wiki.vg/Protocol#Update_View_Position) packet, telling
+    /// the client which chunk the player is in. Only sent to protocol version 477 and above.
+    UpdateViewPosition {
+        /// The x coordinate of the chunk.
+        x: i32,
+        /// The z coordinate of the chunk.
+        z: i32,
+    },
+    /// The [Teleport Confirm](https://wiki.vg/Protocol#Teleport_Confirm) packet. Only sent by
+    /// protocol version 107 and above.
+    TeleportConfirm(i32),
+    /// The [Client Status](https://wiki.vg/Protocol#Client_Status) packet. Contains the action id.
+    ClientStatus(i32),
+    /// The [Client Settings](https://wiki.vg/Protocol#Client_Settings) packet.
+    ClientSettings {
+        /// The locale of the client, e.g. en_GB.
+        locale: String,
+        /// Client-side render distance, in chunks.
+        view_distance: i8,
+        /// 0: enabled, 1: commands only, 2: hidden.
+        chat_mode: i32,
+        /// Whether the client wants colored chat.
+        chat_colors: bool,
+        /// Bit mask of the displayed skin parts.
+        displayed_skin_parts: u8,
+        /// 0: Left, 1: Right. Always 1 before protocol version 107.
+        main_hand: i32,
+    },
+    /// The serverbound [Chat Message](https://wiki.vg/Protocol#Chat_Message_.28serverbound.29)
+    /// packet.
+    ServerboundChatMessage(String),
+    /// The serverbound [Tab-Complete](https://wiki.vg/Protocol#Tab-Complete_.28serverbound.29)
+    /// packet. The transaction id is always 0 before protocol version 393.
+    ServerboundTabComplete {
+        /// The id the server has to answer with.
+        transaction_id: i32,
+        /// All text behind the cursor.
+        text: String,
+    },
+    /// The serverbound [Keep Alive](https://wiki.vg/Protocol#Keep_Alive_.28serverbound.29) packet.
+    /// The id is a VarInt before protocol version 339.
+    ServerboundKeepAlive(i64),
+    /// The [Player Movement](https://wiki.vg/Protocol#Player_Movement) packet. Contains
+    /// `on_ground`.
+    PlayerMovement(bool),
+    /// The [Player Position](https://wiki.vg/Protocol#Player_Position) packet.
+    PlayerPosition {
+        /// Absolute position.
+        x: f64,
+        /// Absolute feet position, normally head y - 1.62.
+        feet_y: f64,
+        /// Absolute position.
+        z: f64,
+        /// True if the client is on the ground.
+        on_ground: bool,
+    },
+    /// The [Player Rotation](https://wiki.vg/Protocol#Player_Rotation) packet.
+    PlayerRotation {
+        /// Absolute rotation on the x axis, in degrees.
+        yaw: f32,
+        /// Absolute rotation on the y axis, in degrees.
+        pitch: f32,
+        /// True if the client is on the ground.
+        on_ground: bool,
+    },
+    /// The [Player Position And Rotation](https://wiki.vg/Protocol#Player_Position_And_Rotation_.28serverbound.29)
+    /// packet.
+    PlayerPositionAndRotation {
+        /// Absolute position.
+        x: f64,
+        /// Absolute feet position, normally head y - 1.62.
+        feet_y: f64,
+        /// Absolute position.
+        z: f64,
+        /// Absolute rotation on the x axis, in degrees.
+        yaw: f32,
+        /// Absolute rotation on the y axis, in degrees.
+        pitch: f32,
+        /// True if the client is on the ground.
+        on_ground: bool,
+    },
+    /// The serverbound [Held Item Change](https://wiki.vg/Protocol#Held_Item_Change_.28serverbound.29)
+    /// packet.
+    ServerboundHeldItemChange(i16),
+    /// The serverbound [Plugin Message](https://wiki.vg/Protocol#Plugin_Message_.28serverbound.29)
+    /// packet.
+    ServerboundPluginMessage {
+        /// Name of the plugin channel used to send the data.
+        channel: String,
+        /// Any data, depending on the channel.
+        data: Vec<u8>,
+    },
+    /// The [Animation](https://wiki.vg/Protocol#Animation_.28serverbound.29) packet. Contains the
+    /// hand, which is always 0 before protocol version 107.
+    Animation(i32),
+    /// The [Player Digging](https://wiki.vg/Protocol#Player_Digging) packet.
+    PlayerDigging {
+        /// The action the player is taking against the block.
+        status: i32,
+        /// Block position.
+        location: Position,
+        /// The face being hit.
+        face: i8,
+    },
+    /// The [Player Block Placement](https://wiki.vg/Protocol#Player_Block_Placement) packet.
+    PlayerBlockPlacement {
+        /// The hand from which the block is placed. Always 0 before protocol version 107.
+        hand: i32,
+        /// Block position.
+        location: Position,
+        /// The face on which the block is placed.
+        face: i32,
+        /// The position of the crosshair on the block, from 0 to 1.
+        cursor_x: f32,
+        /// The position of the crosshair on the block, from 0 to 1.
+        cursor_y: f32,
+        /// The position of the crosshair on the block, from 0 to 1.
+        cursor_z: f32,
+        /// True when the player's head is inside of a block. Always false before protocol version
+        /// 477.
+        inside_block: bool,
+    },
+    /// The [Use Item](https://wiki.vg/Protocol#Use_Item) packet. Contains the hand. Only sent by
+    /// protocol version 107 and above.
+    UseItem(i32),
+    /// The [Entity Action](https://wiki.vg/Protocol#Entity_Action) packet.
+    EntityAction {
+        /// The player's Entity ID.
+        entity_id: i32,
+        /// The action. The meaning of the ids changed with protocol version 107.
+        action_id: i32,
+        /// Only used by the horse jump actions, in the range 0 to 100.
+        jump_boost: i32,
+    },
+    /// The [Click Window](https://wiki.vg/Protocol#Click_Window) packet.
+    ClickWindow {
+        /// The id of the window which was clicked. 0 for the player inventory.
+        window_id: u8,
+        /// The clicked slot number.
+        slot: i16,
+        /// The button used in the click.
+        button: i8,
+        /// A unique number for the action, used for transaction handling.
+        action_number: i16,
+        /// Inventory operation mode.
+        mode: i32,
+        /// The clicked slot.
+        clicked_item: Slot,
+    },
+    /// The serverbound [Close Window](https://wiki.vg/Protocol#Close_Window_.28serverbound.29)
+    /// packet. Contains the window id.
+    ServerboundCloseWindow(u8),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// The states of the protocol.
 pub enum State {
     /// The first state, reached when connecting to the server.
@@ -143,6 +427,29 @@ impl PacketKind {
                 next_state,
             ))),
             LoginStart(name) => Ok(Box::new(common::login::serverbound::LoginStart::new(name))),
+            EncryptionRequest {
+                server_id,
+                public_key,
+                verify_token,
+            } => Ok(Box::new(
+                common::login::clientbound::EncryptionRequest::new(
+                    server_id,
+                    public_key,
+                    verify_token,
+                ),
+            )),
+            EncryptionResponse {
+                shared_secret,
+                verify_token,
+            } => Ok(Box::new(
+                common::login::serverbound::EncryptionResponse::new(shared_secret, verify_token),
+            )),
+            SetCompression(threshold) => Ok(Box::new(
+                common::login::clientbound::SetCompression::new(VarInt(threshold)),
+            )),
+            LoginDisconnect(reason) => Ok(Box::new(common::login::clientbound::Disconnect::new(
+                reason.to_json(protocol_version)?,
+            ))),
             LoginSuccess(uuid, name) => {
                 if protocol_version >= 707 {
                     Ok(Box::new(
@@ -158,12 +465,15 @@ impl PacketKind {
                 }
             }
             StatusRequest => Ok(Box::new(common::status::serverbound::Request)),
-            StatusResponse(json_response) => Ok(Box::new(
-                match common::status::clientbound::Response::new(json_response) {
-                    Ok(s) => s,
-                    Err(e) => return Err(e),
-                },
-            )),
+            StatusResponse(mut json_response) => {
+                json_response.description = json_response
+                    .description
+                    .for_protocol_version(protocol_version);
+
+                Ok(Box::new(common::status::clientbound::Response::new(
+                    json_response,
+                )?))
+            }
             StatusPing(payload) => Ok(Box::new(common::status::serverbound::Ping::new(payload))),
             StatusPong(payload) => Ok(Box::new(common::status::clientbound::Pong::new(payload))),
             JoinGame {
@@ -311,6 +621,480 @@ impl PacketKind {
                     ))
                 }
             }
+            ClientboundKeepAlive(keep_alive_id) => {
+                if protocol_version >= 339 {
+                    Ok(Box::new(v339::clientbound::KeepAlive::new(keep_alive_id)))
+                } else {
+                    Ok(Box::new(v47::clientbound::KeepAlive::new(VarInt(
+                        keep_alive_id as i32,
+                    ))))
+                }
+            }
+            PlayDisconnect(reason) => Ok(Box::new(common::play::clientbound::Disconnect::new(
+                reason.to_json(protocol_version)?,
+            ))),
+            ClientboundChatMessage {
+                message,
+                position,
+                sender,
+            } => {
+                let message = message.to_json(protocol_version)?;
+
+                if protocol_version >= 735 {
+                    Ok(Box::new(
+                        version_specific::play::v735::clientbound::ChatMessage::new(
+                            message,
+                            position as i8,
+                            sender,
+                        ),
+                    ))
+                } else {
+                    Ok(Box::new(v47::clientbound::ChatMessage::new(
+                        message,
+                        position as i8,
+                    )))
+                }
+            }
+            DeclareCommands { nodes, root_index } => {
+                Ok(Box::new(v393::clientbound::DeclareCommands::new(
+                    nodes
+                        .into_iter()
+                        .map(version_specific::types::v393::CommandNode)
+                        .collect(),
+                    VarInt(root_index),
+                )))
+            }
+            ClientboundTabComplete {
+                transaction_id,
+                start,
+                length,
+                matches,
+            } => {
+                if protocol_version >= 393 {
+                    Ok(Box::new(v393::clientbound::TabComplete::new(
+                        VarInt(transaction_id),
+                        VarInt(start),
+                        VarInt(length),
+                        matches
+                            .into_iter()
+                            .map(version_specific::types::v393::TabCompleteMatch)
+                            .collect(),
+                    )))
+                } else {
+                    Ok(Box::new(v47::clientbound::TabComplete::new(matches)))
+                }
+            }
+            PlayerPositionAndLook {
+                x,
+                y,
+                z,
+                yaw,
+                pitch,
+                flags,
+                teleport_id,
+            } => {
+                if protocol_version >= 107 {
+                    Ok(Box::new(v107::clientbound::PlayerPositionAndLook::new(
+                        x,
+                        y,
+                        z,
+                        yaw,
+                        pitch,
+                        flags,
+                        VarInt(teleport_id),
+                    )))
+                } else {
+                    Ok(Box::new(v47::clientbound::PlayerPositionAndLook::new(
+                        x, y, z, yaw, pitch, flags,
+                    )))
+                }
+            }
+            TimeUpdate {
+                world_age,
+                time_of_day,
+            } => Ok(Box::new(play::clientbound::TimeUpdate::new(
+                world_age,
+                time_of_day,
+            ))),
+            ChunkData { x, z, chunk } => {
+                let (mask, data) = chunk.encode_data(protocol_version);
+                let heightmaps = || Heightmaps {
+                    motion_blocking: chunk.encode_heightmap(protocol_version),
+                };
+
+                if protocol_version >= 751 {
+                    Ok(Box::new(
+                        version_specific::play::v751::clientbound::ChunkData::new(
+                            x,
+                            z,
+                            VarInt(mask),
+                            heightmaps(),
+                            chunk.biomes().to_vec(),
+                            data,
+                            vec![],
+                        ),
+                    ))
+                } else if protocol_version >= 735 {
+                    Ok(Box::new(
+                        version_specific::play::v735::clientbound::ChunkData::new(
+                            x,
+                            z,
+                            VarInt(mask),
+                            heightmaps(),
+                            chunk.biomes().to_vec(),
+                            data,
+                            vec![],
+                        ),
+                    ))
+                } else if protocol_version >= 573 {
+                    Ok(Box::new(
+                        version_specific::play::v573::clientbound::ChunkData::new(
+                            x,
+                            z,
+                            VarInt(mask),
+                            heightmaps(),
+                            chunk.biomes().to_vec(),
+                            data,
+                            vec![],
+                        ),
+                    ))
+                } else if protocol_version >= 477 {
+                    Ok(Box::new(v477::clientbound::ChunkData::new(
+                        x,
+                        z,
+                        true,
+                        VarInt(mask),
+                        heightmaps(),
+                        data,
+                        vec![],
+                    )))
+                } else if protocol_version >= 110 {
+                    Ok(Box::new(
+                        version_specific::play::v110::clientbound::ChunkData::new(
+                            x,
+                            z,
+                            true,
+                            VarInt(mask),
+                            data,
+                            vec![],
+                        ),
+                    ))
+                } else if protocol_version >= 107 {
+                    Ok(Box::new(v107::clientbound::ChunkData::new(
+                        x,
+                        z,
+                        true,
+                        VarInt(mask),
+                        data,
+                    )))
+                } else {
+                    Ok(Box::new(v47::clientbound::ChunkData::new(
+                        x,
+                        z,
+                        true,
+                        mask as u16,
+                        data,
+                    )))
+                }
+            }
+            UnloadChunk { x, z } => {
+                if protocol_version >= 107 {
+                    Ok(Box::new(v107::clientbound::UnloadChunk::new(x, z)))
+                } else {
+                    Ok(Box::new(v47::clientbound::ChunkData::new(
+                        x,
+                        z,
+                        true,
+                        0,
+                        vec![],
+                    )))
+                }
+            }
+            UpdateLight {
+                x,
+                z,
+                sky_light,
+                block_light,
+            } => {
+                let mut light_arrays = vec![];
+
+                chunk::write_light_arrays(&sky_light, &mut light_arrays);
+                chunk::write_light_arrays(&block_light, &mut light_arrays);
+
+                let sky_light_mask = VarInt(chunk::light_mask(&sky_light));
+                let block_light_mask = VarInt(chunk::light_mask(&block_light));
+
+                match protocol_version {
+                    477..=734 => Ok(Box::new(v477::clientbound::UpdateLight::new(
+                        VarInt(x),
+                        VarInt(z),
+                        sky_light_mask,
+                        block_light_mask,
+                        VarInt(0),
+                        VarInt(0),
+                        light_arrays,
+                    ))),
+                    735..=754 => Ok(Box::new(
+                        version_specific::play::v735::clientbound::UpdateLight::new(
+                            VarInt(x),
+                            VarInt(z),
+                            sky_light_mask,
+                            block_light_mask,
+                            VarInt(0),
+                            VarInt(0),
+                            light_arrays,
+                        ),
+                    )),
+                    _ => Err(PacketError::UnsupportedPacket(
+                        "UpdateLight",
+                        protocol_version,
+                    )),
+                }
+            }
+            UpdateViewPosition { x, z } => {
+                if protocol_version < 477 {
+                    return Err(PacketError::UnsupportedPacket(
+                        "UpdateViewPosition",
+                        protocol_version,
+                    ));
+                }
+
+                Ok(Box::new(v477::clientbound::UpdateViewPosition::new(
+                    VarInt(x),
+                    VarInt(z),
+                )))
+            }
+            TeleportConfirm(teleport_id) => Ok(Box::new(v107::serverbound::TeleportConfirm::new(
+                VarInt(teleport_id),
+            ))),
+            ClientStatus(action_id) => Ok(Box::new(play::serverbound::ClientStatus::new(VarInt(
+                action_id,
+            )))),
+            ClientSettings {
+                locale,
+                view_distance,
+                chat_mode,
+                chat_colors,
+                displayed_skin_parts,
+                main_hand,
+            } => {
+                if protocol_version >= 107 {
+                    Ok(Box::new(v107::serverbound::ClientSettings::new(
+                        locale,
+                        view_distance,
+                        VarInt(chat_mode),
+                        chat_colors,
+                        displayed_skin_parts,
+                        VarInt(main_hand),
+                    )))
+                } else {
+                    Ok(Box::new(v47::serverbound::ClientSettings::new(
+                        locale,
+                        view_distance,
+                        chat_mode as i8,
+                        chat_colors,
+                        displayed_skin_parts,
+                    )))
+                }
+            }
+            ServerboundChatMessage(message) => {
+                Ok(Box::new(play::serverbound::ChatMessage::new(message)))
+            }
+            ServerboundTabComplete {
+                transaction_id,
+                text,
+            } => {
+                if protocol_version >= 393 {
+                    Ok(Box::new(v393::serverbound::TabComplete::new(
+                        VarInt(transaction_id),
+                        text,
+                    )))
+                } else if protocol_version >= 107 {
+                    Ok(Box::new(v107::serverbound::TabComplete::new(text)))
+                } else {
+                    Ok(Box::new(v47::serverbound::TabComplete::new(text)))
+                }
+            }
+            ServerboundKeepAlive(keep_alive_id) => {
+                if protocol_version >= 339 {
+                    Ok(Box::new(v339::serverbound::KeepAlive::new(keep_alive_id)))
+                } else {
+                    Ok(Box::new(v47::serverbound::KeepAlive::new(VarInt(
+                        keep_alive_id as i32,
+                    ))))
+                }
+            }
+            PlayerMovement(on_ground) => {
+                Ok(Box::new(play::serverbound::PlayerMovement::new(on_ground)))
+            }
+            PlayerPosition {
+                x,
+                feet_y,
+                z,
+                on_ground,
+            } => Ok(Box::new(play::serverbound::PlayerPosition::new(
+                x, feet_y, z, on_ground,
+            ))),
+            PlayerRotation {
+                yaw,
+                pitch,
+                on_ground,
+            } => Ok(Box::new(play::serverbound::PlayerRotation::new(
+                yaw, pitch, on_ground,
+            ))),
+            PlayerPositionAndRotation {
+                x,
+                feet_y,
+                z,
+                yaw,
+                pitch,
+                on_ground,
+            } => Ok(Box::new(play::serverbound::PlayerPositionAndRotation::new(
+                x, feet_y, z, yaw, pitch, on_ground,
+            ))),
+            ServerboundHeldItemChange(slot) => {
+                Ok(Box::new(play::serverbound::HeldItemChange::new(slot)))
+            }
+            ServerboundPluginMessage { channel, data } => Ok(Box::new(
+                play::serverbound::PluginMessage::new(channel, RemainingBytes(data)),
+            )),
+            Animation(hand) => {
+                if protocol_version >= 107 {
+                    Ok(Box::new(v107::serverbound::Animation::new(VarInt(hand))))
+                } else {
+                    Ok(Box::new(v47::serverbound::Animation))
+                }
+            }
+            PlayerDigging {
+                status,
+                location,
+                face,
+            } => {
+                let location = location.to_packed(protocol_version);
+
+                if protocol_version >= 107 {
+                    Ok(Box::new(v107::serverbound::PlayerDigging::new(
+                        VarInt(status),
+                        location,
+                        face,
+                    )))
+                } else {
+                    Ok(Box::new(v47::serverbound::PlayerDigging::new(
+                        status as i8,
+                        location,
+                        face,
+                    )))
+                }
+            }
+            PlayerBlockPlacement {
+                hand,
+                location,
+                face,
+                cursor_x,
+                cursor_y,
+                cursor_z,
+                inside_block,
+            } => {
+                let location = location.to_packed(protocol_version);
+
+                if protocol_version >= 477 {
+                    Ok(Box::new(v477::serverbound::PlayerBlockPlacement::new(
+                        VarInt(hand),
+                        location,
+                        VarInt(face),
+                        cursor_x,
+                        cursor_y,
+                        cursor_z,
+                        inside_block,
+                    )))
+                } else if protocol_version >= 315 {
+                    Ok(Box::new(v315::serverbound::PlayerBlockPlacement::new(
+                        location,
+                        VarInt(face),
+                        VarInt(hand),
+                        cursor_x,
+                        cursor_y,
+                        cursor_z,
+                    )))
+                } else if protocol_version >= 107 {
+                    Ok(Box::new(v107::serverbound::PlayerBlockPlacement::new(
+                        location,
+                        VarInt(face),
+                        VarInt(hand),
+                        (cursor_x * 16.0) as u8,
+                        (cursor_y * 16.0) as u8,
+                        (cursor_z * 16.0) as u8,
+                    )))
+                } else {
+                    Ok(Box::new(v47::serverbound::PlayerBlockPlacement::new(
+                        location,
+                        face as i8,
+                        Slot::new(None).into(),
+                        (cursor_x * 16.0) as u8,
+                        (cursor_y * 16.0) as u8,
+                        (cursor_z * 16.0) as u8,
+                    )))
+                }
+            }
+            UseItem(hand) => Ok(Box::new(v107::serverbound::UseItem::new(VarInt(hand)))),
+            EntityAction {
+                entity_id,
+                action_id,
+                jump_boost,
+            } => Ok(Box::new(play::serverbound::EntityAction::new(
+                VarInt(entity_id),
+                VarInt(action_id),
+                VarInt(jump_boost),
+            ))),
+            ClickWindow {
+                window_id,
+                slot,
+                button,
+                action_number,
+                mode,
+                clicked_item,
+            } => {
+                if protocol_version >= 402 {
+                    Ok(Box::new(v402::serverbound::ClickWindow::new(
+                        window_id,
+                        slot,
+                        button,
+                        action_number,
+                        VarInt(mode),
+                        clicked_item.into(),
+                    )))
+                } else if protocol_version >= 351 {
+                    Ok(Box::new(v351::serverbound::ClickWindow::new(
+                        window_id,
+                        slot,
+                        button,
+                        action_number,
+                        VarInt(mode),
+                        clicked_item.into(),
+                    )))
+                } else if protocol_version >= 107 {
+                    Ok(Box::new(v107::serverbound::ClickWindow::new(
+                        window_id,
+                        slot,
+                        button,
+                        action_number,
+                        VarInt(mode),
+                        clicked_item.into(),
+                    )))
+                } else {
+                    Ok(Box::new(v47::serverbound::ClickWindow::new(
+                        window_id,
+                        slot,
+                        button,
+                        action_number,
+                        mode as i8,
+                        clicked_item.into(),
+                    )))
+                }
+            }
+            ServerboundCloseWindow(window_id) => {
+                Ok(Box::new(play::serverbound::CloseWindow::new(window_id)))
+            }
         }
     }
 
@@ -346,11 +1130,18 @@ impl PacketKind {
 
                         Ok(PacketKind::LoginStart(packet.name))
                     }
+                    i if i == login::serverbound::EncryptionResponse::id(protocol_version) => {
+                        let packet = login::serverbound::EncryptionResponse::deserialize(&mut de)?;
+
+                        Ok(PacketKind::EncryptionResponse {
+                            shared_secret: packet.shared_secret.1,
+                            verify_token: packet.verify_token.1,
+                        })
+                    }
                     i => return Err(PacketError::InvalidPacketId(i, state)),
                 },
-                State::Play => match id {
-                    _ => unimplemented!(),
-                },
+                State::Play => Self::serverbound_play_from_bytes(protocol_version, id, &mut de)
+                    .unwrap_or(Err(PacketError::InvalidPacketId(id, state))),
                 State::Status => match id {
                     i if i == status::serverbound::Request::id(protocol_version) => {
                         Ok(PacketKind::StatusRequest)
@@ -364,8 +1155,862 @@ impl PacketKind {
                 },
             }
         } else {
-            todo!("add clientbound support");
+            match state {
+                State::Handshake => Err(PacketError::InvalidPacketId(id, state)),
+                State::Login => match id {
+                    i if protocol_version >= 707
+                        && i == version_specific::login::v707::clientbound::LoginSuccess::id(
+                            protocol_version,
+                        ) =>
+                    {
+                        let packet =
+                            version_specific::login::v707::clientbound::LoginSuccess::deserialize(
+                                &mut de,
+                            )?;
+
+                        Ok(PacketKind::LoginSuccess(
+                            Uuid::from_u128(packet.uuid),
+                            packet.name,
+                        ))
+                    }
+                    i if protocol_version < 707
+                        && i == version_specific::login::v47::clientbound::LoginSuccess::id(
+                            protocol_version,
+                        ) =>
+                    {
+                        let packet =
+                            version_specific::login::v47::clientbound::LoginSuccess::deserialize(
+                                &mut de,
+                            )?;
+                        let uuid = Uuid::parse_str(&packet.uuid)
+                            .map_err(|e| PacketError::SerdeError(format!("{}", e)))?;
+
+                        Ok(PacketKind::LoginSuccess(uuid, packet.name))
+                    }
+                    i if i == login::clientbound::EncryptionRequest::id(protocol_version) => {
+                        let packet = login::clientbound::EncryptionRequest::deserialize(&mut de)?;
+
+                        Ok(PacketKind::EncryptionRequest {
+                            server_id: packet.server_id,
+                            public_key: packet.public_key.1,
+                            verify_token: packet.verify_token.1,
+                        })
+                    }
+                    i if i == login::clientbound::SetCompression::id(protocol_version) => {
+                        let packet = login::clientbound::SetCompression::deserialize(&mut de)?;
+
+                        Ok(PacketKind::SetCompression(packet.threshold.0))
+                    }
+                    i if i == login::clientbound::Disconnect::id(protocol_version) => {
+                        let packet = login::clientbound::Disconnect::deserialize(&mut de)?;
+
+                        Ok(PacketKind::LoginDisconnect(ChatComponent::from_json(
+                            &packet.reason,
+                        )?))
+                    }
+                    i => Err(PacketError::InvalidPacketId(i, state)),
+                },
+                State::Play => Self::clientbound_play_from_bytes(protocol_version, id, &mut de)
+                    .unwrap_or(Err(PacketError::InvalidPacketId(id, state))),
+                State::Status => match id {
+                    i if i == status::clientbound::Response::id(protocol_version) => {
+                        let packet = status::clientbound::Response::deserialize(&mut de)?;
+
+                        Ok(PacketKind::StatusResponse(packet.data()?))
+                    }
+                    i if i == status::clientbound::Pong::id(protocol_version) => {
+                        let packet = status::clientbound::Pong::deserialize(&mut de)?;
+
+                        Ok(PacketKind::StatusPong(packet.payload))
+                    }
+                    i => Err(PacketError::InvalidPacketId(i, state)),
+                },
+            }
+        }
+    }
+}
+
+impl PacketKind {
+    /// Deserializes a serverbound `play` packet. Returns `None` if the id is unknown for the
+    /// protocol version.
+    fn serverbound_play_from_bytes(
+        protocol_version: i32,
+        id: i32,
+        de: &mut Deserializer,
+    ) -> Option<Result<Self, PacketError>> {
+        use play::serverbound as common;
+
+        macro_rules! packet {
+            ($ty:ty) => {
+                match <$ty>::deserialize(&mut *de) {
+                    Ok(p) => p,
+                    Err(e) => return Some(Err(e.into())),
+                }
+            };
+        }
+
+        let pv = protocol_version;
+
+        let packet = match id {
+            i if pv >= 107 && i == v107::serverbound::TeleportConfirm::id(pv) => {
+                let packet = packet!(v107::serverbound::TeleportConfirm);
+
+                PacketKind::TeleportConfirm(packet.teleport_id.0)
+            }
+            i if i == common::ClientStatus::id(pv) => {
+                let packet = packet!(common::ClientStatus);
+
+                PacketKind::ClientStatus(packet.action_id.0)
+            }
+            i if pv >= 107 && i == v107::serverbound::ClientSettings::id(pv) => {
+                let packet = packet!(v107::serverbound::ClientSettings);
+
+                PacketKind::ClientSettings {
+                    locale: packet.locale,
+                    view_distance: packet.view_distance,
+                    chat_mode: packet.chat_mode.0,
+                    chat_colors: packet.chat_colors,
+                    displayed_skin_parts: packet.displayed_skin_parts,
+                    main_hand: packet.main_hand.0,
+                }
+            }
+            i if pv < 107 && i == v47::serverbound::ClientSettings::id(pv) => {
+                let packet = packet!(v47::serverbound::ClientSettings);
+
+                PacketKind::ClientSettings {
+                    locale: packet.locale,
+                    view_distance: packet.view_distance,
+                    chat_mode: packet.chat_mode as i32,
+                    chat_colors: packet.chat_colors,
+                    displayed_skin_parts: packet.displayed_skin_parts,
+                    main_hand: 1,
+                }
+            }
+            i if i == common::ChatMessage::id(pv) => {
+                let packet = packet!(common::ChatMessage);
+
+                PacketKind::ServerboundChatMessage(packet.message)
+            }
+            i if pv >= 393 && i == v393::serverbound::TabComplete::id(pv) => {
+                let packet = packet!(v393::serverbound::TabComplete);
+
+                PacketKind::ServerboundTabComplete {
+                    transaction_id: packet.transaction_id.0,
+                    text: packet.text,
+                }
+            }
+            i if (107..393).contains(&pv) && i == v107::serverbound::TabComplete::id(pv) => {
+                let packet = packet!(v107::serverbound::TabComplete);
+
+                PacketKind::ServerboundTabComplete {
+                    transaction_id: 0,
+                    text: packet.text,
+                }
+            }
+            i if pv < 107 && i == v47::serverbound::TabComplete::id(pv) => {
+                let packet = packet!(v47::serverbound::TabComplete);
+
+                PacketKind::ServerboundTabComplete {
+                    transaction_id: 0,
+                    text: packet.text,
+                }
+            }
+            i if pv >= 339 && i == v339::serverbound::KeepAlive::id(pv) => {
+                let packet = packet!(v339::serverbound::KeepAlive);
+
+                PacketKind::ServerboundKeepAlive(packet.keep_alive_id)
+            }
+            i if pv < 339 && i == v47::serverbound::KeepAlive::id(pv) => {
+                let packet = packet!(v47::serverbound::KeepAlive);
+
+                PacketKind::ServerboundKeepAlive(packet.keep_alive_id.0 as i64)
+            }
+            i if i == common::PlayerMovement::id(pv) => {
+                let packet = packet!(common::PlayerMovement);
+
+                PacketKind::PlayerMovement(packet.on_ground)
+            }
+            i if i == common::PlayerPosition::id(pv) => {
+                let packet = packet!(common::PlayerPosition);
+
+                PacketKind::PlayerPosition {
+                    x: packet.x,
+                    feet_y: packet.feet_y,
+                    z: packet.z,
+                    on_ground: packet.on_ground,
+                }
+            }
+            i if i == common::PlayerRotation::id(pv) => {
+                let packet = packet!(common::PlayerRotation);
+
+                PacketKind::PlayerRotation {
+                    yaw: packet.yaw,
+                    pitch: packet.pitch,
+                    on_ground: packet.on_ground,
+                }
+            }
+            i if i == common::PlayerPositionAndRotation::id(pv) => {
+                let packet = packet!(common::PlayerPositionAndRotation);
+
+                PacketKind::PlayerPositionAndRotation {
+                    x: packet.x,
+                    feet_y: packet.feet_y,
+                    z: packet.z,
+                    yaw: packet.yaw,
+                    pitch: packet.pitch,
+                    on_ground: packet.on_ground,
+                }
+            }
+            i if i == common::HeldItemChange::id(pv) => {
+                let packet = packet!(common::HeldItemChange);
+
+                PacketKind::ServerboundHeldItemChange(packet.slot)
+            }
+            i if i == common::PluginMessage::id(pv) => {
+                let packet = packet!(common::PluginMessage);
+
+                PacketKind::ServerboundPluginMessage {
+                    channel: packet.channel,
+                    data: packet.data.0,
+                }
+            }
+            i if pv >= 107 && i == v107::serverbound::Animation::id(pv) => {
+                let packet = packet!(v107::serverbound::Animation);
+
+                PacketKind::Animation(packet.hand.0)
+            }
+            i if pv < 107 && i == v47::serverbound::Animation::id(pv) => PacketKind::Animation(0),
+            i if pv >= 107 && i == v107::serverbound::PlayerDigging::id(pv) => {
+                let packet = packet!(v107::serverbound::PlayerDigging);
+
+                PacketKind::PlayerDigging {
+                    status: packet.status.0,
+                    location: Position::from_packed(packet.location, pv),
+                    face: packet.face,
+                }
+            }
+            i if pv < 107 && i == v47::serverbound::PlayerDigging::id(pv) => {
+                let packet = packet!(v47::serverbound::PlayerDigging);
+
+                PacketKind::PlayerDigging {
+                    status: packet.status as i32,
+                    location: Position::from_packed(packet.location, pv),
+                    face: packet.face,
+                }
+            }
+            i if pv >= 477 && i == v477::serverbound::PlayerBlockPlacement::id(pv) => {
+                let packet = packet!(v477::serverbound::PlayerBlockPlacement);
+
+                PacketKind::PlayerBlockPlacement {
+                    hand: packet.hand.0,
+                    location: Position::from_packed(packet.location, pv),
+                    face: packet.face.0,
+                    cursor_x: packet.cursor_x,
+                    cursor_y: packet.cursor_y,
+                    cursor_z: packet.cursor_z,
+                    inside_block: packet.inside_block,
+                }
+            }
+            i if (315..477).contains(&pv)
+                && i == v315::serverbound::PlayerBlockPlacement::id(pv) =>
+            {
+                let packet = packet!(v315::serverbound::PlayerBlockPlacement);
+
+                PacketKind::PlayerBlockPlacement {
+                    hand: packet.hand.0,
+                    location: Position::from_packed(packet.location, pv),
+                    face: packet.face.0,
+                    cursor_x: packet.cursor_x,
+                    cursor_y: packet.cursor_y,
+                    cursor_z: packet.cursor_z,
+                    inside_block: false,
+                }
+            }
+            i if (107..315).contains(&pv)
+                && i == v107::serverbound::PlayerBlockPlacement::id(pv) =>
+            {
+                let packet = packet!(v107::serverbound::PlayerBlockPlacement);
+
+                PacketKind::PlayerBlockPlacement {
+                    hand: packet.hand.0,
+                    location: Position::from_packed(packet.location, pv),
+                    face: packet.face.0,
+                    cursor_x: packet.cursor_x as f32 / 16.0,
+                    cursor_y: packet.cursor_y as f32 / 16.0,
+                    cursor_z: packet.cursor_z as f32 / 16.0,
+                    inside_block: false,
+                }
+            }
+            i if pv < 107 && i == v47::serverbound::PlayerBlockPlacement::id(pv) => {
+                let packet = packet!(v47::serverbound::PlayerBlockPlacement);
+
+                PacketKind::PlayerBlockPlacement {
+                    hand: 0,
+                    location: Position::from_packed(packet.location, pv),
+                    face: packet.face as i32,
+                    cursor_x: packet.cursor_x as f32 / 16.0,
+                    cursor_y: packet.cursor_y as f32 / 16.0,
+                    cursor_z: packet.cursor_z as f32 / 16.0,
+                    inside_block: false,
+                }
+            }
+            i if pv >= 107 && i == v107::serverbound::UseItem::id(pv) => {
+                let packet = packet!(v107::serverbound::UseItem);
+
+                PacketKind::UseItem(packet.hand.0)
+            }
+            i if i == common::EntityAction::id(pv) => {
+                let packet = packet!(common::EntityAction);
+
+                PacketKind::EntityAction {
+                    entity_id: packet.entity_id.0,
+                    action_id: packet.action_id.0,
+                    jump_boost: packet.jump_boost.0,
+                }
+            }
+            i if pv >= 402 && i == v402::serverbound::ClickWindow::id(pv) => {
+                let packet = packet!(v402::serverbound::ClickWindow);
+
+                PacketKind::ClickWindow {
+                    window_id: packet.window_id,
+                    slot: packet.slot,
+                    button: packet.button,
+                    action_number: packet.action_number,
+                    mode: packet.mode.0,
+                    clicked_item: packet.clicked_item.into(),
+                }
+            }
+            i if (351..402).contains(&pv) && i == v351::serverbound::ClickWindow::id(pv) => {
+                let packet = packet!(v351::serverbound::ClickWindow);
+
+                PacketKind::ClickWindow {
+                    window_id: packet.window_id,
+                    slot: packet.slot,
+                    button: packet.button,
+                    action_number: packet.action_number,
+                    mode: packet.mode.0,
+                    clicked_item: packet.clicked_item.into(),
+                }
+            }
+            i if (107..351).contains(&pv) && i == v107::serverbound::ClickWindow::id(pv) => {
+                let packet = packet!(v107::serverbound::ClickWindow);
+
+                PacketKind::ClickWindow {
+                    window_id: packet.window_id,
+                    slot: packet.slot,
+                    button: packet.button,
+                    action_number: packet.action_number,
+                    mode: packet.mode.0,
+                    clicked_item: packet.clicked_item.into(),
+                }
+            }
+            i if pv < 107 && i == v47::serverbound::ClickWindow::id(pv) => {
+                let packet = packet!(v47::serverbound::ClickWindow);
+
+                PacketKind::ClickWindow {
+                    window_id: packet.window_id,
+                    slot: packet.slot,
+                    button: packet.button,
+                    action_number: packet.action_number,
+                    mode: packet.mode as i32,
+                    clicked_item: packet.clicked_item.into(),
+                }
+            }
+            i if i == common::CloseWindow::id(pv) => {
+                let packet = packet!(common::CloseWindow);
+
+                PacketKind::ServerboundCloseWindow(packet.window_id)
+            }
+            _ => return None,
+        };
+
+        Some(Ok(packet))
+    }
+
+    /// Deserializes a clientbound `play` packet. Returns `None` if the id is unknown for the
+    /// protocol version.
+    ///
+    /// Fields of [`JoinGame`](PacketKind::JoinGame) that are not part of the layout used by the
+    /// protocol version are filled with defaults.
+    fn clientbound_play_from_bytes(
+        protocol_version: i32,
+        id: i32,
+        de: &mut Deserializer,
+    ) -> Option<Result<Self, PacketError>> {
+        use version_specific::play::{v108, v110, v453, v464, v468, v552, v573, v735, v751, v754};
+
+        macro_rules! packet {
+            ($ty:ty) => {
+                match <$ty>::deserialize(&mut *de) {
+                    Ok(p) => p,
+                    Err(e) => return Some(Err(e.into())),
+                }
+            };
+        }
+
+        macro_rules! convert {
+            ($e:expr) => {
+                match $e {
+                    Ok(v) => v,
+                    Err(e) => return Some(Err(e)),
+                }
+            };
+        }
+
+        // Only whole chunks are supported, partial ones only update some sections.
+        macro_rules! chunk {
+            ($packet:expr, $mask:expr) => {{
+                if !$packet.full_chunk {
+                    return Some(Err(PacketError::SerdeError(String::from(
+                        "Partial chunks are not supported",
+                    ))));
+                }
+
+                convert!(Chunk::decode_data(protocol_version, $mask, &$packet.data.1))
+            }};
+        }
+
+        macro_rules! light {
+            ($packet:expr) => {{
+                let mut reader = Cursor::new($packet.light_arrays.0);
+
+                PacketKind::UpdateLight {
+                    x: $packet.chunk_x.0,
+                    z: $packet.chunk_z.0,
+                    sky_light: convert!(chunk::read_light_arrays(
+                        LIGHT_SECTION_COUNT,
+                        $packet.sky_light_mask.0,
+                        $packet.empty_sky_light_mask.0,
+                        &mut reader
+                    )),
+                    block_light: convert!(chunk::read_light_arrays(
+                        LIGHT_SECTION_COUNT,
+                        $packet.block_light_mask.0,
+                        $packet.empty_block_light_mask.0,
+                        &mut reader
+                    )),
+                }
+            }};
         }
+
+        // Before protocol version 754 the hardcore flag is bit 3 of the gamemode.
+        macro_rules! gamemode {
+            ($gamemode:expr) => {
+                (
+                    convert!(Gamemode::try_from(($gamemode & 0x7) as i8)),
+                    $gamemode & 0x8 != 0,
+                )
+            };
+        }
+
+        let pv = protocol_version;
+
+        let packet = match id {
+            i if pv >= 754 && i == v754::clientbound::JoinGame::id(pv) => {
+                let packet = packet!(v754::clientbound::JoinGame);
+
+                PacketKind::JoinGame {
+                    entity_id: packet.entity_id,
+                    is_hardcore: packet.is_hardcore,
+                    gamemode: convert!(Gamemode::try_from(packet.gamemode as i8)),
+                    previous_gamemode: convert!(Gamemode::try_from(packet.previous_gamemode)),
+                    world_names: packet.world_names.1,
+                    dimension_codec: packet.dimension_codec.1,
+                    dimension: packet.dimension.1,
+                    dimension_47: Dimension::Overworld,
+                    difficulty: Difficulty::Normal,
+                    world_name: packet.world_name,
+                    hashed_seed: packet.hashed_seed,
+                    max_players: packet.max_players.0,
+                    level_type: if packet.is_flat {
+                        LevelType::Flat
+                    } else {
+                        LevelType::Default
+                    },
+                    view_distance: packet.view_distance,
+                    reduced_debug_info: packet.reduced_debug_info,
+                    enable_respawn_screen: packet.enable_respawn_screen,
+                    is_debug: packet.is_debug,
+                    is_flat: packet.is_flat,
+                }
+            }
+            i if (522..754).contains(&pv) && i == v552::clientbound::JoinGame::id(pv) => {
+                let packet = packet!(v552::clientbound::JoinGame);
+                let (gamemode, is_hardcore) = gamemode!(packet.gamemode);
+                let level_type = convert!(packet.level_type.parse());
+
+                PacketKind::JoinGame {
+                    entity_id: packet.entity_id,
+                    is_hardcore,
+                    gamemode,
+                    previous_gamemode: Gamemode::NoPreviousMode,
+                    world_names: vec![],
+                    dimension_codec: DimensionCodec::default(),
+                    dimension: DimensionType::default(),
+                    dimension_47: convert!(Dimension::try_from(packet.dimension)),
+                    difficulty: Difficulty::Normal,
+                    world_name: String::new(),
+                    hashed_seed: packet.hashed_seed,
+                    max_players: packet.max_players as i32,
+                    level_type,
+                    view_distance: packet.view_distance,
+                    reduced_debug_info: packet.reduced_debug_info,
+                    enable_respawn_screen: packet.enable_respawn_screen,
+                    is_debug: false,
+                    is_flat: level_type == LevelType::Flat,
+                }
+            }
+            i if (468..522).contains(&pv) && i == v468::clientbound::JoinGame::id(pv) => {
+                let packet = packet!(v468::clientbound::JoinGame);
+                let (gamemode, is_hardcore) = gamemode!(packet.gamemode);
+                let level_type = convert!(packet.level_type.parse());
+
+                PacketKind::JoinGame {
+                    entity_id: packet.entity_id,
+                    is_hardcore,
+                    gamemode,
+                    previous_gamemode: Gamemode::NoPreviousMode,
+                    world_names: vec![],
+                    dimension_codec: DimensionCodec::default(),
+                    dimension: DimensionType::default(),
+                    dimension_47: convert!(Dimension::try_from(packet.dimension)),
+                    difficulty: Difficulty::Normal,
+                    world_name: String::new(),
+                    hashed_seed: 0,
+                    max_players: packet.max_players as i32,
+                    level_type,
+                    view_distance: packet.view_distance,
+                    reduced_debug_info: packet.reduced_debug_info,
+                    enable_respawn_screen: true,
+                    is_debug: false,
+                    is_flat: level_type == LevelType::Flat,
+                }
+            }
+            i if (464..468).contains(&pv) && i == v464::clientbound::JoinGame::id(pv) => {
+                let packet = packet!(v464::clientbound::JoinGame);
+                let (gamemode, is_hardcore) = gamemode!(packet.gamemode);
+                let level_type = convert!(packet.level_type.parse());
+
+                PacketKind::JoinGame {
+                    entity_id: packet.entity_id,
+                    is_hardcore,
+                    gamemode,
+                    previous_gamemode: Gamemode::NoPreviousMode,
+                    world_names: vec![],
+                    dimension_codec: DimensionCodec::default(),
+                    dimension: DimensionType::default(),
+                    dimension_47: convert!(Dimension::try_from(packet.dimension)),
+                    difficulty: Difficulty::Normal,
+                    world_name: String::new(),
+                    hashed_seed: 0,
+                    max_players: packet.max_players as i32,
+                    level_type,
+                    view_distance: VarInt(10),
+                    reduced_debug_info: packet.reduced_debug_info,
+                    enable_respawn_screen: true,
+                    is_debug: false,
+                    is_flat: level_type == LevelType::Flat,
+                }
+            }
+            i if (108..464).contains(&pv) && i == v108::clientbound::JoinGame::id(pv) => {
+                let packet = packet!(v108::clientbound::JoinGame);
+                let (gamemode, is_hardcore) = gamemode!(packet.gamemode);
+                let level_type = convert!(packet.level_type.parse());
+
+                PacketKind::JoinGame {
+                    entity_id: packet.entity_id,
+                    is_hardcore,
+                    gamemode,
+                    previous_gamemode: Gamemode::NoPreviousMode,
+                    world_names: vec![],
+                    dimension_codec: DimensionCodec::default(),
+                    dimension: DimensionType::default(),
+                    dimension_47: convert!(Dimension::try_from(packet.dimension)),
+                    difficulty: convert!(Difficulty::try_from(packet.difficulty)),
+                    world_name: String::new(),
+                    hashed_seed: 0,
+                    max_players: packet.max_players as i32,
+                    level_type,
+                    view_distance: VarInt(10),
+                    reduced_debug_info: packet.reduced_debug_info,
+                    enable_respawn_screen: true,
+                    is_debug: false,
+                    is_flat: level_type == LevelType::Flat,
+                }
+            }
+            i if pv < 108 && i == v47::clientbound::JoinGame::id(pv) => {
+                let packet = packet!(v47::clientbound::JoinGame);
+                let (gamemode, is_hardcore) = gamemode!(packet.gamemode);
+                let level_type = convert!(packet.level_type.parse());
+
+                PacketKind::JoinGame {
+                    entity_id: packet.entity_id,
+                    is_hardcore,
+                    gamemode,
+                    previous_gamemode: Gamemode::NoPreviousMode,
+                    world_names: vec![],
+                    dimension_codec: DimensionCodec::default(),
+                    dimension: DimensionType::default(),
+                    dimension_47: convert!(Dimension::try_from(packet.dimension as i32)),
+                    difficulty: convert!(Difficulty::try_from(packet.difficulty)),
+                    world_name: String::new(),
+                    hashed_seed: 0,
+                    max_players: packet.max_players as i32,
+                    level_type,
+                    view_distance: VarInt(10),
+                    reduced_debug_info: packet.reduced_debug_info,
+                    enable_respawn_screen: true,
+                    is_debug: false,
+                    is_flat: level_type == LevelType::Flat,
+                }
+            }
+            i if (453..=754).contains(&pv) && i == v453::clientbound::DeclareRecipes::id(pv) => {
+                let packet = packet!(v453::clientbound::DeclareRecipes);
+
+                PacketKind::DeclareRecipes(packet.recipes.1.into_iter().map(|r| r.into()).collect())
+            }
+            i if (402..453).contains(&pv) && i == v402::clientbound::DeclareRecipes::id(pv) => {
+                let packet = packet!(v402::clientbound::DeclareRecipes);
+
+                PacketKind::DeclareRecipes(packet.recipes.1.into_iter().map(|r| r.into()).collect())
+            }
+            i if (351..402).contains(&pv) && i == v351::clientbound::DeclareRecipes::id(pv) => {
+                let packet = packet!(v351::clientbound::DeclareRecipes);
+
+                PacketKind::DeclareRecipes(packet.recipes.1.into_iter().map(|r| r.into()).collect())
+            }
+            i if (348..351).contains(&pv)
+                && i == version_specific::play::v348::clientbound::DeclareRecipes::id(pv) =>
+            {
+                let packet = packet!(version_specific::play::v348::clientbound::DeclareRecipes);
+
+                PacketKind::DeclareRecipes(packet.recipes.1.into_iter().map(|r| r.into()).collect())
+            }
+            i if i == play::clientbound::HeldItemChange::id(pv) => {
+                let packet = packet!(play::clientbound::HeldItemChange);
+
+                PacketKind::HeldItemChange(packet.slot)
+            }
+            i if pv >= 464 && i == v464::clientbound::ServerDifficulty::id(pv) => {
+                let packet = packet!(v464::clientbound::ServerDifficulty);
+
+                PacketKind::ServerDifficulty(
+                    convert!(Difficulty::try_from(packet.difficulty)),
+                    packet.difficulty_locked,
+                )
+            }
+            i if pv < 464 && i == v47::clientbound::ServerDifficulty::id(pv) => {
+                let packet = packet!(v47::clientbound::ServerDifficulty);
+
+                PacketKind::ServerDifficulty(
+                    convert!(Difficulty::try_from(packet.difficulty)),
+                    false,
+                )
+            }
+            i if pv >= 339 && i == v339::clientbound::KeepAlive::id(pv) => {
+                let packet = packet!(v339::clientbound::KeepAlive);
+
+                PacketKind::ClientboundKeepAlive(packet.keep_alive_id)
+            }
+            i if pv < 339 && i == v47::clientbound::KeepAlive::id(pv) => {
+                let packet = packet!(v47::clientbound::KeepAlive);
+
+                PacketKind::ClientboundKeepAlive(packet.keep_alive_id.0 as i64)
+            }
+            i if i == play::clientbound::Disconnect::id(pv) => {
+                let packet = packet!(play::clientbound::Disconnect);
+
+                PacketKind::PlayDisconnect(convert!(ChatComponent::from_json(&packet.reason)))
+            }
+            i if pv >= 735 && i == v735::clientbound::ChatMessage::id(pv) => {
+                let packet = packet!(v735::clientbound::ChatMessage);
+
+                PacketKind::ClientboundChatMessage {
+                    message: convert!(ChatComponent::from_json(&packet.message)),
+                    position: convert!(ChatPosition::try_from(packet.position)),
+                    sender: Uuid::from_u128(packet.sender),
+                }
+            }
+            i if pv < 735 && i == v47::clientbound::ChatMessage::id(pv) => {
+                let packet = packet!(v47::clientbound::ChatMessage);
+
+                PacketKind::ClientboundChatMessage {
+                    message: convert!(ChatComponent::from_json(&packet.message)),
+                    position: convert!(ChatPosition::try_from(packet.position)),
+                    sender: Uuid::nil(),
+                }
+            }
+            i if pv >= 393 && i == v393::clientbound::DeclareCommands::id(pv) => {
+                let packet = packet!(v393::clientbound::DeclareCommands);
+
+                PacketKind::DeclareCommands {
+                    nodes: packet.nodes.1.into_iter().map(|n| n.0).collect(),
+                    root_index: packet.root_index.0,
+                }
+            }
+            i if pv >= 393 && i == v393::clientbound::TabComplete::id(pv) => {
+                let packet = packet!(v393::clientbound::TabComplete);
+
+                PacketKind::ClientboundTabComplete {
+                    transaction_id: packet.transaction_id.0,
+                    start: packet.start.0,
+                    length: packet.length.0,
+                    matches: packet.matches.1.into_iter().map(|m| m.0).collect(),
+                }
+            }
+            i if pv < 393 && i == v47::clientbound::TabComplete::id(pv) => {
+                let packet = packet!(v47::clientbound::TabComplete);
+
+                PacketKind::ClientboundTabComplete {
+                    transaction_id: 0,
+                    start: 0,
+                    length: 0,
+                    matches: packet.matches.1,
+                }
+            }
+            i if pv >= 107 && i == v107::clientbound::PlayerPositionAndLook::id(pv) => {
+                let packet = packet!(v107::clientbound::PlayerPositionAndLook);
+
+                PacketKind::PlayerPositionAndLook {
+                    x: packet.x,
+                    y: packet.y,
+                    z: packet.z,
+                    yaw: packet.yaw,
+                    pitch: packet.pitch,
+                    flags: packet.flags,
+                    teleport_id: packet.teleport_id.0,
+                }
+            }
+            i if pv < 107 && i == v47::clientbound::PlayerPositionAndLook::id(pv) => {
+                let packet = packet!(v47::clientbound::PlayerPositionAndLook);
+
+                PacketKind::PlayerPositionAndLook {
+                    x: packet.x,
+                    y: packet.y,
+                    z: packet.z,
+                    yaw: packet.yaw,
+                    pitch: packet.pitch,
+                    flags: packet.flags,
+                    teleport_id: 0,
+                }
+            }
+            i if i == play::clientbound::TimeUpdate::id(pv) => {
+                let packet = packet!(play::clientbound::TimeUpdate);
+
+                PacketKind::TimeUpdate {
+                    world_age: packet.world_age,
+                    time_of_day: packet.time_of_day,
+                }
+            }
+            i if pv >= 751 && i == v751::clientbound::ChunkData::id(pv) => {
+                let packet = packet!(v751::clientbound::ChunkData);
+                let mut chunk = chunk!(packet, packet.primary_bit_mask.0);
+
+                chunk.set_biomes(packet.biomes.1.into_iter().map(|b| b.0).collect());
+
+                PacketKind::ChunkData {
+                    x: packet.chunk_x,
+                    z: packet.chunk_z,
+                    chunk: Arc::new(chunk),
+                }
+            }
+            i if (735..751).contains(&pv) && i == v735::clientbound::ChunkData::id(pv) => {
+                let packet = packet!(v735::clientbound::ChunkData);
+                let mut chunk = chunk!(packet, packet.primary_bit_mask.0);
+
+                chunk.set_biomes(packet.biomes.0);
+
+                PacketKind::ChunkData {
+                    x: packet.chunk_x,
+                    z: packet.chunk_z,
+                    chunk: Arc::new(chunk),
+                }
+            }
+            i if (573..735).contains(&pv) && i == v573::clientbound::ChunkData::id(pv) => {
+                let packet = packet!(v573::clientbound::ChunkData);
+                let mut chunk = chunk!(packet, packet.primary_bit_mask.0);
+
+                chunk.set_biomes(packet.biomes.0);
+
+                PacketKind::ChunkData {
+                    x: packet.chunk_x,
+                    z: packet.chunk_z,
+                    chunk: Arc::new(chunk),
+                }
+            }
+            i if (477..573).contains(&pv) && i == v477::clientbound::ChunkData::id(pv) => {
+                let packet = packet!(v477::clientbound::ChunkData);
+
+                PacketKind::ChunkData {
+                    x: packet.chunk_x,
+                    z: packet.chunk_z,
+                    chunk: Arc::new(chunk!(packet, packet.primary_bit_mask.0)),
+                }
+            }
+            i if (110..477).contains(&pv) && i == v110::clientbound::ChunkData::id(pv) => {
+                let packet = packet!(v110::clientbound::ChunkData);
+
+                PacketKind::ChunkData {
+                    x: packet.chunk_x,
+                    z: packet.chunk_z,
+                    chunk: Arc::new(chunk!(packet, packet.primary_bit_mask.0)),
+                }
+            }
+            i if (107..110).contains(&pv) && i == v107::clientbound::ChunkData::id(pv) => {
+                let packet = packet!(v107::clientbound::ChunkData);
+
+                PacketKind::ChunkData {
+                    x: packet.chunk_x,
+                    z: packet.chunk_z,
+                    chunk: Arc::new(chunk!(packet, packet.primary_bit_mask.0)),
+                }
+            }
+            i if pv < 107 && i == v47::clientbound::ChunkData::id(pv) => {
+                let packet = packet!(v47::clientbound::ChunkData);
+
+                if packet.full_chunk && packet.primary_bit_mask == 0 && packet.data.1.is_empty() {
+                    PacketKind::UnloadChunk {
+                        x: packet.chunk_x,
+                        z: packet.chunk_z,
+                    }
+                } else {
+                    PacketKind::ChunkData {
+                        x: packet.chunk_x,
+                        z: packet.chunk_z,
+                        chunk: Arc::new(chunk!(packet, packet.primary_bit_mask as i32)),
+                    }
+                }
+            }
+            i if pv >= 107 && i == v107::clientbound::UnloadChunk::id(pv) => {
+                let packet = packet!(v107::clientbound::UnloadChunk);
+
+                PacketKind::UnloadChunk {
+                    x: packet.chunk_x,
+                    z: packet.chunk_z,
+                }
+            }
+            i if pv >= 735 && i == v735::clientbound::UpdateLight::id(pv) => {
+                let packet = packet!(v735::clientbound::UpdateLight);
+
+                light!(packet)
+            }
+            i if (477..735).contains(&pv) && i == v477::clientbound::UpdateLight::id(pv) => {
+                let packet = packet!(v477::clientbound::UpdateLight);
+
+                light!(packet)
+            }
+            i if pv >= 477 && i == v477::clientbound::UpdateViewPosition::id(pv) => {
+                let packet = packet!(v477::clientbound::UpdateViewPosition);
+
+                PacketKind::UpdateViewPosition {
+                    x: packet.chunk_x.0,
+                    z: packet.chunk_z.0,
+                }
+            }
+            _ => return None,
+        };
+
+        Some(Ok(packet))
     }
 }
 
@@ -382,6 +2027,10 @@ impl Display for PacketKind {
             } => write!(f, "Handshake"),
             LoginStart(_) => write!(f, "LoginStart"),
             LoginSuccess(..) => write!(f, "LoginSuccess"),
+            EncryptionRequest { .. } => write!(f, "EncryptionRequest"),
+            EncryptionResponse { .. } => write!(f, "EncryptionResponse"),
+            SetCompression(_) => write!(f, "SetCompression"),
+            LoginDisconnect(_) => write!(f, "LoginDisconnect"),
             StatusRequest => write!(f, "StatusRequest"),
             StatusResponse(_) => write!(f, "StatusResponse"),
             StatusPing(_) => write!(f, "StatusPing"),
@@ -390,6 +2039,36 @@ impl Display for PacketKind {
             DeclareRecipes(_) => write!(f, "DeclareRecipes"),
             HeldItemChange(_) => write!(f, "HeldItemChange"),
             ServerDifficulty(..) => write!(f, "ServerDifficulty"),
+            ClientboundKeepAlive(_) => write!(f, "ClientboundKeepAlive"),
+            PlayDisconnect(_) => write!(f, "PlayDisconnect"),
+            ClientboundChatMessage { .. } => write!(f, "ClientboundChatMessage"),
+            DeclareCommands { .. } => write!(f, "DeclareCommands"),
+            ClientboundTabComplete { .. } => write!(f, "ClientboundTabComplete"),
+            PlayerPositionAndLook { .. } => write!(f, "PlayerPositionAndLook"),
+            TimeUpdate { .. } => write!(f, "TimeUpdate"),
+            ChunkData { .. } => write!(f, "ChunkData"),
+            UnloadChunk { .. } => write!(f, "UnloadChunk"),
+            UpdateLight { .. } => write!(f, "UpdateLight"),
+            UpdateViewPosition { .. } => write!(f, "UpdateViewPosition"),
+            TeleportConfirm(_) => write!(f, "TeleportConfirm"),
+            ClientStatus(_) => write!(f, "ClientStatus"),
+            ClientSettings { .. } => write!(f, "ClientSettings"),
+            ServerboundChatMessage(_) => write!(f, "ServerboundChatMessage"),
+            ServerboundTabComplete { .. } => write!(f, "ServerboundTabComplete"),
+            ServerboundKeepAlive(_) => write!(f, "ServerboundKeepAlive"),
+            PlayerMovement(_) => write!(f, "PlayerMovement"),
+            PlayerPosition { .. } => write!(f, "PlayerPosition"),
+            PlayerRotation { .. } => write!(f, "PlayerRotation"),
+            PlayerPositionAndRotation { .. } => write!(f, "PlayerPositionAndRotation"),
+            ServerboundHeldItemChange(_) => write!(f, "ServerboundHeldItemChange"),
+            ServerboundPluginMessage { .. } => write!(f, "ServerboundPluginMessage"),
+            Animation(_) => write!(f, "Animation"),
+            PlayerDigging { .. } => write!(f, "PlayerDigging"),
+            PlayerBlockPlacement { .. } => write!(f, "PlayerBlockPlacement"),
+            UseItem(_) => write!(f, "UseItem"),
+            EntityAction { .. } => write!(f, "EntityAction"),
+            ClickWindow { .. } => write!(f, "ClickWindow"),
+            ServerboundCloseWindow(_) => write!(f, "ServerboundCloseWindow"),
         }
     }
 }
@@ -0,0 +1,633 @@
+use nbt::{Blob, Value};
+use uuid::Uuid;
+
+use super::{
+    common::status::{PlayerData, ResponseData, SinglePlayerData, VersionData},
+    error::PacketError,
+    types::{
+        ArgumentParser, ChatPosition, CommandNode, CommandNodeKind, Difficulty, Gamemode,
+        Ingridient, LevelType, Position, Recipe, RecipeData, Slot, SlotData, StringKind,
+    },
+    version_specific::types::{
+        v47::Dimension,
+        v754::{DimensionCodec, DimensionRegistry, DimensionRegistryEntry, DimensionType},
+    },
+    PacketKind, State,
+};
+use crate::{
+    chat::{ChatComponent, Color},
+    chunk::{NibbleArray, LIGHT_SECTION_COUNT},
+    serde::varint::VarInt,
+};
+
+/// A protocol version of every layout change of the packets.
+const PROTOCOL_VERSIONS: [i32; 14] = [
+    47, 107, 108, 110, 315, 340, 393, 404, 477, 498, 578, 735, 751, 754,
+];
+
+/// Encodes `packet` for `protocol_version` and decodes it again. Returns the id and the decoded
+/// packet.
+fn round_trip(
+    packet: &PacketKind,
+    state: State,
+    serverbound: bool,
+    protocol_version: i32,
+) -> (i32, PacketKind) {
+    let encoded = packet.clone().into_packet(protocol_version).unwrap();
+    let id = encoded.self_id(protocol_version);
+    let decoded = PacketKind::from_bytes(
+        state,
+        serverbound,
+        protocol_version,
+        id,
+        encoded.data_bytes().unwrap(),
+    )
+    .unwrap();
+
+    (id, decoded)
+}
+
+/// Returns all serverbound play packets a client with `protocol_version` sends, with values
+/// the protocol version can represent.
+fn serverbound_play_packets(protocol_version: i32) -> Vec<PacketKind> {
+    let pv = protocol_version;
+    let mut packets = vec![
+        PacketKind::ClientStatus(1),
+        PacketKind::ClientSettings {
+            locale: "en_GB".to_string(),
+            view_distance: 12,
+            chat_mode: 1,
+            chat_colors: true,
+            displayed_skin_parts: 0x7F,
+            main_hand: 1,
+        },
+        PacketKind::ServerboundChatMessage("/say hi".to_string()),
+        PacketKind::ServerboundTabComplete {
+            transaction_id: if pv >= 393 { 5 } else { 0 },
+            text: "/ti".to_string(),
+        },
+        PacketKind::ServerboundKeepAlive(123456),
+        PacketKind::PlayerMovement(true),
+        PacketKind::PlayerPosition {
+            x: 1.5,
+            feet_y: 64.0,
+            z: -2.25,
+            on_ground: false,
+        },
+        PacketKind::PlayerRotation {
+            yaw: 90.0,
+            pitch: -45.0,
+            on_ground: true,
+        },
+        PacketKind::PlayerPositionAndRotation {
+            x: -100.5,
+            feet_y: 70.0,
+            z: 3.0,
+            yaw: 180.0,
+            pitch: 10.0,
+            on_ground: true,
+        },
+        PacketKind::ServerboundHeldItemChange(4),
+        PacketKind::ServerboundPluginMessage {
+            channel: "minecraft:brand".to_string(),
+            data: vec![7, 118, 97, 110, 105, 108, 108, 97],
+        },
+        PacketKind::Animation(if pv >= 107 { 1 } else { 0 }),
+        PacketKind::PlayerDigging {
+            status: 2,
+            location: Position::new(-1, 63, 1000),
+            face: 1,
+        },
+        PacketKind::PlayerBlockPlacement {
+            hand: if pv >= 107 { 1 } else { 0 },
+            location: Position::new(12, 5, -30_000),
+            face: 3,
+            cursor_x: 0.5,
+            cursor_y: 0.25,
+            cursor_z: 0.75,
+            inside_block: pv >= 477,
+        },
+        PacketKind::EntityAction {
+            entity_id: 42,
+            action_id: 1,
+            jump_boost: 0,
+        },
+        PacketKind::ClickWindow {
+            window_id: 0,
+            slot: 36,
+            button: 1,
+            action_number: 7,
+            mode: 0,
+            clicked_item: Slot::new(None),
+        },
+        PacketKind::ServerboundCloseWindow(0),
+    ];
+
+    if pv >= 107 {
+        packets.push(PacketKind::TeleportConfirm(3));
+        packets.push(PacketKind::UseItem(1));
+    }
+
+    packets
+}
+
+#[test]
+fn serverbound_play_round_trip() {
+    for pv in PROTOCOL_VERSIONS.iter().copied() {
+        for packet in serverbound_play_packets(pv) {
+            let (id, decoded) = round_trip(&packet, State::Play, true, pv);
+
+            assert_eq!(decoded, packet, "{} with id {:#04x} in {}", packet, id, pv);
+        }
+    }
+}
+
+#[test]
+fn serverbound_play_ids() {
+    let ids = |packet: PacketKind| {
+        [47, 107, 393, 477, 735, 751]
+            .iter()
+            .map(|pv| packet.clone().into_packet(*pv).unwrap().self_id(*pv))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        ids(PacketKind::ServerboundChatMessage(String::new())),
+        [0x01, 0x02, 0x02, 0x03, 0x03, 0x03]
+    );
+    assert_eq!(
+        ids(PacketKind::ServerboundKeepAlive(0)),
+        [0x00, 0x0B, 0x0E, 0x0F, 0x10, 0x10]
+    );
+    assert_eq!(
+        ids(PacketKind::PlayerPosition {
+            x: 0.0,
+            feet_y: 0.0,
+            z: 0.0,
+            on_ground: true
+        }),
+        [0x04, 0x0C, 0x10, 0x11, 0x12, 0x12]
+    );
+    assert_eq!(
+        ids(PacketKind::ClientSettings {
+            locale: String::new(),
+            view_distance: 0,
+            chat_mode: 0,
+            chat_colors: false,
+            displayed_skin_parts: 0,
+            main_hand: 1,
+        }),
+        [0x15, 0x04, 0x04, 0x05, 0x05, 0x05]
+    );
+    assert_eq!(
+        ids(PacketKind::ServerboundHeldItemChange(0)),
+        [0x09, 0x17, 0x21, 0x23, 0x24, 0x25]
+    );
+    assert_eq!(
+        ids(PacketKind::PlayerBlockPlacement {
+            hand: 0,
+            location: Position::new(0, 0, 0),
+            face: 0,
+            cursor_x: 0.0,
+            cursor_y: 0.0,
+            cursor_z: 0.0,
+            inside_block: false,
+        }),
+        [0x08, 0x1C, 0x29, 0x2C, 0x2D, 0x2E]
+    );
+    assert_eq!(
+        ids(PacketKind::ClickWindow {
+            window_id: 0,
+            slot: 0,
+            button: 0,
+            action_number: 0,
+            mode: 0,
+            clicked_item: Slot::new(None),
+        }),
+        [0x0E, 0x07, 0x08, 0x09, 0x09, 0x09]
+    );
+}
+
+#[test]
+fn serverbound_play_ids_depend_on_version() {
+    // 0x00 is Keep Alive before protocol version 107 and Teleport Confirm since.
+    let keep_alive = PacketKind::from_bytes(State::Play, true, 47, 0x00, vec![0x2A]);
+    let teleport = PacketKind::from_bytes(State::Play, true, 107, 0x00, vec![0x2A]);
+
+    assert_eq!(keep_alive.unwrap(), PacketKind::ServerboundKeepAlive(42));
+    assert_eq!(teleport.unwrap(), PacketKind::TeleportConfirm(42));
+}
+
+#[test]
+fn unknown_serverbound_play_id() {
+    for pv in PROTOCOL_VERSIONS.iter().copied() {
+        match PacketKind::from_bytes(State::Play, true, pv, 0x7F, vec![]) {
+            Err(PacketError::InvalidPacketId(0x7F, State::Play)) => {}
+            other => panic!("Unexpected result in {}: {:?}", pv, other),
+        }
+    }
+}
+
+/// Returns a Join Game packet with values the layout of `protocol_version` can represent. The
+/// fields missing from the layout have the defaults the decoder fills in.
+fn join_game(protocol_version: i32) -> PacketKind {
+    let pv = protocol_version;
+    let dimension = DimensionType {
+        natural: true,
+        ambient_light: 0.0,
+        infiniburn: "minecraft:infiniburn_overworld".to_string(),
+        has_skylight: true,
+        bed_works: true,
+        effects: "minecraft:overworld".to_string(),
+        has_raids: true,
+        logical_height: 256,
+        coordinate_scale: 1.0,
+        ..DimensionType::default()
+    };
+    let dimension_codec = DimensionCodec {
+        dimension_registry: DimensionRegistry {
+            dimension_type: "minecraft:dimension_type".to_string(),
+            value: vec![DimensionRegistryEntry {
+                name: "minecraft:overworld".to_string(),
+                id: 0,
+                element: dimension.clone(),
+            }],
+        },
+        ..DimensionCodec::default()
+    };
+    let modern = pv >= 754;
+
+    PacketKind::JoinGame {
+        entity_id: 7,
+        is_hardcore: true,
+        gamemode: Gamemode::Creative,
+        previous_gamemode: if modern {
+            Gamemode::Survival
+        } else {
+            Gamemode::NoPreviousMode
+        },
+        world_names: if modern {
+            vec!["minecraft:overworld".to_string()]
+        } else {
+            vec![]
+        },
+        dimension_codec: if modern {
+            dimension_codec
+        } else {
+            DimensionCodec::default()
+        },
+        dimension: if modern {
+            dimension
+        } else {
+            DimensionType::default()
+        },
+        dimension_47: if modern {
+            Dimension::Overworld
+        } else {
+            Dimension::Nether
+        },
+        difficulty: if pv < 464 {
+            Difficulty::Hard
+        } else {
+            Difficulty::Normal
+        },
+        world_name: if modern {
+            "minecraft:overworld".to_string()
+        } else {
+            String::new()
+        },
+        hashed_seed: if pv >= 522 { -1234567890123 } else { 0 },
+        max_players: 20,
+        level_type: LevelType::Flat,
+        view_distance: VarInt(if pv >= 468 { 8 } else { 10 }),
+        reduced_debug_info: true,
+        enable_respawn_screen: pv < 522,
+        is_debug: false,
+        is_flat: true,
+    }
+}
+
+/// Returns recipes of every kind of recipe data.
+fn recipes() -> Vec<Recipe> {
+    let mut nbt = Blob::new();
+
+    nbt.insert("Damage", Value::Int(3)).unwrap();
+
+    let item = |id, count, nbt| Slot::new(Some(SlotData::new(id, count, 0, nbt)));
+
+    vec![
+        Recipe::new(
+            "arrow:shapeless".to_string(),
+            "minecraft:crafting_shapeless".to_string(),
+            Some(RecipeData::CraftingShapeless {
+                group: "planks".to_string(),
+                ingridients: vec![Ingridient::new(vec![
+                    item(17, 1, Blob::new()),
+                    item(162, 1, Blob::new()),
+                ])],
+                result: item(5, 4, Blob::new()),
+            }),
+        ),
+        Recipe::new(
+            "arrow:shaped".to_string(),
+            "minecraft:crafting_shaped".to_string(),
+            Some(RecipeData::CraftingShaped {
+                width: 2,
+                height: 1,
+                group: String::new(),
+                ingridients: vec![
+                    Ingridient::new(vec![item(5, 1, Blob::new())]),
+                    Ingridient::new(vec![Slot::new(None)]),
+                ],
+                result: item(280, 2, nbt),
+            }),
+        ),
+        Recipe::new(
+            "arrow:armor_dye".to_string(),
+            "minecraft:crafting_special_armordye".to_string(),
+            Some(RecipeData::CraftingSpecialArmorDye),
+        ),
+    ]
+}
+
+/// Returns all clientbound play packets a server sends to `protocol_version`, with values the
+/// protocol version can represent.
+fn clientbound_play_packets(protocol_version: i32) -> Vec<PacketKind> {
+    let pv = protocol_version;
+    let mut packets = vec![
+        join_game(pv),
+        PacketKind::HeldItemChange(3),
+        PacketKind::ServerDifficulty(Difficulty::Easy, pv >= 464),
+        PacketKind::ClientboundKeepAlive(if pv >= 339 { 1 << 40 } else { 1 << 20 }),
+        PacketKind::PlayDisconnect(ChatComponent::text("Bye").with_color(Color::Red)),
+        PacketKind::ClientboundChatMessage {
+            message: ChatComponent::translate(
+                "chat.type.text",
+                vec![ChatComponent::text("Steve"), ChatComponent::text("hi")],
+            ),
+            position: ChatPosition::Chat,
+            sender: if pv >= 735 {
+                Uuid::from_u128(0x0123_4567_89AB_CDEF)
+            } else {
+                Uuid::nil()
+            },
+        },
+        PacketKind::ClientboundTabComplete {
+            transaction_id: if pv >= 393 { 2 } else { 0 },
+            start: if pv >= 393 { 1 } else { 0 },
+            length: if pv >= 393 { 2 } else { 0 },
+            matches: vec!["time".to_string(), "tp".to_string()],
+        },
+        PacketKind::PlayerPositionAndLook {
+            x: 0.5,
+            y: 65.0,
+            z: -10.5,
+            yaw: 45.0,
+            pitch: 0.0,
+            flags: 0x10,
+            teleport_id: if pv >= 107 { 9 } else { 0 },
+        },
+        PacketKind::TimeUpdate {
+            world_age: 123456,
+            time_of_day: -6000,
+        },
+    ];
+
+    if pv >= 348 {
+        packets.push(PacketKind::DeclareRecipes(recipes()));
+    }
+
+    if pv >= 393 {
+        packets.push(PacketKind::DeclareCommands {
+            nodes: vec![
+                CommandNode {
+                    kind: CommandNodeKind::Root,
+                    executable: false,
+                    children: vec![1],
+                    redirect: None,
+                    suggestions: None,
+                },
+                CommandNode {
+                    kind: CommandNodeKind::Literal("msg".to_string()),
+                    executable: false,
+                    children: vec![2],
+                    redirect: None,
+                    suggestions: None,
+                },
+                CommandNode {
+                    kind: CommandNodeKind::Argument {
+                        name: "targets".to_string(),
+                        parser: ArgumentParser::Entity {
+                            single: false,
+                            only_players: true,
+                        },
+                    },
+                    executable: false,
+                    children: vec![3],
+                    redirect: None,
+                    suggestions: Some("minecraft:ask_server".to_string()),
+                },
+                CommandNode {
+                    kind: CommandNodeKind::Argument {
+                        name: "message".to_string(),
+                        parser: ArgumentParser::String(StringKind::GreedyPhrase),
+                    },
+                    executable: true,
+                    children: vec![],
+                    redirect: None,
+                    suggestions: None,
+                },
+            ],
+            root_index: 0,
+        });
+    }
+
+    packets
+}
+
+#[test]
+fn clientbound_play_round_trip() {
+    for pv in PROTOCOL_VERSIONS.iter().copied() {
+        for packet in clientbound_play_packets(pv) {
+            let (id, decoded) = round_trip(&packet, State::Play, false, pv);
+
+            assert_eq!(decoded, packet, "{} with id {:#04x} in {}", packet, id, pv);
+        }
+    }
+}
+
+#[test]
+fn light_packets_before_477() {
+    let packets = vec![
+        PacketKind::UpdateLight {
+            x: 0,
+            z: 0,
+            sky_light: vec![],
+            block_light: vec![],
+        },
+        PacketKind::UpdateViewPosition { x: 0, z: 0 },
+    ];
+
+    for packet in packets {
+        match packet.clone().into_packet(404) {
+            Err(PacketError::UnsupportedPacket(_, 404)) => {}
+            Err(e) => panic!("Unexpected error for {}: {}", packet, e),
+            Ok(_) => panic!("{} was encoded for 404", packet),
+        }
+    }
+}
+
+#[test]
+fn unload_chunk() {
+    let packet = PacketKind::UnloadChunk { x: 1, z: -1 };
+
+    // Before 1.9 chunks are unloaded by an empty Chunk Data packet.
+    for (pv, layout) in [(47, vec![1, 0, 0, 0]), (107, vec![])].iter() {
+        let encoded = packet.clone().into_packet(*pv).unwrap();
+        let mut expected = vec![0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF];
+        expected.extend(layout);
+
+        assert_eq!(encoded.data_bytes().unwrap(), expected, "{}", pv);
+        assert_eq!(round_trip(&packet, State::Play, false, *pv).1, packet);
+    }
+}
+
+#[test]
+fn update_light() {
+    let mut sky_light = vec![None; LIGHT_SECTION_COUNT];
+    let mut block_light = vec![None; LIGHT_SECTION_COUNT];
+
+    sky_light[1] = Some(NibbleArray::new(15));
+    block_light[2] = Some(NibbleArray::new(7));
+
+    let packet = PacketKind::UpdateLight {
+        x: 1,
+        z: -1,
+        sky_light,
+        block_light,
+    };
+
+    let mut light_arrays = vec![2, 4, 0, 0, 0x80, 0x10];
+    light_arrays.extend(vec![0xFF; 2048]);
+    light_arrays.extend(&[0x80, 0x10]);
+    light_arrays.extend(vec![0x77; 2048]);
+
+    // Since 1.16 the light at the edges of the chunk is trusted.
+    for (pv, trust_edges) in [(477, vec![]), (735, vec![1])].iter() {
+        let mut expected = vec![1, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+        expected.extend(trust_edges);
+        expected.extend(&light_arrays);
+
+        let encoded = packet.clone().into_packet(*pv).unwrap();
+
+        assert_eq!(encoded.data_bytes().unwrap(), expected, "{}", pv);
+        assert_eq!(round_trip(&packet, State::Play, false, *pv).1, packet);
+    }
+
+    // Sections in the empty masks get zero light without an array.
+    let id = packet.into_packet(477).unwrap().self_id(477);
+    let decoded = PacketKind::from_bytes(State::Play, false, 477, id, vec![0, 0, 0, 0, 1, 2]);
+
+    match decoded.unwrap() {
+        PacketKind::UpdateLight {
+            sky_light,
+            block_light,
+            ..
+        } => {
+            assert_eq!(sky_light[0], Some(NibbleArray::new(0)));
+            assert_eq!(sky_light[1], None);
+            assert_eq!(block_light[0], None);
+            assert_eq!(block_light[1], Some(NibbleArray::new(0)));
+        }
+        other => panic!("Unexpected packet {}", other),
+    }
+}
+
+#[test]
+fn join_game_layouts() {
+    // The first protocol version of every layout of Join Game.
+    for pv in [47, 108, 464, 468, 522, 754].iter().copied() {
+        let packet = join_game(pv);
+
+        assert_eq!(
+            round_trip(&packet, State::Play, false, pv).1,
+            packet,
+            "{}",
+            pv
+        );
+    }
+}
+
+#[test]
+fn declare_recipes_layouts() {
+    // The first protocol version of every layout of Declare Recipes.
+    for pv in [348, 351, 402, 453].iter().copied() {
+        let packet = PacketKind::DeclareRecipes(recipes());
+
+        assert_eq!(
+            round_trip(&packet, State::Play, false, pv).1,
+            packet,
+            "{}",
+            pv
+        );
+    }
+}
+
+#[test]
+fn clientbound_login_round_trip() {
+    let packets = [
+        PacketKind::EncryptionRequest {
+            server_id: String::new(),
+            public_key: vec![0x30, 0x81, 0x9F],
+            verify_token: vec![1, 2, 3, 4],
+        },
+        PacketKind::SetCompression(256),
+        PacketKind::LoginDisconnect(ChatComponent::text("Outdated client")),
+        PacketKind::LoginSuccess(
+            Uuid::parse_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap(),
+            "Notch".to_string(),
+        ),
+    ];
+
+    for pv in PROTOCOL_VERSIONS.iter().copied() {
+        for packet in packets.iter() {
+            assert_eq!(
+                round_trip(packet, State::Login, false, pv).1,
+                *packet,
+                "{}",
+                pv
+            );
+        }
+    }
+}
+
+#[test]
+fn clientbound_status_round_trip() {
+    let response = PacketKind::StatusResponse(ResponseData {
+        version: VersionData {
+            name: "Arrow 1.8-1.16.5".to_string(),
+            protocol: 754,
+        },
+        players: PlayerData {
+            max: 20,
+            online: 1,
+            sample: vec![SinglePlayerData {
+                name: "Notch".to_string(),
+                id: Uuid::parse_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap(),
+            }],
+        },
+        description: ChatComponent::text("An Arrow server"),
+        favicon: None,
+    });
+
+    for pv in PROTOCOL_VERSIONS.iter().copied() {
+        for packet in [response.clone(), PacketKind::StatusPong(99)].iter() {
+            assert_eq!(
+                round_trip(packet, State::Status, false, pv).1,
+                *packet,
+                "{}",
+                pv
+            );
+        }
+    }
+}
@@ -1,19 +1,22 @@
 use std::{
+    convert::TryFrom,
     io::{Error, ErrorKind, Read},
     marker::PhantomData,
+    str::FromStr,
 };
 
 use nbt::{de::Decoder, to_writer, Blob};
 use serde::{
-    de::{SeqAccess, Visitor},
-    ser::SerializeSeq,
+    de::{Error as DeError, SeqAccess, Visitor},
+    ser::{Error as SerError, SerializeSeq},
     Deserialize, Serialize,
 };
 
+use super::error::PacketError;
 use crate::serde::varint::{read_varint, write_varint};
 
 /// Difficulty type
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Difficulty {
     /// peaceful difficulty
     Peaceful = 0,
@@ -25,8 +28,19 @@ pub enum Difficulty {
     Hard = 3,
 }
 
+/// The position a chat message is displayed at.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatPosition {
+    /// a message sent by a player, shown in the chat box
+    Chat = 0,
+    /// a system message, shown in the chat box
+    System = 1,
+    /// shown above the hotbar
+    GameInfo = 2,
+}
+
 /// Gamemode type
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Gamemode {
     /// If no previous gamemode exists
     NoPreviousMode = -1,
@@ -41,7 +55,7 @@ pub enum Gamemode {
 }
 
 /// LevelType type
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LevelType {
     /// default world
     Default,
@@ -60,6 +74,7 @@ pub enum LevelType {
 }
 
 /// A crafting recipe.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Recipe {
     /// The recipe id.
     pub(crate) id: String,
@@ -93,6 +108,7 @@ impl Recipe {
 
 /// The recipe data.
 #[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum RecipeData {
     CraftingShapeless {
         group: String,
@@ -122,6 +138,7 @@ pub enum RecipeData {
 }
 
 /// A crafting ingridient.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Ingridient {
     pub(crate) items: Vec<Slot>,
 }
@@ -139,11 +156,25 @@ impl Ingridient {
 }
 
 /// A slot.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Slot {
     pub(crate) data: Option<SlotData>,
 }
 
+impl Slot {
+    /// Create a new [`Slot`]. `None` is an empty slot.
+    pub fn new(data: Option<SlotData>) -> Self {
+        Self { data }
+    }
+
+    /// Get a reference to the slot's data.
+    pub fn data(&self) -> Option<&SlotData> {
+        self.data.as_ref()
+    }
+}
+
 /// The data for a slot.
+#[derive(Debug, Clone, PartialEq)]
 pub struct SlotData {
     pub(crate) id: i16,
     pub(crate) count: u8,
@@ -151,6 +182,207 @@ pub struct SlotData {
     pub(crate) nbt: Blob,
 }
 
+impl SlotData {
+    /// Create a new [`SlotData`]. `damage` is only sent to clients before protocol version 351.
+    pub fn new(id: i16, count: u8, damage: i16, nbt: Blob) -> Self {
+        Self {
+            id,
+            count,
+            damage,
+            nbt,
+        }
+    }
+
+    /// Get a reference to the slot data's id.
+    pub fn id(&self) -> &i16 {
+        &self.id
+    }
+
+    /// Get a reference to the slot data's count.
+    pub fn count(&self) -> &u8 {
+        &self.count
+    }
+
+    /// Get a reference to the slot data's damage.
+    pub fn damage(&self) -> &i16 {
+        &self.damage
+    }
+
+    /// Get a reference to the slot data's nbt.
+    pub fn nbt(&self) -> &Blob {
+        &self.nbt
+    }
+}
+
+/// A block position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    /// The x coordinate.
+    pub x: i32,
+    /// The y coordinate.
+    pub y: i32,
+    /// The z coordinate.
+    pub z: i32,
+}
+
+impl Position {
+    /// Create a new [`Position`].
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Packs the position into the [Position](https://wiki.vg/Protocol#Position) long used by
+    /// `protocol_version`. Protocol version 477 swapped the y and z components.
+    pub fn to_packed(&self, protocol_version: i32) -> i64 {
+        let x = (self.x as i64 & 0x3FFFFFF) << 38;
+
+        if protocol_version >= 477 {
+            x | ((self.z as i64 & 0x3FFFFFF) << 12) | (self.y as i64 & 0xFFF)
+        } else {
+            x | ((self.y as i64 & 0xFFF) << 26) | (self.z as i64 & 0x3FFFFFF)
+        }
+    }
+
+    /// Unpacks a [Position](https://wiki.vg/Protocol#Position) long sent by a client with
+    /// `protocol_version`.
+    pub fn from_packed(value: i64, protocol_version: i32) -> Self {
+        let x = (value >> 38) as i32;
+
+        if protocol_version >= 477 {
+            Self::new(x, (value << 52 >> 52) as i32, (value << 26 >> 38) as i32)
+        } else {
+            Self::new(x, (value << 26 >> 52) as i32, (value << 38 >> 38) as i32)
+        }
+    }
+}
+
+/// A node of the command tree sent in the
+/// [Declare Commands](https://wiki.vg/Command_Data) packet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandNode {
+    /// Whether the node is a root, literal or argument node.
+    pub kind: CommandNodeKind,
+    /// Whether the command is complete at this node and can be executed.
+    pub executable: bool,
+    /// The indices of the child nodes.
+    pub children: Vec<i32>,
+    /// The index of the node this node redirects to.
+    pub redirect: Option<i32>,
+    /// The identifier of the suggestions provider, e.g. `minecraft:ask_server`. Only used by
+    /// argument nodes.
+    pub suggestions: Option<String>,
+}
+
+/// The kind of a [`CommandNode`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandNodeKind {
+    /// The root of the tree.
+    Root,
+    /// A literal word with its name.
+    Literal(String),
+    /// An argument parsed by `parser`.
+    Argument {
+        /// The name of the argument.
+        name: String,
+        /// The parser of the argument.
+        parser: ArgumentParser,
+    },
+}
+
+/// The parser of an argument [`CommandNode`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgumentParser {
+    /// `brigadier:bool`
+    Bool,
+    /// `brigadier:integer` with an optional minimum and maximum.
+    Integer {
+        /// The minimum value.
+        min: Option<i32>,
+        /// The maximum value.
+        max: Option<i32>,
+    },
+    /// `brigadier:double` with an optional minimum and maximum.
+    Double {
+        /// The minimum value.
+        min: Option<f64>,
+        /// The maximum value.
+        max: Option<f64>,
+    },
+    /// `brigadier:string`
+    String(StringKind),
+    /// `minecraft:entity`
+    Entity {
+        /// Whether only a single entity is allowed.
+        single: bool,
+        /// Whether only players are allowed.
+        only_players: bool,
+    },
+    /// `minecraft:game_profile`
+    GameProfile,
+    /// `minecraft:block_pos`
+    BlockPos,
+    /// `minecraft:vec3`
+    Vec3,
+    /// `minecraft:message`
+    Message,
+}
+
+/// How much of the input a `brigadier:string` argument reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringKind {
+    /// A single word.
+    SingleWord = 0,
+    /// A single word or a quoted phrase.
+    QuotablePhrase = 1,
+    /// The rest of the input.
+    GreedyPhrase = 2,
+}
+
+impl ArgumentParser {
+    /// Returns the identifier of the parser.
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            Self::Bool => "brigadier:bool",
+            Self::Integer { .. } => "brigadier:integer",
+            Self::Double { .. } => "brigadier:double",
+            Self::String(_) => "brigadier:string",
+            Self::Entity { .. } => "minecraft:entity",
+            Self::GameProfile => "minecraft:game_profile",
+            Self::BlockPos => "minecraft:block_pos",
+            Self::Vec3 => "minecraft:vec3",
+            Self::Message => "minecraft:message",
+        }
+    }
+}
+
+impl TryFrom<i32> for StringKind {
+    type Error = PacketError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::SingleWord),
+            1 => Ok(Self::QuotablePhrase),
+            2 => Ok(Self::GreedyPhrase),
+            v => Err(PacketError::SerdeError(format!(
+                "Invalid string argument kind {}",
+                v
+            ))),
+        }
+    }
+}
+
+/// A struct to serialize and deserialize NBT data that may be absent. An absent tag is sent as a
+/// single `TAG_End` byte.
+pub struct OptionalNbt<'a>(PhantomData<&'a ()>, pub Option<Blob>);
+struct OptionalNbtVisitor<'a>(PhantomData<&'a ()>);
+
+/// A struct serializing to the raw bytes without any length prefix. When deserializing it takes
+/// all remaining bytes of the packet, so it can only be the last field. Mark the field with
+/// `#[serde(default)]` if it may be empty.
+#[derive(Default)]
+pub struct RemainingBytes(pub Vec<u8>);
+struct RemainingBytesVisitor;
+
 /// A struct to serialize and deserialize NBT data.
 pub struct Nbt<'a, T>(PhantomData<&'a T>, pub T);
 struct NbtVisitor<'a, T>(PhantomData<&'a T>);
@@ -174,6 +406,100 @@ impl LevelType {
     }
 }
 
+impl TryFrom<u8> for Difficulty {
+    type Error = PacketError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Peaceful),
+            1 => Ok(Self::Easy),
+            2 => Ok(Self::Normal),
+            3 => Ok(Self::Hard),
+            v => Err(PacketError::SerdeError(format!("Invalid difficulty {}", v))),
+        }
+    }
+}
+
+impl TryFrom<i8> for Gamemode {
+    type Error = PacketError;
+
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        match value {
+            -1 => Ok(Self::NoPreviousMode),
+            0 => Ok(Self::Survival),
+            1 => Ok(Self::Creative),
+            2 => Ok(Self::Adventure),
+            3 => Ok(Self::Spectator),
+            v => Err(PacketError::SerdeError(format!("Invalid gamemode {}", v))),
+        }
+    }
+}
+
+impl TryFrom<i8> for ChatPosition {
+    type Error = PacketError;
+
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Chat),
+            1 => Ok(Self::System),
+            2 => Ok(Self::GameInfo),
+            v => Err(PacketError::SerdeError(format!(
+                "Invalid chat position {}",
+                v
+            ))),
+        }
+    }
+}
+
+impl FromStr for LevelType {
+    type Err = PacketError;
+
+    /// used to convert a String to the enum value
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(Self::Default),
+            "flat" => Ok(Self::Flat),
+            "largebiomes" => Ok(Self::LargeBiomes),
+            "amplified" => Ok(Self::Amplified),
+            "customized" => Ok(Self::Customized),
+            "buffet" => Ok(Self::Buffet),
+            "default_1_1" => Ok(Self::Default11),
+            _ => Err(PacketError::SerdeError(format!("Invalid level type {}", s))),
+        }
+    }
+}
+
+impl RecipeData {
+    /// Returns the data of a special crafting recipe of type `ty`, or `None` if `ty` does not name
+    /// a special crafting recipe. The `minecraft:` namespace is optional.
+    pub(crate) fn special(ty: &str) -> Option<Self> {
+        match ty.trim_start_matches("minecraft:") {
+            "crafting_special_armordye" => Some(Self::CraftingSpecialArmorDye),
+            "crafting_special_bookcloning" => Some(Self::CraftingSpecialBookCloning),
+            "crafting_special_mapcloning" => Some(Self::CraftingSpecialMapCloning),
+            "crafting_special_mapextending" => Some(Self::CraftingSpecialMapExtending),
+            "crafting_special_firework_rocket" => Some(Self::CraftingSpecialFireworkRocket),
+            "crafting_special_firework_star" => Some(Self::CraftingSpecialFireworkStar),
+            "crafting_special_firework_star_fade" => Some(Self::CraftingSpecialFireworkStarFade),
+            "crafting_special_repairitem" => Some(Self::CraftingSpecialRepairItem),
+            "crafting_special_tippedarrow" => Some(Self::CraftingSpecialTippedArrow),
+            "crafting_special_bannerduplicate" => Some(Self::CraftingSpecialBannerDuplicate),
+            "crafting_special_banneraddpattern" => Some(Self::CraftingSpecialBannerAddPattern),
+            "crafting_special_shielddecoration" => Some(Self::CraftingSpecialShieldDecoration),
+            "crafting_special_shulkerboxcoloring" => Some(Self::CraftingSpecialShulkerBoxColoring),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the next element of `seq`, failing if the sequence has ended.
+pub(crate) fn next_element<'de, T: Deserialize<'de>, A: SeqAccess<'de>>(
+    seq: &mut A,
+) -> Result<T, A::Error> {
+    seq.next_element()?
+        .ok_or_else(|| A::Error::custom("unexpected end of sequence"))
+}
+
 impl<'a, 'de: 'a, T: Serialize + Deserialize<'de>> Nbt<'a, T> {
     /// Returns a new NBT type.
     pub fn new(t: T) -> Self {
@@ -215,12 +541,128 @@ impl<'a, 'de: 'a, T: Serialize + Deserialize<'de>> Visitor<'de> for NbtVisitor<'
         A: serde::de::SeqAccess<'de>,
     {
         let mut decoder = Decoder::new(SeqReader(PhantomData, seq));
-        let value = T::deserialize(&mut decoder).unwrap();
+        let value = T::deserialize(&mut decoder).map_err(A::Error::custom)?;
 
         Ok(Nbt(PhantomData, value))
     }
 }
 
+impl<'a> OptionalNbt<'a> {
+    /// Returns a new optional NBT type.
+    pub fn new(blob: Option<Blob>) -> Self {
+        Self(PhantomData, blob)
+    }
+}
+
+impl<'a> From<Blob> for OptionalNbt<'a> {
+    fn from(blob: Blob) -> Self {
+        if blob == Blob::new() {
+            Self::new(None)
+        } else {
+            Self::new(Some(blob))
+        }
+    }
+}
+
+impl<'a> From<OptionalNbt<'a>> for Blob {
+    fn from(nbt: OptionalNbt<'a>) -> Self {
+        nbt.1.unwrap_or_default()
+    }
+}
+
+impl<'a> Serialize for OptionalNbt<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match &self.1 {
+            Some(blob) => {
+                let mut bytes = vec![];
+
+                blob.to_writer(&mut bytes).map_err(S::Error::custom)?;
+
+                serializer.serialize_bytes(&bytes)
+            }
+            None => serializer.serialize_u8(0),
+        }
+    }
+}
+
+impl<'a, 'de: 'a> Deserialize<'de> for OptionalNbt<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(OptionalNbtVisitor(PhantomData))
+    }
+}
+
+impl<'a, 'de: 'a> Visitor<'de> for OptionalNbtVisitor<'a> {
+    type Value = OptionalNbt<'a>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("expected seq")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let tag: u8 = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::custom("Unexpected eof."))?;
+
+        if tag == 0 {
+            return Ok(OptionalNbt::new(None));
+        }
+
+        let tag = [tag];
+        let mut reader = (&tag[..]).chain(SeqReader(PhantomData, &mut seq));
+        let blob = Blob::from_reader(&mut reader).map_err(A::Error::custom)?;
+
+        Ok(OptionalNbt::new(Some(blob)))
+    }
+}
+
+impl Serialize for RemainingBytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for RemainingBytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(RemainingBytesVisitor)
+    }
+}
+
+impl<'de> Visitor<'de> for RemainingBytesVisitor {
+    type Value = RemainingBytes;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("expected seq")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut bytes = vec![];
+
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+
+        Ok(RemainingBytes(bytes))
+    }
+}
+
 impl<'a, 'de: 'a, T: Into<R>, R: Serialize + Deserialize<'de>> From<Vec<T>>
     for LengthPrefixedVec<'a, R>
 {
@@ -15,8 +15,10 @@ pub mod clientbound {
     /// `name` is the name of the joining player. Must be the same as in the [`LoginStart`] packet.
     #[derive(Serialize, Deserialize)]
     pub struct LoginSuccess {
-        uuid: String,
-        name: String,
+        /// The hyphenated uuid of the player.
+        pub uuid: String,
+        /// The name of the player.
+        pub name: String,
     }
 
     impl LoginSuccess {
@@ -16,14 +16,19 @@ pub mod clientbound {
     /// `name` is the name of the joining player. Must be the same as in the [`LoginStart`] packet.
     #[derive(Serialize, Deserialize)]
     pub struct LoginSuccess {
-        uuid: Uuid,
-        name: String,
+        /// The uuid of the player as a 128 bit integer.
+        pub uuid: u128,
+        /// The name of the player.
+        pub name: String,
     }
 
     impl LoginSuccess {
         /// Create a new LoginSuccess packet.
         pub fn new(uuid: Uuid, name: String) -> Self {
-            Self { uuid, name }
+            Self {
+                uuid: uuid.as_u128(),
+                name,
+            }
         }
     }
 
@@ -1,9 +1,19 @@
+/// All `play` packets for protocol version 107 and above.
+pub mod v107;
 /// All `play` packets for protocol version 108 and above.
 pub mod v108;
+/// All `play` packets for protocol versions 110 and above.
+pub mod v110;
+/// All `play` packets for protocol versions 315 and above.
+pub mod v315;
+/// All `play` packets for protocol versions 339 and above.
+pub mod v339;
 /// All `play` packets for protocol versions 348 and above.
 pub mod v348;
 /// All `play` packets for protocol versions 351 and above.
 pub mod v351;
+/// All `play` packets for protocol versions 393 and above.
+pub mod v393;
 /// All `play` packets for protocol versions 402 and above.
 pub mod v402;
 /// All `play` packets for protocol versions 453 and above.
@@ -14,7 +24,15 @@ pub mod v464;
 pub mod v468;
 /// All `play` packets for protocol version 47 and above.
 pub mod v47;
+/// All `play` packets for protocol versions 477 and above.
+pub mod v477;
 /// All `play` packets for protocol version 552 and above.
 pub mod v552;
+/// All `play` packets for protocol versions 573 and above.
+pub mod v573;
+/// All `play` packets for protocol version 735 and above.
+pub mod v735;
+/// All `play` packets for protocol versions 751 and above.
+pub mod v751;
 /// All `play` packets for protocol version 754 and above.
 pub mod v754;
@@ -0,0 +1,631 @@
+/// All clientbound `play` packets for protocol versions 107 and above.
+pub mod clientbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        packets::{error::PacketError, types::LengthPrefixedVec, Packet},
+        serde::{ser::Serializer, varint::VarInt},
+    };
+
+    /// The [Player Position And Look](https://wiki.vg/Protocol#Player_Position_And_Look_.28clientbound.29) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerPositionAndLook {
+        /// The x coordinate.
+        pub x: f64,
+        /// The y coordinate of the feet.
+        pub y: f64,
+        /// The z coordinate.
+        pub z: f64,
+        /// The yaw in degrees.
+        pub yaw: f32,
+        /// The pitch in degrees.
+        pub pitch: f32,
+        /// Bit field of the values that are relative to the current ones.
+        pub flags: i8,
+        /// The id the client confirms the teleport with.
+        pub teleport_id: VarInt,
+    }
+
+    impl PlayerPositionAndLook {
+        /// create a new [PlayerPositionAndLook] packet
+        pub fn new(
+            x: f64,
+            y: f64,
+            z: f64,
+            yaw: f32,
+            pitch: f32,
+            flags: i8,
+            teleport_id: VarInt,
+        ) -> Self {
+            Self {
+                x,
+                y,
+                z,
+                yaw,
+                pitch,
+                flags,
+                teleport_id,
+            }
+        }
+    }
+
+    impl Packet for PlayerPositionAndLook {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 751 {
+                0x34
+            } else if protocol_version >= 721 {
+                0x35
+            } else if protocol_version >= 550 {
+                0x36
+            } else if protocol_version >= 477 {
+                0x35
+            } else if protocol_version >= 393 {
+                0x32
+            } else if protocol_version >= 338 {
+                0x2F
+            } else {
+                0x2E
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Chunk Data](https://wiki.vg/index.php?title=Protocol&oldid=7617#Chunk_Data) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ChunkData<'a> {
+        /// The x coordinate of the chunk.
+        pub chunk_x: i32,
+        /// The z coordinate of the chunk.
+        pub chunk_z: i32,
+        /// True if the packet contains the whole chunk including its biomes.
+        pub full_chunk: bool,
+        /// Bit mask of the sections contained in the data, starting at the bottom.
+        pub primary_bit_mask: VarInt,
+        /// The sections, their light and the biomes.
+        #[serde(borrow)]
+        pub data: LengthPrefixedVec<'a, u8>,
+    }
+
+    impl<'a> ChunkData<'a> {
+        /// create a new [ChunkData] packet
+        pub fn new(
+            chunk_x: i32,
+            chunk_z: i32,
+            full_chunk: bool,
+            primary_bit_mask: VarInt,
+            data: Vec<u8>,
+        ) -> Self {
+            Self {
+                chunk_x,
+                chunk_z,
+                full_chunk,
+                primary_bit_mask,
+                data: LengthPrefixedVec::new(data),
+            }
+        }
+    }
+
+    impl<'a> Packet for ChunkData<'a> {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x20
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Unload Chunk](https://wiki.vg/Protocol#Unload_Chunk) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct UnloadChunk {
+        /// The x coordinate of the chunk.
+        pub chunk_x: i32,
+        /// The z coordinate of the chunk.
+        pub chunk_z: i32,
+    }
+
+    impl UnloadChunk {
+        /// create a new [UnloadChunk] packet
+        pub fn new(chunk_x: i32, chunk_z: i32) -> Self {
+            Self { chunk_x, chunk_z }
+        }
+    }
+
+    impl Packet for UnloadChunk {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 751 {
+                0x1C
+            } else if protocol_version >= 721 {
+                0x1D
+            } else if protocol_version >= 550 {
+                0x1E
+            } else if protocol_version >= 477 {
+                0x1D
+            } else if protocol_version >= 393 {
+                0x1F
+            } else {
+                0x1D
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
+
+/// All serverbound `play` packets for protocol versions 107 and above.
+pub mod serverbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::packets::types::RemainingBytes;
+    use crate::packets::version_specific::types::v47::Slot;
+    use crate::serde::varint::VarInt;
+    use crate::{
+        packets::{error::PacketError, Packet},
+        serde::ser::Serializer,
+    };
+
+    /// The [Teleport Confirm](https://wiki.vg/Protocol#Teleport_Confirm) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct TeleportConfirm {
+        /// The id given by the Player Position And Look packet.
+        pub teleport_id: VarInt,
+    }
+
+    impl TeleportConfirm {
+        /// create a new [TeleportConfirm] packet
+        pub fn new(teleport_id: VarInt) -> Self {
+            Self { teleport_id }
+        }
+    }
+
+    impl Packet for TeleportConfirm {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x00
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Client Settings](https://wiki.vg/Protocol#Client_Settings) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ClientSettings {
+        /// The locale of the client, e.g. en_GB.
+        pub locale: String,
+        /// Client-side render distance, in chunks.
+        pub view_distance: i8,
+        /// 0: enabled, 1: commands only, 2: hidden.
+        pub chat_mode: VarInt,
+        /// Whether the client wants colored chat.
+        pub chat_colors: bool,
+        /// Bit mask of the displayed skin parts.
+        pub displayed_skin_parts: u8,
+        /// 0: Left, 1: Right.
+        pub main_hand: VarInt,
+    }
+
+    impl ClientSettings {
+        /// create a new [ClientSettings] packet
+        pub fn new(
+            locale: String,
+            view_distance: i8,
+            chat_mode: VarInt,
+            chat_colors: bool,
+            displayed_skin_parts: u8,
+            main_hand: VarInt,
+        ) -> Self {
+            Self {
+                locale,
+                view_distance,
+                chat_mode,
+                chat_colors,
+                displayed_skin_parts,
+                main_hand,
+            }
+        }
+    }
+
+    impl Packet for ClientSettings {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 477 {
+                0x05
+            } else if protocol_version >= 338 {
+                0x04
+            } else if protocol_version >= 335 {
+                0x05
+            } else {
+                0x04
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Animation](https://wiki.vg/Protocol#Animation_.28serverbound.29) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct Animation {
+        /// Hand used for the animation. 0: main hand, 1: off hand.
+        pub hand: VarInt,
+    }
+
+    impl Animation {
+        /// create a new [Animation] packet
+        pub fn new(hand: VarInt) -> Self {
+            Self { hand }
+        }
+    }
+
+    impl Packet for Animation {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 751 {
+                0x2C
+            } else if protocol_version >= 735 {
+                0x2B
+            } else if protocol_version >= 477 {
+                0x2A
+            } else if protocol_version >= 393 {
+                0x27
+            } else if protocol_version >= 335 {
+                0x1D
+            } else {
+                0x1A
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Player Digging](https://wiki.vg/Protocol#Player_Digging) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerDigging {
+        /// The action the player is taking against the block.
+        pub status: VarInt,
+        /// Block position, packed for the protocol version.
+        pub location: i64,
+        /// The face being hit.
+        pub face: i8,
+    }
+
+    impl PlayerDigging {
+        /// create a new [PlayerDigging] packet
+        pub fn new(status: VarInt, location: i64, face: i8) -> Self {
+            Self {
+                status,
+                location,
+                face,
+            }
+        }
+    }
+
+    impl Packet for PlayerDigging {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 735 {
+                0x1B
+            } else if protocol_version >= 477 {
+                0x1A
+            } else if protocol_version >= 393 {
+                0x18
+            } else if protocol_version >= 335 {
+                0x14
+            } else {
+                0x13
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Player Block Placement](https://wiki.vg/Protocol#Player_Block_Placement) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerBlockPlacement {
+        /// Block position, packed for the protocol version.
+        pub location: i64,
+        /// The face on which the block is placed.
+        pub face: VarInt,
+        /// The hand from which the block is placed. 0: main hand, 1: off hand.
+        pub hand: VarInt,
+        /// The position of the crosshair on the block, multiplied by 16.
+        pub cursor_x: u8,
+        /// The position of the crosshair on the block, multiplied by 16.
+        pub cursor_y: u8,
+        /// The position of the crosshair on the block, multiplied by 16.
+        pub cursor_z: u8,
+    }
+
+    impl PlayerBlockPlacement {
+        /// create a new [PlayerBlockPlacement] packet
+        pub fn new(
+            location: i64,
+            face: VarInt,
+            hand: VarInt,
+            cursor_x: u8,
+            cursor_y: u8,
+            cursor_z: u8,
+        ) -> Self {
+            Self {
+                location,
+                face,
+                hand,
+                cursor_x,
+                cursor_y,
+                cursor_z,
+            }
+        }
+    }
+
+    impl Packet for PlayerBlockPlacement {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x1C
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Use Item](https://wiki.vg/Protocol#Use_Item) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct UseItem {
+        /// Hand used for the animation. 0: main hand, 1: off hand.
+        pub hand: VarInt,
+    }
+
+    impl UseItem {
+        /// create a new [UseItem] packet
+        pub fn new(hand: VarInt) -> Self {
+            Self { hand }
+        }
+    }
+
+    impl Packet for UseItem {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 751 {
+                0x2F
+            } else if protocol_version >= 735 {
+                0x2E
+            } else if protocol_version >= 477 {
+                0x2D
+            } else if protocol_version >= 393 {
+                0x2A
+            } else if protocol_version >= 335 {
+                0x20
+            } else {
+                0x1D
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Click Window](https://wiki.vg/Protocol#Click_Window) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ClickWindow<'a> {
+        /// The id of the window which was clicked. 0 for the player inventory.
+        pub window_id: u8,
+        /// The clicked slot number.
+        pub slot: i16,
+        /// The button used in the click.
+        pub button: i8,
+        /// A unique number for the action, used for transaction handling.
+        pub action_number: i16,
+        /// Inventory operation mode.
+        pub mode: VarInt,
+        /// The clicked slot. Has to be empty (item ID = -1) for drop mode.
+        #[serde(borrow)]
+        pub clicked_item: Slot<'a>,
+    }
+
+    impl<'a> ClickWindow<'a> {
+        /// create a new [ClickWindow] packet
+        pub fn new(
+            window_id: u8,
+            slot: i16,
+            button: i8,
+            action_number: i16,
+            mode: VarInt,
+            clicked_item: Slot<'a>,
+        ) -> Self {
+            Self {
+                window_id,
+                slot,
+                button,
+                action_number,
+                mode,
+                clicked_item,
+            }
+        }
+    }
+
+    impl<'a> Packet for ClickWindow<'a> {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 338 {
+                0x07
+            } else if protocol_version >= 335 {
+                0x08
+            } else {
+                0x07
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Tab-Complete](https://wiki.vg/index.php?title=Protocol&oldid=14204#Tab-Complete_.28serverbound.29) packet for version 107 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct TabComplete {
+        /// All text behind the cursor.
+        pub text: String,
+        /// Whether the text should be completed as a command even without a leading `/`.
+        pub assume_command: bool,
+        /// Whether the player is looking at a block.
+        pub has_position: bool,
+        /// The packed position of the looked at block. Only present if `has_position` is set.
+        #[serde(default)]
+        pub looked_at_block: RemainingBytes,
+    }
+
+    impl TabComplete {
+        /// create a new [TabComplete] packet
+        pub fn new(text: String) -> Self {
+            Self {
+                text,
+                assume_command: false,
+                has_position: false,
+                looked_at_block: RemainingBytes::default(),
+            }
+        }
+    }
+
+    impl Packet for TabComplete {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 338 {
+                0x01
+            } else if protocol_version >= 335 {
+                0x02
+            } else {
+                0x01
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
@@ -0,0 +1,81 @@
+/// All clientbound `play` packets for protocol versions 110 and above.
+pub mod clientbound {
+    use nbt::Blob;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        packets::{
+            error::PacketError,
+            types::{LengthPrefixedVec, Nbt},
+            Packet,
+        },
+        serde::{ser::Serializer, varint::VarInt},
+    };
+
+    /// The [Chunk Data](https://wiki.vg/index.php?title=Protocol&oldid=14204#Chunk_Data) packet for version 110 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ChunkData<'a> {
+        /// The x coordinate of the chunk.
+        pub chunk_x: i32,
+        /// The z coordinate of the chunk.
+        pub chunk_z: i32,
+        /// True if the packet contains the whole chunk including its biomes.
+        pub full_chunk: bool,
+        /// Bit mask of the sections contained in the data, starting at the bottom.
+        pub primary_bit_mask: VarInt,
+        /// The sections, their light and the biomes.
+        #[serde(borrow)]
+        pub data: LengthPrefixedVec<'a, u8>,
+        /// The NBT data of the block entities in the chunk.
+        #[serde(borrow)]
+        pub block_entities: LengthPrefixedVec<'a, Nbt<'a, Blob>>,
+    }
+
+    impl<'a> ChunkData<'a> {
+        /// create a new [ChunkData] packet
+        pub fn new(
+            chunk_x: i32,
+            chunk_z: i32,
+            full_chunk: bool,
+            primary_bit_mask: VarInt,
+            data: Vec<u8>,
+            block_entities: Vec<Blob>,
+        ) -> Self {
+            Self {
+                chunk_x,
+                chunk_z,
+                full_chunk,
+                primary_bit_mask,
+                data: LengthPrefixedVec::new(data),
+                block_entities: LengthPrefixedVec::new(
+                    block_entities.into_iter().map(Nbt::new).collect(),
+                ),
+            }
+        }
+    }
+
+    impl<'a> Packet for ChunkData<'a> {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 393 {
+                0x22
+            } else {
+                0x20
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
@@ -0,0 +1,75 @@
+/// All serverbound `play` packets for protocol versions 315 and above.
+pub mod serverbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::serde::varint::VarInt;
+    use crate::{
+        packets::{error::PacketError, Packet},
+        serde::ser::Serializer,
+    };
+
+    /// The [Player Block Placement](https://wiki.vg/Protocol#Player_Block_Placement) packet for version 315 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerBlockPlacement {
+        /// Block position, packed for the protocol version.
+        pub location: i64,
+        /// The face on which the block is placed.
+        pub face: VarInt,
+        /// The hand from which the block is placed. 0: main hand, 1: off hand.
+        pub hand: VarInt,
+        /// The position of the crosshair on the block, from 0 to 1 increasing from west to east.
+        pub cursor_x: f32,
+        /// The position of the crosshair on the block, from 0 to 1 increasing from bottom to top.
+        pub cursor_y: f32,
+        /// The position of the crosshair on the block, from 0 to 1 increasing from north to south.
+        pub cursor_z: f32,
+    }
+
+    impl PlayerBlockPlacement {
+        /// create a new [PlayerBlockPlacement] packet
+        pub fn new(
+            location: i64,
+            face: VarInt,
+            hand: VarInt,
+            cursor_x: f32,
+            cursor_y: f32,
+            cursor_z: f32,
+        ) -> Self {
+            Self {
+                location,
+                face,
+                hand,
+                cursor_x,
+                cursor_y,
+                cursor_z,
+            }
+        }
+    }
+
+    impl Packet for PlayerBlockPlacement {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 393 {
+                0x29
+            } else if protocol_version >= 335 {
+                0x1F
+            } else {
+                0x1C
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
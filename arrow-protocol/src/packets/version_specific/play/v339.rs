@@ -0,0 +1,109 @@
+/// All clientbound `play` packets for protocol versions 339 and above.
+pub mod clientbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        packets::{error::PacketError, Packet},
+        serde::ser::Serializer,
+    };
+
+    /// The [Keep Alive](https://wiki.vg/Protocol#Keep_Alive_.28clientbound.29) packet for version 339 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct KeepAlive {
+        /// The id the client has to answer with in the serverbound Keep Alive packet.
+        pub keep_alive_id: i64,
+    }
+
+    impl KeepAlive {
+        /// create a new [KeepAlive] packet
+        pub fn new(keep_alive_id: i64) -> Self {
+            Self { keep_alive_id }
+        }
+    }
+
+    impl Packet for KeepAlive {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 751 {
+                0x1F
+            } else if protocol_version >= 721 {
+                0x20
+            } else if protocol_version >= 550 {
+                0x21
+            } else if protocol_version >= 477 {
+                0x20
+            } else if protocol_version >= 393 {
+                0x21
+            } else {
+                0x1F
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
+
+/// All serverbound `play` packets for protocol versions 339 and above.
+pub mod serverbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        packets::{error::PacketError, Packet},
+        serde::ser::Serializer,
+    };
+
+    /// The [Keep Alive](https://wiki.vg/Protocol#Keep_Alive_.28serverbound.29) packet for version 339 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct KeepAlive {
+        /// The id sent by the server in the clientbound Keep Alive packet.
+        pub keep_alive_id: i64,
+    }
+
+    impl KeepAlive {
+        /// create a new [KeepAlive] packet
+        pub fn new(keep_alive_id: i64) -> Self {
+            Self { keep_alive_id }
+        }
+    }
+
+    impl Packet for KeepAlive {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 735 {
+                0x10
+            } else if protocol_version >= 477 {
+                0x0F
+            } else if protocol_version >= 393 {
+                0x0E
+            } else {
+                0x0B
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
@@ -0,0 +1,177 @@
+/// All clientbound `play` packets for protocol versions 393 and above.
+pub mod clientbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        packets::{
+            error::PacketError,
+            types::LengthPrefixedVec,
+            version_specific::types::v393::{CommandNode, TabCompleteMatch},
+            Packet,
+        },
+        serde::{ser::Serializer, varint::VarInt},
+    };
+
+    /// The [Declare Commands](https://wiki.vg/Protocol#Declare_Commands) packet for version 393 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct DeclareCommands<'a> {
+        /// All nodes of the command tree.
+        #[serde(borrow)]
+        pub nodes: LengthPrefixedVec<'a, CommandNode>,
+        /// The index of the root node in `nodes`.
+        pub root_index: VarInt,
+    }
+
+    impl<'a> DeclareCommands<'a> {
+        /// create a new [DeclareCommands] packet
+        pub fn new(nodes: Vec<CommandNode>, root_index: VarInt) -> Self {
+            Self {
+                nodes: LengthPrefixedVec::new(nodes),
+                root_index,
+            }
+        }
+    }
+
+    impl<'a> Packet for DeclareCommands<'a> {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 751 {
+                0x10
+            } else if protocol_version >= 721 {
+                0x11
+            } else if protocol_version >= 550 {
+                0x12
+            } else {
+                0x11
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Tab-Complete](https://wiki.vg/Protocol#Tab-Complete_.28clientbound.29) packet for version 393 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct TabComplete<'a> {
+        /// The id of the request this packet answers.
+        pub transaction_id: VarInt,
+        /// The start of the text to replace.
+        pub start: VarInt,
+        /// The length of the text to replace.
+        pub length: VarInt,
+        /// The eligible values to insert.
+        #[serde(borrow)]
+        pub matches: LengthPrefixedVec<'a, TabCompleteMatch>,
+    }
+
+    impl<'a> TabComplete<'a> {
+        /// create a new [TabComplete] packet
+        pub fn new(
+            transaction_id: VarInt,
+            start: VarInt,
+            length: VarInt,
+            matches: Vec<TabCompleteMatch>,
+        ) -> Self {
+            Self {
+                transaction_id,
+                start,
+                length,
+                matches: LengthPrefixedVec::new(matches),
+            }
+        }
+    }
+
+    impl<'a> Packet for TabComplete<'a> {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 751 {
+                0x0F
+            } else if protocol_version >= 721 {
+                0x10
+            } else if protocol_version >= 550 {
+                0x11
+            } else {
+                0x10
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
+
+/// All serverbound `play` packets for protocol versions 393 and above.
+pub mod serverbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        packets::{error::PacketError, Packet},
+        serde::{ser::Serializer, varint::VarInt},
+    };
+
+    /// The [Tab-Complete](https://wiki.vg/Protocol#Tab-Complete_.28serverbound.29) packet for version 393 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct TabComplete {
+        /// The id the server has to answer with.
+        pub transaction_id: VarInt,
+        /// All text behind the cursor including the leading `/`.
+        pub text: String,
+    }
+
+    impl TabComplete {
+        /// create a new [TabComplete] packet
+        pub fn new(transaction_id: VarInt, text: String) -> Self {
+            Self {
+                transaction_id,
+                text,
+            }
+        }
+    }
+
+    impl Packet for TabComplete {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 477 {
+                0x06
+            } else {
+                0x05
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
@@ -3,7 +3,7 @@ pub mod clientbound {
     use serde::{Deserialize, Serialize};
 
     use crate::{
-        packets::{types::LengthPrefixedVec, version_specific::types::v351::Recipe, Packet},
+        packets::{types::LengthPrefixedVec, version_specific::types::v402::Recipe, Packet},
         serde::ser::Serializer,
     };
 
@@ -41,3 +41,79 @@ pub mod clientbound {
         }
     }
 }
+
+/// All serverbound `play` packets for protocol versions 402 and above.
+pub mod serverbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::packets::version_specific::types::v402::Slot;
+    use crate::serde::varint::VarInt;
+    use crate::{
+        packets::{error::PacketError, Packet},
+        serde::ser::Serializer,
+    };
+
+    /// The [Click Window](https://wiki.vg/Protocol#Click_Window) packet for version 402 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ClickWindow<'a> {
+        /// The id of the window which was clicked. 0 for the player inventory.
+        pub window_id: u8,
+        /// The clicked slot number.
+        pub slot: i16,
+        /// The button used in the click.
+        pub button: i8,
+        /// A unique number for the action, used for transaction handling.
+        pub action_number: i16,
+        /// Inventory operation mode.
+        pub mode: VarInt,
+        /// The clicked slot. Has to be empty (item ID = -1) for drop mode.
+        #[serde(borrow)]
+        pub clicked_item: Slot<'a>,
+    }
+
+    impl<'a> ClickWindow<'a> {
+        /// create a new [ClickWindow] packet
+        pub fn new(
+            window_id: u8,
+            slot: i16,
+            button: i8,
+            action_number: i16,
+            mode: VarInt,
+            clicked_item: Slot<'a>,
+        ) -> Self {
+            Self {
+                window_id,
+                slot,
+                button,
+                action_number,
+                mode,
+                clicked_item,
+            }
+        }
+    }
+
+    impl<'a> Packet for ClickWindow<'a> {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 477 {
+                0x09
+            } else {
+                0x08
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
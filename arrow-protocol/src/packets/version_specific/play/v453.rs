@@ -3,7 +3,7 @@ pub mod clientbound {
     use serde::{Deserialize, Serialize};
 
     use crate::{
-        packets::{types::LengthPrefixedVec, version_specific::types::v351::Recipe, Packet},
+        packets::{types::LengthPrefixedVec, version_specific::types::v453::Recipe, Packet},
         serde::ser::Serializer,
     };
 
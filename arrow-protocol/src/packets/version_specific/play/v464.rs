@@ -92,14 +92,11 @@ pub mod clientbound {
         where
             Self: Sized,
         {
-            if protocol_version < 67 {
-                return 0x41;
-            } else if protocol_version < 318 {
-                return 0x0D;
-            } else if protocol_version < 332 {
-                return 0x0E;
+            if (550..721).contains(&protocol_version) {
+                0x0E
+            } else {
+                0x0D
             }
-            0x0D
         }
 
         fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
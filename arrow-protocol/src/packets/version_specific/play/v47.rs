@@ -2,7 +2,8 @@
 pub mod clientbound {
     use serde::{Deserialize, Serialize};
 
-    use crate::packets::types::LevelType;
+    use crate::packets::types::{LengthPrefixedVec, LevelType};
+    use crate::serde::varint::VarInt;
     use crate::{
         packets::{error::PacketError, Packet},
         serde::ser::Serializer,
@@ -119,4 +120,584 @@ pub mod clientbound {
             Self::id(protocol_version)
         }
     }
+
+    /// The [Keep Alive](https://wiki.vg/Protocol#Keep_Alive_.28clientbound.29) packet for version 47 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct KeepAlive {
+        /// The id the client has to answer with in the serverbound Keep Alive packet.
+        pub keep_alive_id: VarInt,
+    }
+
+    impl KeepAlive {
+        /// create a new [KeepAlive] packet
+        pub fn new(keep_alive_id: VarInt) -> Self {
+            Self { keep_alive_id }
+        }
+    }
+
+    impl Packet for KeepAlive {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 107 {
+                0x1F
+            } else {
+                0x00
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Chat Message](https://wiki.vg/index.php?title=Protocol&oldid=16067#Chat_Message_.28clientbound.29) packet for version 47 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ChatMessage {
+        /// The message as a JSON chat component.
+        pub message: String,
+        /// 0: chat box, 1: system message, 2: game info above the hotbar
+        pub position: i8,
+    }
+
+    impl ChatMessage {
+        /// create a new [ChatMessage] packet
+        pub fn new(message: String, position: i8) -> Self {
+            Self { message, position }
+        }
+    }
+
+    impl Packet for ChatMessage {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 721 {
+                0x0E
+            } else if protocol_version >= 550 {
+                0x0F
+            } else if protocol_version >= 393 {
+                0x0E
+            } else if protocol_version >= 107 {
+                0x0F
+            } else {
+                0x02
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Tab-Complete](https://wiki.vg/index.php?title=Protocol&oldid=14204#Tab-Complete_.28clientbound.29) packet for version 47 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct TabComplete<'a> {
+        /// The eligible values to replace the last word with.
+        #[serde(borrow)]
+        pub matches: LengthPrefixedVec<'a, String>,
+    }
+
+    impl<'a> TabComplete<'a> {
+        /// create a new [TabComplete] packet
+        pub fn new(matches: Vec<String>) -> Self {
+            Self {
+                matches: LengthPrefixedVec::new(matches),
+            }
+        }
+    }
+
+    impl<'a> Packet for TabComplete<'a> {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 107 {
+                0x0E
+            } else {
+                0x3A
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Player Position And Look](https://wiki.vg/index.php?title=Protocol&oldid=7368#Player_Position_And_Look) packet for version 47 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerPositionAndLook {
+        /// The x coordinate.
+        pub x: f64,
+        /// The y coordinate of the feet.
+        pub y: f64,
+        /// The z coordinate.
+        pub z: f64,
+        /// The yaw in degrees.
+        pub yaw: f32,
+        /// The pitch in degrees.
+        pub pitch: f32,
+        /// Bit field of the values that are relative to the current ones.
+        pub flags: i8,
+    }
+
+    impl PlayerPositionAndLook {
+        /// create a new [PlayerPositionAndLook] packet
+        pub fn new(x: f64, y: f64, z: f64, yaw: f32, pitch: f32, flags: i8) -> Self {
+            Self {
+                x,
+                y,
+                z,
+                yaw,
+                pitch,
+                flags,
+            }
+        }
+    }
+
+    impl Packet for PlayerPositionAndLook {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x08
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Chunk Data](https://wiki.vg/index.php?title=Protocol&oldid=7368#Chunk_Data) packet for version 47 and above.
+    /// Sent with `full_chunk` set and an empty bit mask to unload a chunk.
+    #[derive(Serialize, Deserialize)]
+    pub struct ChunkData<'a> {
+        /// The x coordinate of the chunk.
+        pub chunk_x: i32,
+        /// The z coordinate of the chunk.
+        pub chunk_z: i32,
+        /// True if the packet contains the whole chunk including its biomes.
+        pub full_chunk: bool,
+        /// Bit mask of the sections contained in the data, starting at the bottom.
+        pub primary_bit_mask: u16,
+        /// The sections, their light and the biomes.
+        #[serde(borrow)]
+        pub data: LengthPrefixedVec<'a, u8>,
+    }
+
+    impl<'a> ChunkData<'a> {
+        /// create a new [ChunkData] packet
+        pub fn new(
+            chunk_x: i32,
+            chunk_z: i32,
+            full_chunk: bool,
+            primary_bit_mask: u16,
+            data: Vec<u8>,
+        ) -> Self {
+            Self {
+                chunk_x,
+                chunk_z,
+                full_chunk,
+                primary_bit_mask,
+                data: LengthPrefixedVec::new(data),
+            }
+        }
+    }
+
+    impl<'a> Packet for ChunkData<'a> {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x21
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
+
+/// All serverbound `play` packets for protocol versions 47 and above.
+pub mod serverbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::packets::types::RemainingBytes;
+    use crate::packets::version_specific::types::v47::Slot;
+    use crate::serde::varint::VarInt;
+    use crate::{
+        packets::{error::PacketError, Packet},
+        serde::ser::Serializer,
+    };
+
+    /// The [Keep Alive](https://wiki.vg/Protocol#Keep_Alive_.28serverbound.29) packet for version 47 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct KeepAlive {
+        /// The id sent by the server in the clientbound Keep Alive packet.
+        pub keep_alive_id: VarInt,
+    }
+
+    impl KeepAlive {
+        /// create a new [KeepAlive] packet
+        pub fn new(keep_alive_id: VarInt) -> Self {
+            Self { keep_alive_id }
+        }
+    }
+
+    impl Packet for KeepAlive {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 338 {
+                0x0B
+            } else if protocol_version >= 335 {
+                0x0C
+            } else if protocol_version >= 107 {
+                0x0B
+            } else {
+                0x00
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Client Settings](https://wiki.vg/Protocol#Client_Settings) packet for version 47 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ClientSettings {
+        /// The locale of the client, e.g. en_GB.
+        pub locale: String,
+        /// Client-side render distance, in chunks.
+        pub view_distance: i8,
+        /// 0: enabled, 1: commands only, 2: hidden.
+        pub chat_mode: i8,
+        /// Whether the client wants colored chat.
+        pub chat_colors: bool,
+        /// Bit mask of the displayed skin parts.
+        pub displayed_skin_parts: u8,
+    }
+
+    impl ClientSettings {
+        /// create a new [ClientSettings] packet
+        pub fn new(
+            locale: String,
+            view_distance: i8,
+            chat_mode: i8,
+            chat_colors: bool,
+            displayed_skin_parts: u8,
+        ) -> Self {
+            Self {
+                locale,
+                view_distance,
+                chat_mode,
+                chat_colors,
+                displayed_skin_parts,
+            }
+        }
+    }
+
+    impl Packet for ClientSettings {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x15
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Animation](https://wiki.vg/Protocol#Animation_.28serverbound.29) packet for version 47 and above. It has no fields as only the main hand exists.
+    #[derive(Serialize, Deserialize)]
+    pub struct Animation;
+
+    impl Packet for Animation {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x0A
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Player Digging](https://wiki.vg/Protocol#Player_Digging) packet for version 47 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerDigging {
+        /// The action the player is taking against the block.
+        pub status: i8,
+        /// Block position, packed for the protocol version.
+        pub location: i64,
+        /// The face being hit.
+        pub face: i8,
+    }
+
+    impl PlayerDigging {
+        /// create a new [PlayerDigging] packet
+        pub fn new(status: i8, location: i64, face: i8) -> Self {
+            Self {
+                status,
+                location,
+                face,
+            }
+        }
+    }
+
+    impl Packet for PlayerDigging {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x07
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Player Block Placement](https://wiki.vg/Protocol#Player_Block_Placement) packet for version 47 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerBlockPlacement<'a> {
+        /// Block position, packed for the protocol version. -1 when only the held item is used.
+        pub location: i64,
+        /// The face on which the block is placed. -1 when only the held item is used.
+        pub face: i8,
+        /// The held item.
+        #[serde(borrow)]
+        pub held_item: Slot<'a>,
+        /// The position of the crosshair on the block, multiplied by 16.
+        pub cursor_x: u8,
+        /// The position of the crosshair on the block, multiplied by 16.
+        pub cursor_y: u8,
+        /// The position of the crosshair on the block, multiplied by 16.
+        pub cursor_z: u8,
+    }
+
+    impl<'a> PlayerBlockPlacement<'a> {
+        /// create a new [PlayerBlockPlacement] packet
+        pub fn new(
+            location: i64,
+            face: i8,
+            held_item: Slot<'a>,
+            cursor_x: u8,
+            cursor_y: u8,
+            cursor_z: u8,
+        ) -> Self {
+            Self {
+                location,
+                face,
+                held_item,
+                cursor_x,
+                cursor_y,
+                cursor_z,
+            }
+        }
+    }
+
+    impl<'a> Packet for PlayerBlockPlacement<'a> {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x08
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Click Window](https://wiki.vg/Protocol#Click_Window) packet for version 47 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ClickWindow<'a> {
+        /// The id of the window which was clicked. 0 for the player inventory.
+        pub window_id: u8,
+        /// The clicked slot number.
+        pub slot: i16,
+        /// The button used in the click.
+        pub button: i8,
+        /// A unique number for the action, used for transaction handling.
+        pub action_number: i16,
+        /// Inventory operation mode.
+        pub mode: i8,
+        /// The clicked slot. Has to be empty (item ID = -1) for drop mode.
+        #[serde(borrow)]
+        pub clicked_item: Slot<'a>,
+    }
+
+    impl<'a> ClickWindow<'a> {
+        /// create a new [ClickWindow] packet
+        pub fn new(
+            window_id: u8,
+            slot: i16,
+            button: i8,
+            action_number: i16,
+            mode: i8,
+            clicked_item: Slot<'a>,
+        ) -> Self {
+            Self {
+                window_id,
+                slot,
+                button,
+                action_number,
+                mode,
+                clicked_item,
+            }
+        }
+    }
+
+    impl<'a> Packet for ClickWindow<'a> {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x0E
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Tab-Complete](https://wiki.vg/index.php?title=Protocol&oldid=7368#Tab-Complete) packet for version 47 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct TabComplete {
+        /// All text behind the cursor.
+        pub text: String,
+        /// Whether the player is looking at a block.
+        pub has_position: bool,
+        /// The packed position of the looked at block. Only present if `has_position` is set.
+        #[serde(default)]
+        pub looked_at_block: RemainingBytes,
+    }
+
+    impl TabComplete {
+        /// create a new [TabComplete] packet
+        pub fn new(text: String) -> Self {
+            Self {
+                text,
+                has_position: false,
+                looked_at_block: RemainingBytes::default(),
+            }
+        }
+    }
+
+    impl Packet for TabComplete {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x14
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
 }
@@ -0,0 +1,280 @@
+/// All clientbound `play` packets for protocol versions 477 and above.
+pub mod clientbound {
+    use nbt::Blob;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        packets::{
+            error::PacketError,
+            types::{LengthPrefixedVec, Nbt, RemainingBytes},
+            version_specific::types::v477::Heightmaps,
+            Packet,
+        },
+        serde::{ser::Serializer, varint::VarInt},
+    };
+
+    /// The [Chunk Data](https://wiki.vg/index.php?title=Protocol&oldid=15346#Chunk_Data) packet for version 477 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ChunkData<'a> {
+        /// The x coordinate of the chunk.
+        pub chunk_x: i32,
+        /// The z coordinate of the chunk.
+        pub chunk_z: i32,
+        /// True if the packet contains the whole chunk including its biomes.
+        pub full_chunk: bool,
+        /// Bit mask of the sections contained in the data, starting at the bottom.
+        pub primary_bit_mask: VarInt,
+        /// The heightmaps of the chunk.
+        #[serde(borrow)]
+        pub heightmaps: Nbt<'a, Heightmaps>,
+        /// The sections and the biomes.
+        #[serde(borrow)]
+        pub data: LengthPrefixedVec<'a, u8>,
+        /// The NBT data of the block entities in the chunk.
+        #[serde(borrow)]
+        pub block_entities: LengthPrefixedVec<'a, Nbt<'a, Blob>>,
+    }
+
+    impl<'a> ChunkData<'a> {
+        /// create a new [ChunkData] packet
+        pub fn new(
+            chunk_x: i32,
+            chunk_z: i32,
+            full_chunk: bool,
+            primary_bit_mask: VarInt,
+            heightmaps: Heightmaps,
+            data: Vec<u8>,
+            block_entities: Vec<Blob>,
+        ) -> Self {
+            Self {
+                chunk_x,
+                chunk_z,
+                full_chunk,
+                primary_bit_mask,
+                heightmaps: Nbt::new(heightmaps),
+                data: LengthPrefixedVec::new(data),
+                block_entities: LengthPrefixedVec::new(
+                    block_entities.into_iter().map(Nbt::new).collect(),
+                ),
+            }
+        }
+    }
+
+    impl<'a> Packet for ChunkData<'a> {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 550 {
+                0x22
+            } else {
+                0x21
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Update Light](https://wiki.vg/index.php?title=Protocol&oldid=15346#Update_Light) packet for version 477 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct UpdateLight {
+        /// The x coordinate of the chunk.
+        pub chunk_x: VarInt,
+        /// The z coordinate of the chunk.
+        pub chunk_z: VarInt,
+        /// Bit mask of the sections with sky light in `light_arrays`, starting below the chunk.
+        pub sky_light_mask: VarInt,
+        /// Bit mask of the sections with block light in `light_arrays`, starting below the chunk.
+        pub block_light_mask: VarInt,
+        /// Bit mask of the sections without any sky light, starting below the chunk.
+        pub empty_sky_light_mask: VarInt,
+        /// Bit mask of the sections without any block light, starting below the chunk.
+        pub empty_block_light_mask: VarInt,
+        /// The sky light arrays followed by the block light arrays, each prefixed by its length.
+        #[serde(default)]
+        pub light_arrays: RemainingBytes,
+    }
+
+    impl UpdateLight {
+        /// create a new [UpdateLight] packet
+        pub fn new(
+            chunk_x: VarInt,
+            chunk_z: VarInt,
+            sky_light_mask: VarInt,
+            block_light_mask: VarInt,
+            empty_sky_light_mask: VarInt,
+            empty_block_light_mask: VarInt,
+            light_arrays: Vec<u8>,
+        ) -> Self {
+            Self {
+                chunk_x,
+                chunk_z,
+                sky_light_mask,
+                block_light_mask,
+                empty_sky_light_mask,
+                empty_block_light_mask,
+                light_arrays: RemainingBytes(light_arrays),
+            }
+        }
+    }
+
+    impl Packet for UpdateLight {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 721 {
+                0x24
+            } else if protocol_version >= 550 {
+                0x25
+            } else {
+                0x24
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Update View Position](https://wiki.vg/index.php?title=Protocol&oldid=15346#Update_View_Position) packet for version 477 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct UpdateViewPosition {
+        /// The x coordinate of the chunk the player is in.
+        pub chunk_x: VarInt,
+        /// The z coordinate of the chunk the player is in.
+        pub chunk_z: VarInt,
+    }
+
+    impl UpdateViewPosition {
+        /// create a new [UpdateViewPosition] packet
+        pub fn new(chunk_x: VarInt, chunk_z: VarInt) -> Self {
+            Self { chunk_x, chunk_z }
+        }
+    }
+
+    impl Packet for UpdateViewPosition {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 721 {
+                0x40
+            } else if protocol_version >= 550 {
+                0x41
+            } else {
+                0x40
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
+
+/// All serverbound `play` packets for protocol versions 477 and above.
+pub mod serverbound {
+    use serde::{Deserialize, Serialize};
+
+    use crate::serde::varint::VarInt;
+    use crate::{
+        packets::{error::PacketError, Packet},
+        serde::ser::Serializer,
+    };
+
+    /// The [Player Block Placement](https://wiki.vg/Protocol#Player_Block_Placement) packet for version 477 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct PlayerBlockPlacement {
+        /// The hand from which the block is placed. 0: main hand, 1: off hand.
+        pub hand: VarInt,
+        /// Block position, packed for the protocol version.
+        pub location: i64,
+        /// The face on which the block is placed.
+        pub face: VarInt,
+        /// The position of the crosshair on the block, from 0 to 1 increasing from west to east.
+        pub cursor_x: f32,
+        /// The position of the crosshair on the block, from 0 to 1 increasing from bottom to top.
+        pub cursor_y: f32,
+        /// The position of the crosshair on the block, from 0 to 1 increasing from north to south.
+        pub cursor_z: f32,
+        /// True when the player's head is inside of a block.
+        pub inside_block: bool,
+    }
+
+    impl PlayerBlockPlacement {
+        /// create a new [PlayerBlockPlacement] packet
+        pub fn new(
+            hand: VarInt,
+            location: i64,
+            face: VarInt,
+            cursor_x: f32,
+            cursor_y: f32,
+            cursor_z: f32,
+            inside_block: bool,
+        ) -> Self {
+            Self {
+                hand,
+                location,
+                face,
+                cursor_x,
+                cursor_y,
+                cursor_z,
+                inside_block,
+            }
+        }
+    }
+
+    impl Packet for PlayerBlockPlacement {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 751 {
+                0x2E
+            } else if protocol_version >= 735 {
+                0x2D
+            } else {
+                0x2C
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
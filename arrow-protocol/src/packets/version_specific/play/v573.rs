@@ -0,0 +1,90 @@
+/// All clientbound `play` packets for protocol versions 573 and above.
+pub mod clientbound {
+    use nbt::Blob;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        packets::{
+            error::PacketError,
+            types::{LengthPrefixedVec, Nbt},
+            version_specific::types::{v477::Heightmaps, v573::Biomes},
+            Packet,
+        },
+        serde::{ser::Serializer, varint::VarInt},
+    };
+
+    /// The [Chunk Data](https://wiki.vg/index.php?title=Protocol&oldid=15743#Chunk_Data) packet for version 573 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ChunkData<'a> {
+        /// The x coordinate of the chunk.
+        pub chunk_x: i32,
+        /// The z coordinate of the chunk.
+        pub chunk_z: i32,
+        /// True if the packet contains the whole chunk including its biomes.
+        pub full_chunk: bool,
+        /// Bit mask of the sections contained in the data, starting at the bottom.
+        pub primary_bit_mask: VarInt,
+        /// The heightmaps of the chunk.
+        #[serde(borrow)]
+        pub heightmaps: Nbt<'a, Heightmaps>,
+        /// The biome of every 4x4x4 cube of the chunk. Only sent for full chunks.
+        pub biomes: Biomes,
+        /// The sections.
+        #[serde(borrow)]
+        pub data: LengthPrefixedVec<'a, u8>,
+        /// The NBT data of the block entities in the chunk.
+        #[serde(borrow)]
+        pub block_entities: LengthPrefixedVec<'a, Nbt<'a, Blob>>,
+    }
+
+    impl<'a> ChunkData<'a> {
+        /// create a new [ChunkData] packet
+        pub fn new(
+            chunk_x: i32,
+            chunk_z: i32,
+            primary_bit_mask: VarInt,
+            heightmaps: Heightmaps,
+            biomes: Vec<i32>,
+            data: Vec<u8>,
+            block_entities: Vec<Blob>,
+        ) -> Self {
+            Self {
+                chunk_x,
+                chunk_z,
+                full_chunk: true,
+                primary_bit_mask,
+                heightmaps: Nbt::new(heightmaps),
+                biomes: Biomes(biomes),
+                data: LengthPrefixedVec::new(data),
+                block_entities: LengthPrefixedVec::new(
+                    block_entities.into_iter().map(Nbt::new).collect(),
+                ),
+            }
+        }
+    }
+
+    impl<'a> Packet for ChunkData<'a> {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 721 {
+                0x21
+            } else {
+                0x22
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
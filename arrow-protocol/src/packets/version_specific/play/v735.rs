@@ -0,0 +1,205 @@
+/// All clientbound `play` packets for protocol versions 735 and above.
+pub mod clientbound {
+    use nbt::Blob;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use crate::{
+        packets::{
+            error::PacketError,
+            types::{LengthPrefixedVec, Nbt, RemainingBytes},
+            version_specific::types::{v477::Heightmaps, v573::Biomes},
+            Packet,
+        },
+        serde::{ser::Serializer, varint::VarInt},
+    };
+
+    /// The [Chat Message](https://wiki.vg/Protocol#Chat_Message_.28clientbound.29) packet for version 735 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ChatMessage {
+        /// The message as a JSON chat component.
+        pub message: String,
+        /// 0: chat box, 1: system message, 2: game info above the hotbar
+        pub position: i8,
+        /// The uuid of the sender as a 128 bit integer. Used by the client to hide messages of
+        /// blocked players.
+        pub sender: u128,
+    }
+
+    impl ChatMessage {
+        /// create a new [ChatMessage] packet
+        pub fn new(message: String, position: i8, sender: Uuid) -> Self {
+            Self {
+                message,
+                position,
+                sender: sender.as_u128(),
+            }
+        }
+    }
+
+    impl Packet for ChatMessage {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x0E
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Chunk Data](https://wiki.vg/index.php?title=Protocol&oldid=16067#Chunk_Data) packet for version 735 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct ChunkData<'a> {
+        /// The x coordinate of the chunk.
+        pub chunk_x: i32,
+        /// The z coordinate of the chunk.
+        pub chunk_z: i32,
+        /// True if the packet contains the whole chunk including its biomes.
+        pub full_chunk: bool,
+        /// True if the client should discard the light it has for the chunk.
+        pub ignore_old_data: bool,
+        /// Bit mask of the sections contained in the data, starting at the bottom.
+        pub primary_bit_mask: VarInt,
+        /// The heightmaps of the chunk.
+        #[serde(borrow)]
+        pub heightmaps: Nbt<'a, Heightmaps>,
+        /// The biome of every 4x4x4 cube of the chunk. Only sent for full chunks.
+        pub biomes: Biomes,
+        /// The sections.
+        #[serde(borrow)]
+        pub data: LengthPrefixedVec<'a, u8>,
+        /// The NBT data of the block entities in the chunk.
+        #[serde(borrow)]
+        pub block_entities: LengthPrefixedVec<'a, Nbt<'a, Blob>>,
+    }
+
+    impl<'a> ChunkData<'a> {
+        /// create a new [ChunkData] packet replacing the light the client has for the chunk
+        pub fn new(
+            chunk_x: i32,
+            chunk_z: i32,
+            primary_bit_mask: VarInt,
+            heightmaps: Heightmaps,
+            biomes: Vec<i32>,
+            data: Vec<u8>,
+            block_entities: Vec<Blob>,
+        ) -> Self {
+            Self {
+                chunk_x,
+                chunk_z,
+                full_chunk: true,
+                ignore_old_data: true,
+                primary_bit_mask,
+                heightmaps: Nbt::new(heightmaps),
+                biomes: Biomes(biomes),
+                data: LengthPrefixedVec::new(data),
+                block_entities: LengthPrefixedVec::new(
+                    block_entities.into_iter().map(Nbt::new).collect(),
+                ),
+            }
+        }
+    }
+
+    impl<'a> Packet for ChunkData<'a> {
+        fn id(_: i32) -> i32
+        where
+            Self: Sized,
+        {
+            0x21
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+
+    /// The [Update Light](https://wiki.vg/index.php?title=Protocol&oldid=16067#Update_Light) packet for version 735 and above.
+    #[derive(Serialize, Deserialize)]
+    pub struct UpdateLight {
+        /// The x coordinate of the chunk.
+        pub chunk_x: VarInt,
+        /// The z coordinate of the chunk.
+        pub chunk_z: VarInt,
+        /// True if the client should not recalculate the light at the edges of the chunk.
+        pub trust_edges: bool,
+        /// Bit mask of the sections with sky light in `light_arrays`, starting below the chunk.
+        pub sky_light_mask: VarInt,
+        /// Bit mask of the sections with block light in `light_arrays`, starting below the chunk.
+        pub block_light_mask: VarInt,
+        /// Bit mask of the sections without any sky light, starting below the chunk.
+        pub empty_sky_light_mask: VarInt,
+        /// Bit mask of the sections without any block light, starting below the chunk.
+        pub empty_block_light_mask: VarInt,
+        /// The sky light arrays followed by the block light arrays, each prefixed by its length.
+        #[serde(default)]
+        pub light_arrays: RemainingBytes,
+    }
+
+    impl UpdateLight {
+        /// create a new [UpdateLight] packet with light the client can trust at the edges
+        pub fn new(
+            chunk_x: VarInt,
+            chunk_z: VarInt,
+            sky_light_mask: VarInt,
+            block_light_mask: VarInt,
+            empty_sky_light_mask: VarInt,
+            empty_block_light_mask: VarInt,
+            light_arrays: Vec<u8>,
+        ) -> Self {
+            Self {
+                chunk_x,
+                chunk_z,
+                trust_edges: true,
+                sky_light_mask,
+                block_light_mask,
+                empty_sky_light_mask,
+                empty_block_light_mask,
+                light_arrays: RemainingBytes(light_arrays),
+            }
+        }
+    }
+
+    impl Packet for UpdateLight {
+        fn id(protocol_version: i32) -> i32
+        where
+            Self: Sized,
+        {
+            if protocol_version >= 751 {
+                0x23
+            } else {
+                0x24
+            }
+        }
+
+        fn data_bytes(&self) -> Result<Vec<u8>, PacketError> {
+            let mut ser = Serializer::new();
+
+            self.serialize(&mut ser)?;
+
+            Ok(ser.get_bytes())
+        }
+
+        fn self_id(&self, protocol_version: i32) -> i32 {
+            Self::id(protocol_version)
+        }
+    }
+}
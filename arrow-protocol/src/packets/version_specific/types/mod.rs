@@ -2,11 +2,17 @@
 pub mod v348;
 /// Types for version 351 and above.
 pub mod v351;
+/// Types for version 393 and above.
+pub mod v393;
 /// Types for version 402 and above.
 pub mod v402;
 /// Types for version 453 and above.
 pub mod v453;
 /// Types for versiom 47 and above.
 pub mod v47;
+/// Types for version 477 and above.
+pub mod v477;
+/// Types for version 573 and above.
+pub mod v573;
 /// Types for version 754 and above
 pub mod v754;
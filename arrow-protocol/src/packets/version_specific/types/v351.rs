@@ -1,15 +1,17 @@
 use std::marker::PhantomData;
 
-use nbt::Blob;
-use serde::{de::Visitor, Deserialize, Serialize};
+use serde::{
+    de::{Error, SeqAccess, Visitor},
+    Deserialize, Serialize,
+};
 
 use crate::{
-    packets::types::{LengthPrefixedVec, Nbt},
+    packets::types::{next_element, LengthPrefixedVec, OptionalNbt},
     serde::varint::VarInt,
 };
 
 /// A crafting recipe.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 pub struct Recipe<'a> {
     /// The recipe id.
     id: String,
@@ -20,6 +22,8 @@ pub struct Recipe<'a> {
     data: Option<RecipeData<'a>>,
 }
 
+struct RecipeVisitor<'a>(PhantomData<&'a ()>);
+
 impl<'a> Recipe<'a> {
     /// Create a new recipe.
     pub fn new(id: String, ty: String, data: Option<RecipeData<'a>>) -> Self {
@@ -44,7 +48,7 @@ impl<'a> Recipe<'a> {
 
 /// The recipe data.
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 pub enum RecipeData<'a> {
     CraftingShapeless {
         group: String,
@@ -58,7 +62,7 @@ pub enum RecipeData<'a> {
         height: VarInt,
         group: String,
         #[serde(borrow)]
-        ingridients: LengthPrefixedVec<'a, Ingridient<'a>>,
+        ingridients: Vec<Ingridient<'a>>,
         #[serde(borrow)]
         result: Slot<'a>,
     },
@@ -101,7 +105,6 @@ impl<'a> Ingridient<'a> {
 pub struct Slot<'a> {
     id: i16,
     #[serde(borrow)]
-    #[serde(flatten)]
     data: Option<SlotData<'a>>,
 }
 
@@ -112,12 +115,12 @@ struct SlotVisitor<'a>(PhantomData<&'a ()>);
 pub struct SlotData<'a> {
     count: u8,
     #[serde(borrow)]
-    nbt: Nbt<'a, Blob>,
+    nbt: OptionalNbt<'a>,
 }
 
 impl<'a> SlotData<'a> {
     /// Creates a new [`SlotData`].
-    pub fn new(count: u8, nbt: Nbt<'a, Blob>) -> Self {
+    pub fn new(count: u8, nbt: OptionalNbt<'a>) -> Self {
         Self { count, nbt }
     }
 
@@ -127,7 +130,7 @@ impl<'a> SlotData<'a> {
     }
 
     /// Get a reference to the slot data's nbt.
-    pub fn nbt(&self) -> &Nbt<'a, Blob> {
+    pub fn nbt(&self) -> &OptionalNbt<'a> {
         &self.nbt
     }
 }
@@ -175,7 +178,7 @@ impl<'a, 'de: 'a> Visitor<'de> for SlotVisitor<'a> {
             Ok(Slot::new(id, None))
         } else {
             let count: u8 = seq.next_element()?.unwrap();
-            let nbt: Nbt<'_, Blob> = seq.next_element()?.unwrap();
+            let nbt: OptionalNbt<'_> = seq.next_element()?.unwrap();
 
             Ok(Slot::new(id, Some(SlotData::new(count, nbt))))
         }
@@ -212,7 +215,7 @@ impl<'a> From<crate::packets::types::RecipeData> for RecipeData<'a> {
                 width: VarInt(width),
                 height: VarInt(height),
                 group,
-                ingridients: ingridients.into(),
+                ingridients: ingridients.into_iter().map(|i| i.into()).collect(),
                 result: result.into(),
             },
             CraftingSpecialArmorDye => Self::CraftingSpecialArmorDye,
@@ -240,12 +243,137 @@ impl<'a> From<crate::packets::types::Ingridient> for Ingridient<'a> {
 
 impl<'a> From<crate::packets::types::Slot> for Slot<'a> {
     fn from(s: crate::packets::types::Slot) -> Self {
-        if s.data.is_none() {
-            Self::new(-1, None);
+        match s.data {
+            Some(data) => Self::new(data.id, Some(SlotData::new(data.count, data.nbt.into()))),
+            None => Self::new(-1, None),
+        }
+    }
+}
+
+impl<'a> From<Slot<'a>> for crate::packets::types::Slot {
+    fn from(s: Slot<'a>) -> Self {
+        let id = s.id;
+
+        Self::new(
+            s.data.map(|data| {
+                crate::packets::types::SlotData::new(id, data.count, 0, data.nbt.into())
+            }),
+        )
+    }
+}
+
+impl<'a, 'de: 'a> Deserialize<'de> for Recipe<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(RecipeVisitor(PhantomData))
+    }
+}
+
+impl<'a, 'de: 'a> Visitor<'de> for RecipeVisitor<'a> {
+    type Value = Recipe<'a>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("recipe")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let id: String = next_element(&mut seq)?;
+        let ty: String = next_element(&mut seq)?;
+        let data = RecipeData::from_seq(&ty, &mut seq)?;
+
+        Ok(Recipe::new(id, ty, Some(data)))
+    }
+}
+
+impl<'a> From<Recipe<'a>> for crate::packets::types::Recipe {
+    fn from(r: Recipe<'a>) -> Self {
+        Self::new(r.id, r.ty, r.data.map(|v| v.into()))
+    }
+}
+
+impl<'a> RecipeData<'a> {
+    /// Reads the data of a recipe of type `ty` from `seq`.
+    fn from_seq<'de: 'a, A: SeqAccess<'de>>(ty: &str, seq: &mut A) -> Result<Self, A::Error> {
+        match ty.trim_start_matches("minecraft:") {
+            "crafting_shapeless" => Ok(Self::CraftingShapeless {
+                group: next_element(seq)?,
+                ingridients: next_element(seq)?,
+                result: next_element(seq)?,
+            }),
+            "crafting_shaped" => {
+                let width: VarInt = next_element(seq)?;
+                let height: VarInt = next_element(seq)?;
+                let group = next_element(seq)?;
+                let ingridients = (0..width.0 * height.0)
+                    .map(|_| next_element(seq))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Self::CraftingShaped {
+                    width,
+                    height,
+                    group,
+                    ingridients,
+                    result: next_element(seq)?,
+                })
+            }
+            _ => crate::packets::types::RecipeData::special(ty)
+                .map(|data| data.into())
+                .ok_or_else(|| A::Error::custom(format!("Unsupported recipe type: {}", ty))),
         }
+    }
+}
+
+impl<'a> From<RecipeData<'a>> for crate::packets::types::RecipeData {
+    fn from(r: RecipeData<'a>) -> Self {
+        use RecipeData::*;
 
-        let data = s.data.unwrap();
+        match r {
+            CraftingShapeless {
+                group,
+                ingridients,
+                result,
+            } => Self::CraftingShapeless {
+                group,
+                ingridients: ingridients.1.into_iter().map(|i| i.into()).collect(),
+                result: result.into(),
+            },
+            CraftingShaped {
+                width,
+                height,
+                group,
+                ingridients,
+                result,
+            } => Self::CraftingShaped {
+                width: width.0,
+                height: height.0,
+                group,
+                ingridients: ingridients.into_iter().map(|i| i.into()).collect(),
+                result: result.into(),
+            },
+            CraftingSpecialArmorDye => Self::CraftingSpecialArmorDye,
+            CraftingSpecialBookCloning => Self::CraftingSpecialBookCloning,
+            CraftingSpecialMapCloning => Self::CraftingSpecialMapCloning,
+            CraftingSpecialMapExtending => Self::CraftingSpecialMapExtending,
+            CraftingSpecialFireworkRocket => Self::CraftingSpecialFireworkRocket,
+            CraftingSpecialFireworkStar => Self::CraftingSpecialFireworkStar,
+            CraftingSpecialFireworkStarFade => Self::CraftingSpecialFireworkStarFade,
+            CraftingSpecialRepairItem => Self::CraftingSpecialRepairItem,
+            CraftingSpecialTippedArrow => Self::CraftingSpecialTippedArrow,
+            CraftingSpecialBannerDuplicate => Self::CraftingSpecialBannerDuplicate,
+            CraftingSpecialBannerAddPattern => Self::CraftingSpecialBannerAddPattern,
+            CraftingSpecialShieldDecoration => Self::CraftingSpecialShieldDecoration,
+            CraftingSpecialShulkerBoxColoring => Self::CraftingSpecialShulkerBoxColoring,
+        }
+    }
+}
 
-        Self::new(data.id, Some(SlotData::new(data.count, Nbt::new(data.nbt))))
+impl<'a> From<Ingridient<'a>> for crate::packets::types::Ingridient {
+    fn from(i: Ingridient<'a>) -> Self {
+        Self::new(i.items.1.into_iter().map(|s| s.into()).collect())
     }
 }
@@ -0,0 +1,304 @@
+use std::convert::TryFrom;
+
+use serde::{
+    de::{Error, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Serialize,
+};
+
+use crate::{
+    packets::types::{next_element, ArgumentParser, CommandNodeKind, StringKind},
+    serde::varint::VarInt,
+};
+
+/// The mask of the node type in the flags of a command node.
+const NODE_TYPE_MASK: i8 = 0x03;
+const NODE_TYPE_ROOT: i8 = 0x00;
+const NODE_TYPE_LITERAL: i8 = 0x01;
+const NODE_TYPE_ARGUMENT: i8 = 0x02;
+const IS_EXECUTABLE: i8 = 0x04;
+const HAS_REDIRECT: i8 = 0x08;
+const HAS_SUGGESTIONS: i8 = 0x10;
+
+/// Set in the flags of number parsers if a minimum is present.
+const HAS_MIN: i8 = 0x01;
+/// Set in the flags of number parsers if a maximum is present.
+const HAS_MAX: i8 = 0x02;
+
+/// Set in the flags of the entity parser if only a single entity is allowed.
+const SINGLE_ENTITY: i8 = 0x01;
+/// Set in the flags of the entity parser if only players are allowed.
+const ONLY_PLAYERS: i8 = 0x02;
+
+/// A [command node](https://wiki.vg/Command_Data) serialized with its flags, children indices and
+/// parser properties.
+pub struct CommandNode(pub crate::packets::types::CommandNode);
+
+struct CommandNodeVisitor;
+
+/// A match of the Tab-Complete packet. Tooltips are never sent and skipped when reading.
+pub struct TabCompleteMatch(pub String);
+
+struct TabCompleteMatchVisitor;
+
+impl Serialize for CommandNode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let node = &self.0;
+        let mut flags = match node.kind {
+            CommandNodeKind::Root => NODE_TYPE_ROOT,
+            CommandNodeKind::Literal(_) => NODE_TYPE_LITERAL,
+            CommandNodeKind::Argument { .. } => NODE_TYPE_ARGUMENT,
+        };
+
+        if node.executable {
+            flags |= IS_EXECUTABLE;
+        }
+        if node.redirect.is_some() {
+            flags |= HAS_REDIRECT;
+        }
+        if node.suggestions.is_some() {
+            flags |= HAS_SUGGESTIONS;
+        }
+
+        let mut seq = serializer.serialize_seq(None)?;
+
+        seq.serialize_element(&flags)?;
+        seq.serialize_element(&VarInt(node.children.len() as i32))?;
+
+        for child in &node.children {
+            seq.serialize_element(&VarInt(*child))?;
+        }
+
+        if let Some(redirect) = node.redirect {
+            seq.serialize_element(&VarInt(redirect))?;
+        }
+
+        match &node.kind {
+            CommandNodeKind::Root => {}
+            CommandNodeKind::Literal(name) => seq.serialize_element(name)?,
+            CommandNodeKind::Argument { name, parser } => {
+                seq.serialize_element(name)?;
+                seq.serialize_element(parser.identifier())?;
+
+                match *parser {
+                    ArgumentParser::Integer { min, max } => {
+                        seq.serialize_element(&number_flags(min.is_some(), max.is_some()))?;
+                        seq.serialize_element(&min)?;
+                        seq.serialize_element(&max)?;
+                    }
+                    ArgumentParser::Double { min, max } => {
+                        seq.serialize_element(&number_flags(min.is_some(), max.is_some()))?;
+                        seq.serialize_element(&min)?;
+                        seq.serialize_element(&max)?;
+                    }
+                    ArgumentParser::String(kind) => seq.serialize_element(&VarInt(kind as i32))?,
+                    ArgumentParser::Entity {
+                        single,
+                        only_players,
+                    } => {
+                        let mut flags = 0;
+
+                        if single {
+                            flags |= SINGLE_ENTITY;
+                        }
+                        if only_players {
+                            flags |= ONLY_PLAYERS;
+                        }
+
+                        seq.serialize_element(&flags)?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(suggestions) = &node.suggestions {
+            seq.serialize_element(suggestions)?;
+        }
+
+        seq.end()
+    }
+}
+
+/// Returns the flags of a number parser.
+fn number_flags(has_min: bool, has_max: bool) -> i8 {
+    let mut flags = 0;
+
+    if has_min {
+        flags |= HAS_MIN;
+    }
+    if has_max {
+        flags |= HAS_MAX;
+    }
+
+    flags
+}
+
+impl<'de> Deserialize<'de> for CommandNode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(CommandNodeVisitor)
+    }
+}
+
+impl<'de> Visitor<'de> for CommandNodeVisitor {
+    type Value = CommandNode;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("command node")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let flags: i8 = next_element(&mut seq)?;
+        let count: VarInt = next_element(&mut seq)?;
+        let children = (0..count.0)
+            .map(|_| next_element(&mut seq).map(|c: VarInt| c.0))
+            .collect::<Result<_, _>>()?;
+        let redirect = if flags & HAS_REDIRECT != 0 {
+            Some(next_element::<VarInt, _>(&mut seq)?.0)
+        } else {
+            None
+        };
+
+        let kind = match flags & NODE_TYPE_MASK {
+            NODE_TYPE_ROOT => CommandNodeKind::Root,
+            NODE_TYPE_LITERAL => CommandNodeKind::Literal(next_element(&mut seq)?),
+            NODE_TYPE_ARGUMENT => {
+                let name = next_element(&mut seq)?;
+                let identifier: String = next_element(&mut seq)?;
+
+                CommandNodeKind::Argument {
+                    name,
+                    parser: parser_from_seq(&identifier, &mut seq)?,
+                }
+            }
+            t => return Err(A::Error::custom(format!("Invalid command node type {}", t))),
+        };
+
+        let suggestions = if flags & HAS_SUGGESTIONS != 0 {
+            Some(next_element(&mut seq)?)
+        } else {
+            None
+        };
+
+        Ok(CommandNode(crate::packets::types::CommandNode {
+            kind,
+            executable: flags & IS_EXECUTABLE != 0,
+            children,
+            redirect,
+            suggestions,
+        }))
+    }
+}
+
+/// Reads the properties of the parser `identifier` from `seq`.
+fn parser_from_seq<'de, A: SeqAccess<'de>>(
+    identifier: &str,
+    seq: &mut A,
+) -> Result<ArgumentParser, A::Error> {
+    match identifier {
+        "brigadier:bool" => Ok(ArgumentParser::Bool),
+        "brigadier:integer" => {
+            let flags: i8 = next_element(seq)?;
+            let min = optional_element(seq, flags & HAS_MIN != 0)?;
+            let max = optional_element(seq, flags & HAS_MAX != 0)?;
+
+            Ok(ArgumentParser::Integer { min, max })
+        }
+        "brigadier:double" => {
+            let flags: i8 = next_element(seq)?;
+            let min = optional_element(seq, flags & HAS_MIN != 0)?;
+            let max = optional_element(seq, flags & HAS_MAX != 0)?;
+
+            Ok(ArgumentParser::Double { min, max })
+        }
+        "brigadier:string" => {
+            let kind: VarInt = next_element(seq)?;
+
+            StringKind::try_from(kind.0)
+                .map(ArgumentParser::String)
+                .map_err(A::Error::custom)
+        }
+        "minecraft:entity" => {
+            let flags: i8 = next_element(seq)?;
+
+            Ok(ArgumentParser::Entity {
+                single: flags & SINGLE_ENTITY != 0,
+                only_players: flags & ONLY_PLAYERS != 0,
+            })
+        }
+        "minecraft:game_profile" => Ok(ArgumentParser::GameProfile),
+        "minecraft:block_pos" => Ok(ArgumentParser::BlockPos),
+        "minecraft:vec3" => Ok(ArgumentParser::Vec3),
+        "minecraft:message" => Ok(ArgumentParser::Message),
+        _ => Err(A::Error::custom(format!(
+            "Unsupported argument parser: {}",
+            identifier
+        ))),
+    }
+}
+
+/// Reads the next element of `seq` if `present` is set.
+fn optional_element<'de, T: Deserialize<'de>, A: SeqAccess<'de>>(
+    seq: &mut A,
+    present: bool,
+) -> Result<Option<T>, A::Error> {
+    if present {
+        next_element(seq).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+impl Serialize for TabCompleteMatch {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut seq = serializer.serialize_seq(None)?;
+
+        seq.serialize_element(&self.0)?;
+        seq.serialize_element(&false)?;
+
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for TabCompleteMatch {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(TabCompleteMatchVisitor)
+    }
+}
+
+impl<'de> Visitor<'de> for TabCompleteMatchVisitor {
+    type Value = TabCompleteMatch;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("tab complete match")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let text = next_element(&mut seq)?;
+        let has_tooltip: bool = next_element(&mut seq)?;
+
+        if has_tooltip {
+            next_element::<String, _>(&mut seq)?;
+        }
+
+        Ok(TabCompleteMatch(text))
+    }
+}
@@ -1,16 +1,20 @@
 use std::marker::PhantomData;
 
-use nbt::Blob;
-use serde::{de::Visitor, Deserialize, Serialize};
+use serde::{
+    de::{Error, SeqAccess, Visitor},
+    Deserialize, Serialize,
+};
 
-use crate::{packets::types::Nbt, serde::varint::VarInt};
+use crate::{
+    packets::types::{next_element, LengthPrefixedVec, OptionalNbt},
+    serde::varint::VarInt,
+};
 
 /// The [Slot](https://wiki.vg/Slot) data type.
 #[derive(Serialize)]
 pub struct Slot<'a> {
     present: bool,
     #[serde(borrow)]
-    #[serde(flatten)]
     data: Option<SlotData<'a>>,
 }
 
@@ -22,12 +26,12 @@ pub struct SlotData<'a> {
     id: VarInt,
     count: u8,
     #[serde(borrow)]
-    nbt: Nbt<'a, Blob>,
+    nbt: OptionalNbt<'a>,
 }
 
 impl<'a> SlotData<'a> {
     /// Create a [`SlotData`] struct.
-    pub fn new(id: VarInt, count: u8, nbt: Nbt<'a, Blob>) -> Self {
+    pub fn new(id: VarInt, count: u8, nbt: OptionalNbt<'a>) -> Self {
         Self { id, count, nbt }
     }
 
@@ -42,7 +46,7 @@ impl<'a> SlotData<'a> {
     }
 
     /// Get a mutable reference to the slot data's nbt.
-    pub fn nbt_mut(&mut self) -> &mut Nbt<'a, Blob> {
+    pub fn nbt_mut(&mut self) -> &mut OptionalNbt<'a> {
         &mut self.nbt
     }
 }
@@ -91,7 +95,7 @@ impl<'a, 'de: 'a> Visitor<'de> for SlotVisitor<'a> {
         } else {
             let id: VarInt = seq.next_element()?.unwrap();
             let count: u8 = seq.next_element()?.unwrap();
-            let nbt: Nbt<'_, Blob> = seq.next_element()?.unwrap();
+            let nbt: OptionalNbt<'_> = seq.next_element()?.unwrap();
 
             Ok(Slot::new(present, Some(SlotData::new(id, count, nbt))))
         }
@@ -100,19 +104,289 @@ impl<'a, 'de: 'a> Visitor<'de> for SlotVisitor<'a> {
 
 impl<'a> From<crate::packets::types::Slot> for Slot<'a> {
     fn from(s: crate::packets::types::Slot) -> Self {
-        if s.data.is_none() {
-            Self::new(false, None);
+        match s.data {
+            Some(data) => Self::new(
+                true,
+                Some(SlotData::new(
+                    VarInt(data.id as i32),
+                    data.count,
+                    data.nbt.into(),
+                )),
+            ),
+            None => Self::new(false, None),
         }
+    }
+}
+
+impl<'a> From<Slot<'a>> for crate::packets::types::Slot {
+    fn from(s: Slot<'a>) -> Self {
+        Self::new(s.data.map(|data| {
+            crate::packets::types::SlotData::new(data.id.0 as i16, data.count, 0, data.nbt.into())
+        }))
+    }
+}
+
+/// A crafting recipe.
+#[derive(Serialize)]
+pub struct Recipe<'a> {
+    /// The recipe id.
+    id: String,
+    /// The type of the recipe.
+    ty: String,
+    /// The data for the recipe.
+    #[serde(borrow)]
+    data: Option<RecipeData<'a>>,
+}
+
+struct RecipeVisitor<'a>(PhantomData<&'a ()>);
+
+impl<'a> Recipe<'a> {
+    /// Create a new recipe.
+    pub fn new(id: String, ty: String, data: Option<RecipeData<'a>>) -> Self {
+        Self { id, ty, data }
+    }
+
+    /// Get a reference to the recipe's id.
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    /// Get a reference to the recipe's ty.
+    pub fn ty(&self) -> &str {
+        self.ty.as_str()
+    }
+
+    /// Get a mutable reference to the recipe's data.
+    pub fn data(&self) -> &Option<RecipeData<'a>> {
+        &self.data
+    }
+}
+
+/// The recipe data.
+#[allow(missing_docs)]
+#[derive(Serialize)]
+pub enum RecipeData<'a> {
+    CraftingShapeless {
+        group: String,
+        #[serde(borrow)]
+        ingridients: LengthPrefixedVec<'a, Ingridient<'a>>,
+        #[serde(borrow)]
+        result: Slot<'a>,
+    },
+    CraftingShaped {
+        width: VarInt,
+        height: VarInt,
+        group: String,
+        #[serde(borrow)]
+        ingridients: Vec<Ingridient<'a>>,
+        #[serde(borrow)]
+        result: Slot<'a>,
+    },
+    CraftingSpecialArmorDye,
+    CraftingSpecialBookCloning,
+    CraftingSpecialMapCloning,
+    CraftingSpecialMapExtending,
+    CraftingSpecialFireworkRocket,
+    CraftingSpecialFireworkStar,
+    CraftingSpecialFireworkStarFade,
+    CraftingSpecialRepairItem,
+    CraftingSpecialTippedArrow,
+    CraftingSpecialBannerDuplicate,
+    CraftingSpecialBannerAddPattern,
+    CraftingSpecialShieldDecoration,
+    CraftingSpecialShulkerBoxColoring,
+}
+
+/// A crafting ingridient.
+#[derive(Serialize, Deserialize)]
+pub struct Ingridient<'a> {
+    #[serde(borrow)]
+    items: LengthPrefixedVec<'a, Slot<'a>>,
+}
+
+impl<'a> Ingridient<'a> {
+    /// Create a new [`Ingridient`].
+    pub fn new(items: LengthPrefixedVec<'a, Slot<'a>>) -> Self {
+        Self { items }
+    }
+
+    /// Get a mutable reference to the ingridient's items.
+    pub fn items_mut(&mut self) -> &mut LengthPrefixedVec<'a, Slot<'a>> {
+        &mut self.items
+    }
+}
+
+impl<'a> From<crate::packets::types::Recipe> for Recipe<'a> {
+    fn from(r: crate::packets::types::Recipe) -> Self {
+        Self::new(r.id, r.ty, r.data.map(|v| v.into()))
+    }
+}
+
+impl<'a> From<crate::packets::types::RecipeData> for RecipeData<'a> {
+    fn from(r: crate::packets::types::RecipeData) -> Self {
+        use crate::packets::types::RecipeData::*;
+
+        match r {
+            CraftingShapeless {
+                group,
+                ingridients,
+                result,
+            } => Self::CraftingShapeless {
+                group,
+                ingridients: ingridients.into(),
+                result: result.into(),
+            },
+            CraftingShaped {
+                width,
+                height,
+                group,
+                ingridients,
+                result,
+            } => Self::CraftingShaped {
+                width: VarInt(width),
+                height: VarInt(height),
+                group,
+                ingridients: ingridients.into_iter().map(|i| i.into()).collect(),
+                result: result.into(),
+            },
+            CraftingSpecialArmorDye => Self::CraftingSpecialArmorDye,
+            CraftingSpecialBookCloning => Self::CraftingSpecialBookCloning,
+            CraftingSpecialMapCloning => Self::CraftingSpecialMapCloning,
+            CraftingSpecialMapExtending => Self::CraftingSpecialMapExtending,
+            CraftingSpecialFireworkRocket => Self::CraftingSpecialFireworkRocket,
+            CraftingSpecialFireworkStar => Self::CraftingSpecialFireworkStar,
+            CraftingSpecialFireworkStarFade => Self::CraftingSpecialFireworkStarFade,
+            CraftingSpecialRepairItem => Self::CraftingSpecialRepairItem,
+            CraftingSpecialTippedArrow => Self::CraftingSpecialTippedArrow,
+            CraftingSpecialBannerDuplicate => Self::CraftingSpecialBannerDuplicate,
+            CraftingSpecialBannerAddPattern => Self::CraftingSpecialBannerAddPattern,
+            CraftingSpecialShieldDecoration => Self::CraftingSpecialShieldDecoration,
+            CraftingSpecialShulkerBoxColoring => Self::CraftingSpecialShulkerBoxColoring,
+        }
+    }
+}
+
+impl<'a> From<crate::packets::types::Ingridient> for Ingridient<'a> {
+    fn from(i: crate::packets::types::Ingridient) -> Self {
+        Self::new(i.items.into())
+    }
+}
+
+impl<'a, 'de: 'a> Deserialize<'de> for Recipe<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(RecipeVisitor(PhantomData))
+    }
+}
+
+impl<'a, 'de: 'a> Visitor<'de> for RecipeVisitor<'a> {
+    type Value = Recipe<'a>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("recipe")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let id: String = next_element(&mut seq)?;
+        let ty: String = next_element(&mut seq)?;
+        let data = RecipeData::from_seq(&ty, &mut seq)?;
+
+        Ok(Recipe::new(id, ty, Some(data)))
+    }
+}
+
+impl<'a> From<Recipe<'a>> for crate::packets::types::Recipe {
+    fn from(r: Recipe<'a>) -> Self {
+        Self::new(r.id, r.ty, r.data.map(|v| v.into()))
+    }
+}
 
-        let data = s.data.unwrap();
+impl<'a> RecipeData<'a> {
+    /// Reads the data of a recipe of type `ty` from `seq`.
+    pub(crate) fn from_seq<'de: 'a, A: SeqAccess<'de>>(
+        ty: &str,
+        seq: &mut A,
+    ) -> Result<Self, A::Error> {
+        match ty.trim_start_matches("minecraft:") {
+            "crafting_shapeless" => Ok(Self::CraftingShapeless {
+                group: next_element(seq)?,
+                ingridients: next_element(seq)?,
+                result: next_element(seq)?,
+            }),
+            "crafting_shaped" => {
+                let width: VarInt = next_element(seq)?;
+                let height: VarInt = next_element(seq)?;
+                let group = next_element(seq)?;
+                let ingridients = (0..width.0 * height.0)
+                    .map(|_| next_element(seq))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Self::CraftingShaped {
+                    width,
+                    height,
+                    group,
+                    ingridients,
+                    result: next_element(seq)?,
+                })
+            }
+            _ => crate::packets::types::RecipeData::special(ty)
+                .map(|data| data.into())
+                .ok_or_else(|| A::Error::custom(format!("Unsupported recipe type: {}", ty))),
+        }
+    }
+}
+
+impl<'a> From<RecipeData<'a>> for crate::packets::types::RecipeData {
+    fn from(r: RecipeData<'a>) -> Self {
+        use RecipeData::*;
+
+        match r {
+            CraftingShapeless {
+                group,
+                ingridients,
+                result,
+            } => Self::CraftingShapeless {
+                group,
+                ingridients: ingridients.1.into_iter().map(|i| i.into()).collect(),
+                result: result.into(),
+            },
+            CraftingShaped {
+                width,
+                height,
+                group,
+                ingridients,
+                result,
+            } => Self::CraftingShaped {
+                width: width.0,
+                height: height.0,
+                group,
+                ingridients: ingridients.into_iter().map(|i| i.into()).collect(),
+                result: result.into(),
+            },
+            CraftingSpecialArmorDye => Self::CraftingSpecialArmorDye,
+            CraftingSpecialBookCloning => Self::CraftingSpecialBookCloning,
+            CraftingSpecialMapCloning => Self::CraftingSpecialMapCloning,
+            CraftingSpecialMapExtending => Self::CraftingSpecialMapExtending,
+            CraftingSpecialFireworkRocket => Self::CraftingSpecialFireworkRocket,
+            CraftingSpecialFireworkStar => Self::CraftingSpecialFireworkStar,
+            CraftingSpecialFireworkStarFade => Self::CraftingSpecialFireworkStarFade,
+            CraftingSpecialRepairItem => Self::CraftingSpecialRepairItem,
+            CraftingSpecialTippedArrow => Self::CraftingSpecialTippedArrow,
+            CraftingSpecialBannerDuplicate => Self::CraftingSpecialBannerDuplicate,
+            CraftingSpecialBannerAddPattern => Self::CraftingSpecialBannerAddPattern,
+            CraftingSpecialShieldDecoration => Self::CraftingSpecialShieldDecoration,
+            CraftingSpecialShulkerBoxColoring => Self::CraftingSpecialShulkerBoxColoring,
+        }
+    }
+}
 
-        Self::new(
-            true,
-            Some(SlotData::new(
-                VarInt(data.id as i32),
-                data.count,
-                Nbt::new(data.nbt),
-            )),
-        )
+impl<'a> From<Ingridient<'a>> for crate::packets::types::Ingridient {
+    fn from(i: Ingridient<'a>) -> Self {
+        Self::new(i.items.1.into_iter().map(|s| s.into()).collect())
     }
 }
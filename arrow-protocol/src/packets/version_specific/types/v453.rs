@@ -1,11 +1,16 @@
-use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
 
-use crate::{packets::types::LengthPrefixedVec, serde::varint::VarInt};
+use serde::{
+    de::{SeqAccess, Visitor},
+    Deserialize, Serialize,
+};
 
-use super::v402::Slot;
+use crate::packets::types::next_element;
+
+use super::v402::RecipeData;
 
 /// A crafting recipe.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 pub struct Recipe<'a> {
     /// The type of the recipe.
     ty: String,
@@ -16,6 +21,8 @@ pub struct Recipe<'a> {
     data: Option<RecipeData<'a>>,
 }
 
+struct RecipeVisitor<'a>(PhantomData<&'a ()>);
+
 impl<'a> Recipe<'a> {
     /// Create a new recipe.
     pub fn new(id: String, ty: String, data: Option<RecipeData<'a>>) -> Self {
@@ -38,112 +45,42 @@ impl<'a> Recipe<'a> {
     }
 }
 
-/// The recipe data.
-#[allow(missing_docs)]
-#[derive(Serialize, Deserialize)]
-pub enum RecipeData<'a> {
-    CraftingShapeless {
-        group: String,
-        #[serde(borrow)]
-        ingridients: LengthPrefixedVec<'a, Ingridient<'a>>,
-        #[serde(borrow)]
-        result: Slot<'a>,
-    },
-    CraftingShaped {
-        width: VarInt,
-        height: VarInt,
-        group: String,
-        #[serde(borrow)]
-        ingridients: LengthPrefixedVec<'a, Ingridient<'a>>,
-        #[serde(borrow)]
-        result: Slot<'a>,
-    },
-    CraftingSpecialArmorDye,
-    CraftingSpecialBookCloning,
-    CraftingSpecialMapCloning,
-    CraftingSpecialMapExtending,
-    CraftingSpecialFireworkRocket,
-    CraftingSpecialFireworkStar,
-    CraftingSpecialFireworkStarFade,
-    CraftingSpecialRepairItem,
-    CraftingSpecialTippedArrow,
-    CraftingSpecialBannerDuplicate,
-    CraftingSpecialBannerAddPattern,
-    CraftingSpecialShieldDecoration,
-    CraftingSpecialShulkerBoxColoring,
+impl<'a> From<crate::packets::types::Recipe> for Recipe<'a> {
+    fn from(r: crate::packets::types::Recipe) -> Self {
+        Self::new(r.id, r.ty, r.data.map(|v| v.into()))
+    }
 }
 
-/// A crafting ingridient.
-#[derive(Serialize, Deserialize)]
-pub struct Ingridient<'a> {
-    #[serde(borrow)]
-    items: LengthPrefixedVec<'a, Slot<'a>>,
+impl<'a, 'de: 'a> Deserialize<'de> for Recipe<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(RecipeVisitor(PhantomData))
+    }
 }
 
-impl<'a> Ingridient<'a> {
-    /// Create a new [`Ingridient`].
-    pub fn new(items: LengthPrefixedVec<'a, Slot<'a>>) -> Self {
-        Self { items }
-    }
+impl<'a, 'de: 'a> Visitor<'de> for RecipeVisitor<'a> {
+    type Value = Recipe<'a>;
 
-    /// Get a mutable reference to the ingridient's items.
-    pub fn items_mut(&mut self) -> &mut LengthPrefixedVec<'a, Slot<'a>> {
-        &mut self.items
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("recipe")
     }
-}
 
-impl<'a> From<crate::packets::types::Recipe> for Recipe<'a> {
-    fn from(r: crate::packets::types::Recipe) -> Self {
-        Self::new(r.id, r.ty, r.data.map(|v| v.into()))
-    }
-}
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let ty: String = next_element(&mut seq)?;
+        let id: String = next_element(&mut seq)?;
+        let data = RecipeData::from_seq(&ty, &mut seq)?;
 
-impl<'a> From<crate::packets::types::RecipeData> for RecipeData<'a> {
-    fn from(r: crate::packets::types::RecipeData) -> Self {
-        use crate::packets::types::RecipeData::*;
-
-        match r {
-            CraftingShapeless {
-                group,
-                ingridients,
-                result,
-            } => Self::CraftingShapeless {
-                group,
-                ingridients: ingridients.into(),
-                result: result.into(),
-            },
-            CraftingShaped {
-                width,
-                height,
-                group,
-                ingridients,
-                result,
-            } => Self::CraftingShaped {
-                width: VarInt(width),
-                height: VarInt(height),
-                group,
-                ingridients: ingridients.into(),
-                result: result.into(),
-            },
-            CraftingSpecialArmorDye => Self::CraftingSpecialArmorDye,
-            CraftingSpecialBookCloning => Self::CraftingSpecialBookCloning,
-            CraftingSpecialMapCloning => Self::CraftingSpecialMapCloning,
-            CraftingSpecialMapExtending => Self::CraftingSpecialMapExtending,
-            CraftingSpecialFireworkRocket => Self::CraftingSpecialFireworkRocket,
-            CraftingSpecialFireworkStar => Self::CraftingSpecialFireworkStar,
-            CraftingSpecialFireworkStarFade => Self::CraftingSpecialFireworkStarFade,
-            CraftingSpecialRepairItem => Self::CraftingSpecialRepairItem,
-            CraftingSpecialTippedArrow => Self::CraftingSpecialTippedArrow,
-            CraftingSpecialBannerDuplicate => Self::CraftingSpecialBannerDuplicate,
-            CraftingSpecialBannerAddPattern => Self::CraftingSpecialBannerAddPattern,
-            CraftingSpecialShieldDecoration => Self::CraftingSpecialShieldDecoration,
-            CraftingSpecialShulkerBoxColoring => Self::CraftingSpecialShulkerBoxColoring,
-        }
+        Ok(Recipe::new(id, ty, Some(data)))
     }
 }
 
-impl<'a> From<crate::packets::types::Ingridient> for Ingridient<'a> {
-    fn from(i: crate::packets::types::Ingridient) -> Self {
-        Self::new(i.items.into())
+impl<'a> From<Recipe<'a>> for crate::packets::types::Recipe {
+    fn from(r: Recipe<'a>) -> Self {
+        Self::new(r.id, r.ty, r.data.map(|v| v.into()))
     }
 }
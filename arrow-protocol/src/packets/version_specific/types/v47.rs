@@ -1,13 +1,12 @@
-use std::marker::PhantomData;
+use std::{convert::TryFrom, marker::PhantomData};
 
-use nbt::Blob;
 use serde::{de::Visitor, Deserialize, Serialize};
 
-use crate::packets::types::Nbt;
+use crate::packets::{error::PacketError, types::OptionalNbt};
 
 /// Dimension type as int enum
 #[repr(i32)]
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Dimension {
     /// nehter dimension
     Nether = -1,
@@ -17,12 +16,24 @@ pub enum Dimension {
     End = 1,
 }
 
+impl TryFrom<i32> for Dimension {
+    type Error = PacketError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            -1 => Ok(Self::Nether),
+            0 => Ok(Self::Overworld),
+            1 => Ok(Self::End),
+            v => Err(PacketError::SerdeError(format!("Invalid dimension {}", v))),
+        }
+    }
+}
+
 /// The [Slot](https://wiki.vg/Slot) data type.
 #[derive(Serialize)]
 pub struct Slot<'a> {
     id: i16,
     #[serde(borrow)]
-    #[serde(flatten)]
     data: Option<SlotData<'a>>,
 }
 
@@ -34,12 +45,12 @@ pub struct SlotData<'a> {
     count: u8,
     damage: i16,
     #[serde(borrow)]
-    nbt: Nbt<'a, Blob>,
+    nbt: OptionalNbt<'a>,
 }
 
 impl<'a> SlotData<'a> {
     /// Creates a new [`SlotData`].
-    pub fn new(count: u8, damage: i16, nbt: Nbt<'a, Blob>) -> Self {
+    pub fn new(count: u8, damage: i16, nbt: OptionalNbt<'a>) -> Self {
         Self { count, damage, nbt }
     }
 
@@ -54,7 +65,7 @@ impl<'a> SlotData<'a> {
     }
 
     /// Get a reference to the slot data's nbt.
-    pub fn nbt(&self) -> &Nbt<'a, Blob> {
+    pub fn nbt(&self) -> &OptionalNbt<'a> {
         &self.nbt
     }
 }
@@ -103,9 +114,19 @@ impl<'a, 'de: 'a> Visitor<'de> for SlotVisitor<'a> {
         } else {
             let count: u8 = seq.next_element()?.unwrap();
             let damage: i16 = seq.next_element()?.unwrap();
-            let nbt: Nbt<'_, Blob> = seq.next_element()?.unwrap();
+            let nbt: OptionalNbt<'_> = seq.next_element()?.unwrap();
 
             Ok(Slot::new(id, Some(SlotData::new(count, damage, nbt))))
         }
     }
 }
+
+impl<'a> From<Slot<'a>> for crate::packets::types::Slot {
+    fn from(s: Slot<'a>) -> Self {
+        let id = s.id;
+
+        Self::new(s.data.map(|data| {
+            crate::packets::types::SlotData::new(id, data.count, data.damage, data.nbt.into())
+        }))
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// The heightmaps sent in the Chunk Data packet for version 477 and above.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Heightmaps {
+    /// The height above the highest block that blocks motion or contains a fluid of every
+    /// column, packed into longs with 9 bits per column.
+    #[serde(rename = "MOTION_BLOCKING", serialize_with = "nbt::i64_array")]
+    pub motion_blocking: Vec<i64>,
+}
@@ -0,0 +1,51 @@
+use serde::{
+    de::{SeqAccess, Visitor},
+    Deserialize, Serialize,
+};
+
+use crate::{chunk::BIOME_COUNT, packets::types::next_element};
+
+/// The biomes of a chunk sent in the Chunk Data packet for versions 573 to 750. There are always
+/// [`BIOME_COUNT`] biomes, so they are not prefixed by their length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Biomes(pub Vec<i32>);
+struct BiomesVisitor;
+
+impl Serialize for Biomes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Biomes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(BiomesVisitor)
+    }
+}
+
+impl<'de> Visitor<'de> for BiomesVisitor {
+    type Value = Biomes;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("expected seq")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut biomes = Vec::with_capacity(BIOME_COUNT);
+
+        for _ in 0..BIOME_COUNT {
+            biomes.push(next_element(&mut seq)?);
+        }
+
+        Ok(Biomes(biomes))
+    }
+}
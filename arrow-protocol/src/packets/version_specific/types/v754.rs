@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct DimensionCodec {
     #[serde(rename = "minecraft:dimension_type")]
     pub dimension_registry: DimensionRegistry,
@@ -12,7 +12,7 @@ pub struct DimensionCodec {
 
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct DimensionRegistry {
     #[serde(rename = "type")]
     pub dimension_type: String,
@@ -20,7 +20,7 @@ pub struct DimensionRegistry {
 }
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct DimensionRegistryEntry {
     pub name: String,
     pub id: i32,
@@ -28,7 +28,7 @@ pub struct DimensionRegistryEntry {
 }
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct DimensionType {
     pub piglin_safe: bool,
     pub natural: bool,
@@ -48,7 +48,7 @@ pub struct DimensionType {
 
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct BiomeRegistry {
     #[serde(rename = "type")]
     pub biome_type: String,
@@ -56,7 +56,7 @@ pub struct BiomeRegistry {
 }
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct BiomeRegistryEntry {
     pub name: String,
     pub id: i32,
@@ -64,7 +64,7 @@ pub struct BiomeRegistryEntry {
 }
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct BiomeProperties {
     pub precipitation: String,
     pub depth: f32,
@@ -78,7 +78,7 @@ pub struct BiomeProperties {
 }
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct BiomeEffects {
     pub sky_color: i32,
     pub water_fog_color: i32,
@@ -94,7 +94,7 @@ pub struct BiomeEffects {
 }
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct BiomeMusicProperties {
     pub replace_current_music: i8,
     pub sound: String,
@@ -103,14 +103,14 @@ pub struct BiomeMusicProperties {
 }
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct AdditionSoundProperties {
     pub sound: String,
     pub tick_chance: f64,
 }
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct MoodSoundProperties {
     pub sound: String,
     pub tick_delay: i32,
@@ -119,14 +119,14 @@ pub struct MoodSoundProperties {
 }
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct BiomeParticles {
     pub probability: f32,
     pub options: BiomeParticleOptions,
 }
 ///
 #[allow(missing_docs)]
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct BiomeParticleOptions {
     #[serde(rename = "type")]
     pub particle_type: String,
@@ -142,6 +142,19 @@ impl<'a, 'de: 'a> serde::Deserializer<'de> for &'a mut Deserializer {
         visitor.visit_u64(u64::from_be_bytes(buf))
     }
 
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let mut buf = [0; 16];
+
+        for b in buf.iter_mut() {
+            *b = self.get_u8()?;
+        }
+
+        visitor.visit_u128(u128::from_be_bytes(buf))
+    }
+
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
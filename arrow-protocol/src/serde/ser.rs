@@ -95,6 +95,12 @@ impl<'a> serde::Serializer for &'a mut Serializer {
         Ok(())
     }
 
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.output.append(&mut v.to_be_bytes().to_vec());
+
+        Ok(())
+    }
+
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
         self.output.append(&mut v.to_be_bytes().to_vec());
 
@@ -221,7 +227,7 @@ impl<'a> serde::Serializer for &'a mut Serializer {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        todo!()
+        Ok(self)
     }
 
     fn is_human_readable(&self) -> bool {
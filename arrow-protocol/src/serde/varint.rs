@@ -11,8 +11,10 @@ use serde::{
 use super::error::{Result, SerdeError};
 
 /// The representation of a [VarInt](https://wiki.vg/Protocol#VarInt_and_VarLong).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct VarInt(pub i32);
 /// The representation of a [VarLong](https://wiki.vg/Protocol#VarInt_and_VarLong).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct VarLong(pub i64);
 
 struct VarIntVisitor;
@@ -188,7 +190,8 @@ pub fn write_varint<W>(value: i32, mut output: W) -> Result<()>
 where
     W: Write,
 {
-    let mut value = value;
+    // Shifted as unsigned, so negative values end after the sign bit.
+    let mut value = value as u32;
     let mut buf = vec![];
 
     loop {
@@ -212,7 +215,7 @@ where
 }
 
 pub fn varint_len(value: i32) -> usize {
-    let mut value = value;
+    let mut value = value as u32;
     let mut len = 0;
 
     loop {
@@ -279,7 +282,7 @@ pub fn write_varlong<W>(value: i64, mut output: W) -> Result<()>
 where
     W: Write,
 {
-    let mut value = value;
+    let mut value = value as u64;
     let mut buf = vec![];
 
     loop {
@@ -303,7 +306,7 @@ where
 }
 
 pub fn varlong_len(value: i64) -> usize {
-    let mut value = value;
+    let mut value = value as u64;
     let mut len = 0;
 
     loop {
@@ -1,14 +1,41 @@
-use std::{io::ErrorKind, ops::RangeInclusive};
-use tokio::fs::{read_to_string, write};
+use std::{io::ErrorKind, ops::RangeInclusive, path::Path, sync::Arc};
+use tokio::fs::{read, read_to_string, write};
+
+use arrow_net::{
+    version,
+    world::{
+        anvil::AnvilWorld,
+        generator::{FlatGenerator, NoiseGenerator, VoidGenerator, WorldGenerator, DEFAULT_LAYERS},
+    },
+};
+use arrow_protocol::block::{BlockRegistry, BlockState, BlockTable, FLATTENED_PROTOCOL_VERSIONS};
 
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     port: u16,
     host: String,
     motd: String,
+    favicon: String,
+    max_players: i32,
+    compression_threshold: i32,
+    online_mode: bool,
+    legacy_offline_uuids: bool,
+    keep_alive_timeout: u64,
+    operators: Vec<String>,
+    shutdown_message: String,
+    do_daylight_cycle: bool,
+    block_tables: String,
+    fallback_block: String,
+    world: String,
+    generator: String,
+    seed: String,
+    flat_layers: String,
+    void_platform: String,
+    // Tables have to come after all plain values in TOML.
     version_range: RangeInclusive<i32>,
 }
 
@@ -28,10 +55,91 @@ impl Config {
         &self.motd
     }
 
+    /// Get a reference to the config's favicon path.
+    pub fn favicon(&self) -> &String {
+        &self.favicon
+    }
+
+    /// Get a reference to the config's max players.
+    pub fn max_players(&self) -> &i32 {
+        &self.max_players
+    }
+
     /// Get a reference to the config's version range.
     pub fn version_range(&self) -> &RangeInclusive<i32> {
         &self.version_range
     }
+
+    /// Get a reference to the config's compression threshold. A negative value disables
+    /// compression.
+    pub fn compression_threshold(&self) -> &i32 {
+        &self.compression_threshold
+    }
+
+    /// Get a reference to the config's online mode.
+    pub fn online_mode(&self) -> &bool {
+        &self.online_mode
+    }
+
+    /// Get a reference to the config's legacy offline uuids flag.
+    pub fn legacy_offline_uuids(&self) -> &bool {
+        &self.legacy_offline_uuids
+    }
+
+    /// Get a reference to the config's keep alive timeout in seconds.
+    pub fn keep_alive_timeout(&self) -> &u64 {
+        &self.keep_alive_timeout
+    }
+
+    /// Get a reference to the config's operator names.
+    pub fn operators(&self) -> &Vec<String> {
+        &self.operators
+    }
+
+    /// Get a reference to the config's shutdown message.
+    pub fn shutdown_message(&self) -> &String {
+        &self.shutdown_message
+    }
+
+    /// Get a reference to the config's daylight cycle flag.
+    pub fn do_daylight_cycle(&self) -> &bool {
+        &self.do_daylight_cycle
+    }
+
+    /// Get a reference to the config's block table directory.
+    pub fn block_tables(&self) -> &String {
+        &self.block_tables
+    }
+
+    /// Get a reference to the config's fallback block.
+    pub fn fallback_block(&self) -> &String {
+        &self.fallback_block
+    }
+
+    /// Get a reference to the config's world directory. Empty to keep the world in memory.
+    pub fn world(&self) -> &String {
+        &self.world
+    }
+
+    /// Get a reference to the config's world generator name.
+    pub fn generator(&self) -> &String {
+        &self.generator
+    }
+
+    /// Get a reference to the config's world seed. Empty for a random seed.
+    pub fn seed(&self) -> &String {
+        &self.seed
+    }
+
+    /// Get a reference to the config's superflat layers.
+    pub fn flat_layers(&self) -> &String {
+        &self.flat_layers
+    }
+
+    /// Get a reference to the config's void spawn platform block. Empty for no platform.
+    pub fn void_platform(&self) -> &String {
+        &self.void_platform
+    }
 }
 
 impl Default for Config {
@@ -40,7 +148,23 @@ impl Default for Config {
             port: 25565,
             host: "0.0.0.0".to_string(),
             motd: "Arrow - A minecraft server written in Rust".to_string(),
+            favicon: "server-icon.png".to_string(),
+            max_players: 100,
             version_range: 47..=754,
+            compression_threshold: 256,
+            online_mode: false,
+            legacy_offline_uuids: false,
+            keep_alive_timeout: 30,
+            operators: vec![],
+            shutdown_message: "Server closed".to_string(),
+            do_daylight_cycle: true,
+            block_tables: "blocks".to_string(),
+            fallback_block: "minecraft:stone".to_string(),
+            world: String::new(),
+            generator: "flat".to_string(),
+            seed: String::new(),
+            flat_layers: DEFAULT_LAYERS.to_string(),
+            void_platform: "minecraft:stone".to_string(),
         }
     }
 }
@@ -72,3 +196,156 @@ pub async fn load_config() -> Config {
         }
     }
 }
+
+/// Loads the favicon at `path` and encodes it for the status response. Returns `None` if the file
+/// does not exist or is not a 64x64 PNG image.
+pub async fn load_favicon(path: &str) -> Option<String> {
+    let png = match read(path).await {
+        Ok(png) => png,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            info!("No favicon found at {}.", path);
+            return None;
+        }
+        Err(e) => {
+            error!("Failed reading favicon: {}", e);
+            return None;
+        }
+    };
+
+    match arrow_net::settings::favicon_from_png(&png) {
+        Ok(favicon) => Some(favicon),
+        Err(e) => {
+            error!("Failed loading favicon {}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Loads the block state tables in the directory `dir` into a registry using `fallback` for blocks
+/// that don't exist in a protocol version. The tables are named after the last version of a
+/// release, e.g. `1.16.5.json`. Since 1.13 they are the `blocks.json` report of the vanilla data
+/// generator, before that a legacy table. Releases without a table use the built-in one.
+pub async fn load_blocks(dir: &str, fallback: &str) -> BlockRegistry {
+    let mut registry = BlockRegistry::new();
+
+    match fallback.parse() {
+        Ok(fallback) => registry.set_fallback(fallback),
+        Err(e) => error!("Invalid fallback block: {}", e),
+    }
+
+    let mut loaded = 0;
+
+    for (name, versions) in version::releases() {
+        let path = Path::new(dir).join(format!("{}.json", name));
+        let json = match read_to_string(&path).await {
+            Ok(json) => json,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => {
+                error!("Failed reading block table {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        let table = if FLATTENED_PROTOCOL_VERSIONS.contains(versions.start()) {
+            BlockTable::from_report(&json)
+        } else {
+            BlockTable::from_legacy(&json)
+        };
+
+        match table {
+            Ok(table) => {
+                registry.add_table(versions, table);
+                loaded += 1;
+            }
+            Err(e) => error!("Failed parsing block table {}: {}", path.display(), e),
+        }
+    }
+
+    if loaded == 0 {
+        info!(
+            "No block tables found in {}, using the built-in blocks.",
+            dir
+        );
+    } else {
+        info!(
+            "Loaded {} block tables with {} block states.",
+            loaded,
+            registry.len()
+        );
+    }
+
+    registry
+}
+
+/// Opens the vanilla world in the directory `dir`. Returns `None` if `dir` is empty or the world
+/// can't be opened.
+pub fn open_world(dir: &str, blocks: Arc<BlockRegistry>) -> Option<Arc<AnvilWorld>> {
+    if dir.is_empty() {
+        return None;
+    }
+
+    match AnvilWorld::open(dir, blocks) {
+        Ok(world) => {
+            if world.level().is_none() {
+                info!("No level.dat found in {}, creating a new world.", dir);
+            }
+
+            Some(Arc::new(world))
+        }
+        Err(e) => {
+            error!("Failed opening world {}: {}", dir, e);
+            None
+        }
+    }
+}
+
+/// Creates the world generator of the config, which is either `flat`, `void` or `noise`. The
+/// `noise` generator creates the world of `seed`. Falls back to the default superflat world if
+/// the generator is invalid.
+pub fn create_generator(
+    config: &Config,
+    seed: i64,
+    blocks: &BlockRegistry,
+) -> Arc<dyn WorldGenerator> {
+    match config.generator().as_str() {
+        "void" => {
+            let platform = void_platform(config.void_platform(), blocks);
+
+            return Arc::new(VoidGenerator::new(platform));
+        }
+        "noise" => match NoiseGenerator::new(seed, blocks) {
+            Ok(generator) => return Arc::new(generator),
+            Err(e) => error!("{}, using flat instead.", e),
+        },
+        "flat" => {}
+        generator => error!("Unknown world generator {}, using flat instead.", generator),
+    }
+
+    match FlatGenerator::from_layers(config.flat_layers(), blocks) {
+        Ok(generator) => Arc::new(generator),
+        Err(e) => {
+            error!("{}, using the default layers instead.", e);
+            // The default layers only use built-in blocks.
+            Arc::new(FlatGenerator::from_layers(DEFAULT_LAYERS, blocks).unwrap())
+        }
+    }
+}
+
+/// Returns the canonical id of the block of the void spawn platform `platform`, or `None` if it
+/// is empty or invalid.
+fn void_platform(platform: &str, blocks: &BlockRegistry) -> Option<u32> {
+    if platform.is_empty() {
+        return None;
+    }
+
+    let id = platform
+        .parse::<BlockState>()
+        .ok()
+        .and_then(|state| blocks.closest_canonical_id(&state));
+
+    if id.is_none() {
+        error!("Invalid spawn platform block {}, using none.", platform);
+    }
+
+    id
+}
@@ -0,0 +1,159 @@
+use std::thread;
+
+use arrow_net::{
+    command::{self, CommandSource, COMMANDS},
+    server::SERVER,
+};
+use log::error;
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Context, Editor, Helper,
+};
+use tokio::runtime::Handle;
+
+/// The name of the thread reading from stdin.
+pub const THREAD_NAME: &str = "console";
+
+/// The prompt shown in front of the input line.
+const PROMPT: &str = "> ";
+
+/// The console reading commands from stdin. Restores the terminal when dropped.
+pub struct Console {
+    /// The terminal mode before the console was started.
+    #[cfg(unix)]
+    terminal_mode: Option<libc::termios>,
+}
+
+/// Completes commands and player names using the registered commands.
+struct ConsoleHelper {
+    handle: Handle,
+}
+
+/// Starts reading commands from stdin on a separate thread and executes them as the console.
+/// Log messages are printed above the prompt from now on.
+pub fn start() -> Console {
+    let console = Console {
+        #[cfg(unix)]
+        terminal_mode: terminal_mode(),
+    };
+
+    let mut editor = match Editor::<ConsoleHelper, DefaultHistory>::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            error!("Failed setting up the console: {}", e);
+            return console;
+        }
+    };
+    let handle = Handle::current();
+
+    editor.set_helper(Some(ConsoleHelper {
+        handle: handle.clone(),
+    }));
+
+    // Fails if stdin or stdout is not a terminal, in which case there is no prompt to keep intact.
+    if let Ok(printer) = editor.create_external_printer() {
+        crate::log::set_printer(printer);
+    }
+
+    if let Err(e) = thread::Builder::new()
+        .name(THREAD_NAME.to_string())
+        .spawn(move || read_commands(editor, handle))
+    {
+        error!("Failed starting the console: {}", e);
+    }
+
+    console
+}
+
+/// Executes the lines read from stdin until it is closed. Ctrl-C stops the server.
+fn read_commands(mut editor: Editor<ConsoleHelper, DefaultHistory>, handle: Handle) {
+    loop {
+        match editor.readline(PROMPT) {
+            Ok(line) => {
+                let line = line.trim();
+
+                if line.is_empty() {
+                    continue;
+                }
+
+                if let Err(e) = editor.add_history_entry(line) {
+                    error!("Failed adding a command to the console history: {}", e);
+                }
+
+                let command = line.strip_prefix('/').unwrap_or(line);
+
+                handle.block_on(command::execute(CommandSource::Console, command));
+            }
+            Err(ReadlineError::Interrupted) => {
+                SERVER.stop();
+                break;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                error!("Failed reading from the console: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+impl Completer for ConsoleHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let (offset, input) = match line.strip_prefix('/') {
+            Some(input) => (1, input),
+            None => (0, line),
+        };
+
+        let (start, matches) = self.handle.block_on(async {
+            let players = SERVER.player_names();
+
+            COMMANDS.read().await.suggest(input, true, &players)
+        });
+
+        Ok((start + offset, matches))
+    }
+}
+
+impl Hinter for ConsoleHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ConsoleHelper {}
+
+impl Validator for ConsoleHelper {}
+
+impl Helper for ConsoleHelper {}
+
+impl Drop for Console {
+    fn drop(&mut self) {
+        // The console thread may still be waiting for input in raw mode when the server stops.
+        #[cfg(unix)]
+        if let Some(mode) = &self.terminal_mode {
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, mode);
+            }
+        }
+    }
+}
+
+/// Returns the current mode of the terminal connected to stdin, if any.
+#[cfg(unix)]
+fn terminal_mode() -> Option<libc::termios> {
+    unsafe {
+        let mut mode = std::mem::zeroed();
+
+        if libc::tcgetattr(libc::STDIN_FILENO, &mut mode) == 0 {
+            Some(mode)
+        } else {
+            None
+        }
+    }
+}
@@ -1,4 +1,10 @@
+use std::sync::Mutex;
+
 use fern::colors::ColoredLevelConfig;
+use rustyline::ExternalPrinter;
+
+/// Prints the log messages above the console prompt once the console is started.
+static PRINTER: Mutex<Option<Box<dyn ExternalPrinter + Send>>> = Mutex::new(None);
 
 pub fn init_logger() -> Result<(), fern::InitError> {
     let color = ColoredLevelConfig::new();
@@ -9,6 +15,7 @@ pub fn init_logger() -> Result<(), fern::InitError> {
         } else {
             log::LevelFilter::Info
         })
+        .level_for("rustyline", log::LevelFilter::Warn)
         .chain(
             fern::Dispatch::new()
                 .format(move |out, message, record| {
@@ -20,7 +27,7 @@ pub fn init_logger() -> Result<(), fern::InitError> {
                         message
                     ))
                 })
-                .chain(std::io::stderr()),
+                .chain(fern::Output::call(print)),
         )
         .chain(
             fern::Dispatch::new()
@@ -39,3 +46,31 @@ pub fn init_logger() -> Result<(), fern::InitError> {
 
     Ok(())
 }
+
+/// Prints all following log messages using `printer` instead of writing them to stderr.
+pub fn set_printer<P: ExternalPrinter + Send + 'static>(printer: P) {
+    if let Ok(mut p) = PRINTER.lock() {
+        *p = Some(Box::new(printer));
+    }
+}
+
+/// Prints a formatted log message to the console or stderr.
+fn print(record: &log::Record) {
+    // The console thread drains the printer itself, so waiting for it there would never return.
+    let on_console = std::thread::current().name() == Some(crate::console::THREAD_NAME);
+
+    if on_console {
+        eprintln!("{}", record.args());
+        return;
+    }
+
+    if let Ok(mut printer) = PRINTER.lock() {
+        if let Some(printer) = printer.as_mut() {
+            if printer.print(format!("{}\n", record.args())).is_ok() {
+                return;
+            }
+        }
+    }
+
+    eprintln!("{}", record.args());
+}
@@ -1,6 +1,18 @@
 mod config;
+mod console;
 mod log;
 
+use std::{sync::Arc, time::Duration};
+
+use arrow_net::{
+    auth::{Authenticator, MojangSessionVerifier},
+    server::SERVER,
+    settings::Settings,
+    version::SUPPORTED_PROTOCOL_VERSIONS,
+    world::seed,
+};
+use arrow_protocol::chat::ChatComponent;
+
 #[tokio::main]
 async fn main() {
     if let Err(e) = log::init_logger() {
@@ -9,7 +21,103 @@ async fn main() {
 
     let config = config::load_config().await;
 
-    arrow_net::start_server(config.host(), *config.port())
+    let authenticator = if *config.online_mode() {
+        match Authenticator::new(Box::new(MojangSessionVerifier::new())) {
+            Ok(a) => Some(Arc::new(a)),
+            Err(e) => panic!("Failed setting up online mode: {}", e),
+        }
+    } else {
+        None
+    };
+
+    let version_range = (*config.version_range().start()).max(*SUPPORTED_PROTOCOL_VERSIONS.start())
+        ..=(*config.version_range().end()).min(*SUPPORTED_PROTOCOL_VERSIONS.end());
+
+    if &version_range != config.version_range() {
+        ::log::warn!(
+            "Configured version range {:?} is not supported, using {:?} instead.",
+            config.version_range(),
+            version_range
+        );
+    }
+
+    let favicon = config::load_favicon(config.favicon()).await;
+    let blocks =
+        Arc::new(config::load_blocks(config.block_tables(), config.fallback_block()).await);
+    let storage = config::open_world(config.world(), blocks.clone());
+    let level = storage.as_ref().and_then(|storage| storage.level());
+
+    // An existing world keeps its seed and game rules.
+    let seed = match level.as_ref().and_then(|level| level.seed()) {
+        Some(seed) => seed,
+        None => seed::parse_seed(config.seed()),
+    };
+    let do_daylight_cycle = match level
+        .as_ref()
+        .and_then(|level| level.game_rule("doDaylightCycle"))
+    {
+        Some(rule) => rule == "true",
+        None => *config.do_daylight_cycle(),
+    };
+    let generator = config::create_generator(&config, seed, &blocks);
+
+    let settings = Settings {
+        motd: ChatComponent::from_legacy(config.motd()),
+        favicon,
+        max_players: *config.max_players(),
+        compression_threshold: *config.compression_threshold(),
+        authenticator,
+        legacy_offline_uuids: *config.legacy_offline_uuids(),
+        keep_alive_timeout: Duration::from_secs(*config.keep_alive_timeout()),
+        version_range,
+        operators: config.operators().clone(),
+        shutdown_message: ChatComponent::from_legacy(config.shutdown_message()),
+        do_daylight_cycle,
+        blocks,
+        generator,
+        seed,
+        storage,
+    };
+
+    let _console = console::start();
+
+    tokio::spawn(stop_on_signal());
+
+    arrow_net::start_server(config.host(), *config.port(), settings)
         .await
         .unwrap();
 }
+
+/// Stops the server once the process receives SIGINT or SIGTERM.
+async fn stop_on_signal() {
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(e) => {
+                ::log::error!("Failed listening for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    let interrupt = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            ::log::error!("Failed listening for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    tokio::select! {
+        _ = interrupt => {}
+        _ = terminate => {}
+    }
+
+    SERVER.stop();
+}